@@ -48,12 +48,22 @@ extern "C" {
 #define PC_STATUS_AUCTION     3
 #define PC_STATUS_IGNORED     4
 
+// corporate action status, a combination of the following flags
+#define PC_CORP_ACT_NONE          0
+#define PC_CORP_ACT_SPLIT         1
+#define PC_CORP_ACT_DIVIDEND      2
+#define PC_CORP_ACT_SYMBOL_CHANGE 4
+#define PC_CORP_ACT_ALL           7
+#define PC_CORP_ACT_NUM_FLAGS     3
+
 // account types
 #define PC_ACCTYPE_MAPPING    1
 #define PC_ACCTYPE_PRODUCT    2
 #define PC_ACCTYPE_PRICE      3
 #define PC_ACCTYPE_TEST       4
 #define PC_ACCTYPE_PERMISSIONS       5
+#define PC_ACCTYPE_DELEGATION        6
+#define PC_ACCTYPE_SCHEDULE          7
 
 
 // Compute budget requested per price update instruction
@@ -423,7 +433,7 @@ typedef struct cmd_upd_price
   uint32_t     ver_;
   int32_t      cmd_;
   uint32_t     status_;
-  uint32_t     unused_;
+  uint32_t     corp_act_status_;
   int64_t      price_;
   uint64_t     conf_;
   uint64_t     pub_slot_;
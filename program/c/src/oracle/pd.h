@@ -59,7 +59,7 @@ static inline bool pd_store( int64_t *r, pd_t const *n )
   return true;
 }
 
-void pd_load( pd_t *r, int64_t const n )
+static inline void pd_load( pd_t *r, int64_t const n )
 {
   pd_new( r, n >> EXP_BITS, ( ( n & EXP_MASK ) << 59 ) >> 59 );
   pd_scale( r );
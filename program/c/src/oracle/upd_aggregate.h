@@ -151,9 +151,11 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
   // compute the aggregate prices and ranges
   int64_t  agg_price;
   int64_t  agg_conf;
+  uint32_t agg_corp_act = PC_CORP_ACT_NONE;
   {
     uint32_t numv  = 0;
     uint32_t nprcs = (uint32_t)0;
+    uint32_t ncorp[ PC_CORP_ACT_NUM_FLAGS ] = { 0 };
     int64_t  prcs[ PC_NUM_COMP * 3 ]; // ~0.75KiB for current PC_NUM_COMP (FIXME: DOUBLE CHECK THIS FITS INTO STACK FRAME LIMIT)
     for ( uint32_t i = 0; i != ptr->num_; ++i ) {
       pc_price_comp_t *iptr = &ptr->comp_[i];
@@ -174,6 +176,9 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
         prcs[ nprcs++ ] = price - conf;
         prcs[ nprcs++ ] = price;
         prcs[ nprcs++ ] = price + conf;
+        for ( uint32_t j = 0; j != PC_CORP_ACT_NUM_FLAGS; ++j ) {
+          ncorp[ j ] += ( iptr->agg_.corp_act_status_ >> j ) & 1U;
+        }
       }
     }
 
@@ -181,6 +186,7 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
     ptr->num_qt_ = numv;
     if ( numv == 0 || numv < ptr->min_pub_ ) {
       ptr->agg_.status_ = PC_STATUS_UNKNOWN;
+      ptr->agg_.corp_act_status_ = PC_CORP_ACT_NONE;
       return false;
     }
 
@@ -206,8 +212,17 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
     // positive confidences given the current pricing model
     if( agg_conf <= (int64_t)0 ) {
       ptr->agg_.status_ = PC_STATUS_UNKNOWN;
+      ptr->agg_.corp_act_status_ = PC_CORP_ACT_NONE;
       return false;
     }
+
+    // a corporate action is part of the aggregate if a strict majority of the valid quotes
+    // report it
+    for ( uint32_t j = 0; j != PC_CORP_ACT_NUM_FLAGS; ++j ) {
+      if ( 2 * ncorp[ j ] > numv ) {
+        agg_corp_act |= 1U << j;
+      }
+    }
   }
 
   // update status and publish slot of last trading status price
@@ -215,6 +230,7 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
   ptr->last_slot_   = slot;
   ptr->agg_.price_  = agg_price;
   ptr->agg_.conf_   = (uint64_t)agg_conf;
+  ptr->agg_.corp_act_status_ = agg_corp_act;
 
   return true;
 }
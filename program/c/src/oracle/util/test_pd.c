@@ -0,0 +1,61 @@
+// Exposes the decimal arithmetic in pd.h to the rust tests, which compare it against the rust
+// implementation of the same operations.
+#include <stdbool.h>
+#include <stdint.h>
+#include "../pd.h"
+
+static const int64_t pd_factors[PC_FACTOR_SIZE] = {
+  1L,
+  10L,
+  100L,
+  1000L,
+  10000L,
+  100000L,
+  1000000L,
+  10000000L,
+  100000000L,
+  1000000000L,
+  10000000000L,
+  100000000000L,
+  1000000000000L,
+  10000000000000L,
+  100000000000000L,
+  1000000000000000L,
+  10000000000000000L,
+  100000000000000000L
+};
+
+void test_pd_scale( pd_t *r )
+{
+  pd_scale( r );
+}
+
+void test_pd_mul( pd_t *r, const pd_t *n1, const pd_t *n2 )
+{
+  pd_mul( r, n1, n2 );
+}
+
+void test_pd_div( pd_t *r, pd_t *n1, pd_t *n2 )
+{
+  pd_div( r, n1, n2 );
+}
+
+void test_pd_add( pd_t *r, const pd_t *n1, const pd_t *n2 )
+{
+  pd_add( r, n1, n2, pd_factors );
+}
+
+void test_pd_sub( pd_t *r, const pd_t *n1, const pd_t *n2 )
+{
+  pd_sub( r, n1, n2, pd_factors );
+}
+
+int test_pd_lt( const pd_t *n1, const pd_t *n2 )
+{
+  return pd_lt( n1, n2, pd_factors );
+}
+
+int test_pd_gt( const pd_t *n1, const pd_t *n2 )
+{
+  return pd_gt( n1, n2, pd_factors );
+}
@@ -13,6 +13,7 @@ fn main() {
     let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
 
     let has_feat_check = std::env::var("CARGO_FEATURE_CHECK").is_ok();
+    let has_feat_rust_aggregation = std::env::var("CARGO_FEATURE_RUST_AGGREGATION").is_ok();
 
     // OUT_DIR is the path cargo provides to a build directory under `target/` specifically for
     // isolated build artifacts. We use this to build the C program and then link against the
@@ -38,6 +39,10 @@ fn main() {
     // Solana in its GitHub Action.
     if has_feat_check {
         eprintln!("WARNING: `check` feature active, make build is skipped");
+    } else if has_feat_rust_aggregation {
+        // The aggregation runs in Rust and the constants of the C headers are checked in, so
+        // neither the C toolchain nor bindgen is needed.
+        eprintln!("`rust-aggregation` feature active, make build and bindgen are skipped");
     } else {
         do_make_build(make_targets, &out_dir);
 
@@ -52,24 +57,26 @@ fn main() {
         println!("cargo:rustc-link-search={}", out_dir.display());
     }
 
-    std::fs::create_dir("./codegen").unwrap_or_else(|e| {
-        eprintln!(
-            "Could not create codegen directory (may exist which is fine), error: {}",
-            e
-        );
-    });
-
-    // Generate and write bindings
-    let bindings = Builder::default()
-        .clang_arg(format!("-I{:}", get_solana_inc_path().display()))
-        .header("./src/bindings.h")
-        .rustfmt_bindings(true)
-        .generate()
-        .expect("Unable to generate bindings");
-
-    bindings
-        .write_to_file("./codegen/bindings.rs")
-        .expect("Couldn't write bindings!");
+    if !has_feat_rust_aggregation {
+        std::fs::create_dir("./codegen").unwrap_or_else(|e| {
+            eprintln!(
+                "Could not create codegen directory (may exist which is fine), error: {}",
+                e
+            );
+        });
+
+        // Generate and write bindings
+        let bindings = Builder::default()
+            .clang_arg(format!("-I{:}", get_solana_inc_path().display()))
+            .header("./src/bindings.h")
+            .rustfmt_bindings(true)
+            .generate()
+            .expect("Unable to generate bindings");
+
+        bindings
+            .write_to_file("./codegen/bindings.rs")
+            .expect("Couldn't write bindings!");
+    }
 
     // Rerun the build script if either the rust or C code changes
     println!("cargo:rerun-if-changed=../");
@@ -0,0 +1,299 @@
+//! Walks the linked lists of oracle accounts: mapping accounts point to product accounts, which
+//! point to their first price account, which point to the next price account of the same
+//! product. Given a way to fetch account data, `walk_account_graph` follows these links starting
+//! from the first mapping account and returns every reachable account as a tree, along with
+//! diagnostics for anything that doesn't look right.
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            MappingAccount,
+            PriceAccount,
+            ProductAccount,
+            ProductMetadata,
+            ProductMetadataError,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_MAP_TABLE_SIZE,
+            PC_VERSION,
+        },
+    },
+    bytemuck::{
+        pod_read_unaligned,
+        Pod,
+    },
+    solana_program::pubkey::Pubkey,
+    std::{
+        collections::{
+            BTreeMap,
+            HashMap,
+            HashSet,
+        },
+        mem::size_of,
+    },
+};
+
+/// Offset of `MappingAccount::products_list`, which is the last field of the account.
+const PRODUCTS_LIST_OFFSET: usize =
+    size_of::<MappingAccount>() - PC_MAP_TABLE_SIZE as usize * size_of::<Pubkey>();
+
+/// Source of account data for `walk_account_graph`.
+pub trait AccountLookup {
+    fn get_account_data(&mut self, key: &Pubkey) -> Option<Vec<u8>>;
+}
+
+impl<F: FnMut(&Pubkey) -> Option<Vec<u8>>> AccountLookup for F {
+    fn get_account_data(&mut self, key: &Pubkey) -> Option<Vec<u8>> {
+        self(key)
+    }
+}
+
+impl AccountLookup for &HashMap<Pubkey, Vec<u8>> {
+    fn get_account_data(&mut self, key: &Pubkey) -> Option<Vec<u8>> {
+        self.get(key).cloned()
+    }
+}
+
+impl AccountLookup for &BTreeMap<Pubkey, Vec<u8>> {
+    fn get_account_data(&mut self, key: &Pubkey) -> Option<Vec<u8>> {
+        self.get(key).cloned()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// `key` is linked from `referenced_by` but the lookup doesn't know about it.
+    MissingAccount {
+        key:           Pubkey,
+        referenced_by: Pubkey,
+    },
+    /// `key` was expected to be an account of type `expected_account_type` but its data is too
+    /// small or its header has the wrong magic number, version or account type.
+    InvalidAccountHeader {
+        key:                   Pubkey,
+        expected_account_type: u32,
+    },
+    /// `key` was reached a second time through `referenced_by`. This happens when the links form
+    /// a cycle or when the same account is linked from two places. The account is only walked
+    /// the first time.
+    Cycle {
+        key:           Pubkey,
+        referenced_by: Pubkey,
+    },
+    /// The mapping account claims to hold more products than it has room for. Only the first
+    /// `PC_MAP_TABLE_SIZE` products are walked.
+    InvalidNumberOfProducts {
+        key:                Pubkey,
+        number_of_products: u32,
+    },
+    /// The metadata of the product account couldn't be parsed.
+    InvalidProductMetadata {
+        key:   Pubkey,
+        error: ProductMetadataError,
+    },
+    /// The price account is in the price list of `product_account` but its own `product_account`
+    /// field points somewhere else.
+    ProductMismatch {
+        key:                  Pubkey,
+        product_account:      Pubkey,
+        back_pointer_product: Pubkey,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AccountGraph {
+    /// Mapping accounts in the order of the `next_mapping_account` list
+    pub mappings:    Vec<MappingNode>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MappingNode {
+    pub key:      Pubkey,
+    pub products: Vec<ProductNode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProductNode {
+    pub key:      Pubkey,
+    pub metadata: ProductMetadata,
+    /// Price accounts in the order of the `next_price_account` list
+    pub prices:   Vec<PriceNode>,
+}
+
+#[derive(Clone)]
+pub struct PriceNode {
+    pub key:           Pubkey,
+    pub price_account: Box<PriceAccount>,
+}
+
+impl std::fmt::Debug for PriceNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriceNode").field("key", &self.key).finish()
+    }
+}
+
+impl AccountGraph {
+    /// Iterates over every product account along with its price accounts.
+    pub fn products(&self) -> impl Iterator<Item = &ProductNode> {
+        self.mappings.iter().flat_map(|x| x.products.iter())
+    }
+
+    /// Iterates over every price account along with the product account that links to it.
+    pub fn prices(&self) -> impl Iterator<Item = (&ProductNode, &PriceNode)> {
+        self.products()
+            .flat_map(|product| product.prices.iter().map(move |price| (product, price)))
+    }
+}
+
+/// Follows the links starting from `first_mapping_account` and returns all the accounts that can be
+/// reached. This never fails: problems are reported in `AccountGraph::diagnostics` and the walk
+/// continues with the accounts that can still be read.
+pub fn walk_account_graph<L: AccountLookup>(
+    first_mapping_account: &Pubkey,
+    mut lookup: L,
+) -> AccountGraph {
+    let mut walker = Walker {
+        lookup:  &mut lookup,
+        visited: HashSet::new(),
+        graph:   AccountGraph::default(),
+    };
+
+    let mut next_mapping = *first_mapping_account;
+    let mut referenced_by = Pubkey::default();
+    while next_mapping != Pubkey::default() {
+        let data = match walker.fetch::<MappingAccount>(&next_mapping, &referenced_by) {
+            Some(data) => data,
+            None => break,
+        };
+        let (node, next) = walker.walk_mapping(next_mapping, &data);
+        walker.graph.mappings.push(node);
+        referenced_by = next_mapping;
+        next_mapping = next;
+    }
+
+    walker.graph
+}
+
+struct Walker<'a, L: AccountLookup> {
+    lookup:  &'a mut L,
+    visited: HashSet<Pubkey>,
+    graph:   AccountGraph,
+}
+
+impl<L: AccountLookup> Walker<'_, L> {
+    /// Returns the data of `key` if it's a valid account of type `T` that hasn't been visited yet.
+    fn fetch<T: PythAccount>(&mut self, key: &Pubkey, referenced_by: &Pubkey) -> Option<Vec<u8>> {
+        if !self.visited.insert(*key) {
+            self.graph.diagnostics.push(Diagnostic::Cycle {
+                key:           *key,
+                referenced_by: *referenced_by,
+            });
+            return None;
+        }
+
+        let data = match self.lookup.get_account_data(key) {
+            Some(data) => data,
+            None => {
+                self.graph.diagnostics.push(Diagnostic::MissingAccount {
+                    key:           *key,
+                    referenced_by: *referenced_by,
+                });
+                return None;
+            }
+        };
+
+        let header_is_valid = data.len() >= T::MINIMUM_SIZE && {
+            let header: AccountHeader = read(&data, 0);
+            header.magic_number == PC_MAGIC
+                && header.version == PC_VERSION
+                && header.account_type == T::ACCOUNT_TYPE
+        };
+        if !header_is_valid {
+            self.graph
+                .diagnostics
+                .push(Diagnostic::InvalidAccountHeader {
+                    key:                   *key,
+                    expected_account_type: T::ACCOUNT_TYPE,
+                });
+            return None;
+        }
+
+        Some(data)
+    }
+
+    /// Walks the products of a mapping account and returns the next mapping account.
+    fn walk_mapping(&mut self, key: Pubkey, data: &[u8]) -> (MappingNode, Pubkey) {
+        // The mapping account is too large to copy around, only read the fields we need.
+        let number_of_products: u32 = read(data, size_of::<AccountHeader>());
+        let next_mapping_account: Pubkey =
+            read(data, size_of::<AccountHeader>() + 2 * size_of::<u32>());
+        if number_of_products > PC_MAP_TABLE_SIZE {
+            self.graph
+                .diagnostics
+                .push(Diagnostic::InvalidNumberOfProducts {
+                    key,
+                    number_of_products,
+                });
+        }
+
+        let mut products = vec![];
+        for i in 0..number_of_products.min(PC_MAP_TABLE_SIZE) as usize {
+            let offset = PRODUCTS_LIST_OFFSET + i * size_of::<Pubkey>();
+            let product_key: Pubkey = read(data, offset);
+            if let Some(data) = self.fetch::<ProductAccount>(&product_key, &key) {
+                products.push(self.walk_product(product_key, &data));
+            }
+        }
+
+        (MappingNode { key, products }, next_mapping_account)
+    }
+
+    fn walk_product(&mut self, key: Pubkey, data: &[u8]) -> ProductNode {
+        let metadata = ProductMetadata::from_account_data(data).unwrap_or_else(|error| {
+            self.graph
+                .diagnostics
+                .push(Diagnostic::InvalidProductMetadata { key, error });
+            ProductMetadata::new()
+        });
+
+        let mut prices = vec![];
+        let product_account: ProductAccount = read(data, 0);
+        let mut next_price = product_account.first_price_account;
+        let mut referenced_by = key;
+        while next_price != Pubkey::default() {
+            let data = match self.fetch::<PriceAccount>(&next_price, &referenced_by) {
+                Some(data) => data,
+                None => break,
+            };
+            let price_account: Box<PriceAccount> = Box::new(read(&data, 0));
+            if price_account.product_account != key {
+                self.graph.diagnostics.push(Diagnostic::ProductMismatch {
+                    key:                  next_price,
+                    product_account:      key,
+                    back_pointer_product: price_account.product_account,
+                });
+            }
+            referenced_by = next_price;
+            next_price = price_account.next_price_account;
+            prices.push(PriceNode {
+                key: referenced_by,
+                price_account,
+            });
+        }
+
+        ProductNode {
+            key,
+            metadata,
+            prices,
+        }
+    }
+}
+
+/// Reads a `T` at `offset` in `data`. Account data fetched from RPC isn't necessarily aligned so
+/// this makes a copy.
+fn read<T: Pod>(data: &[u8], offset: usize) -> T {
+    pod_read_unaligned(&data[offset..offset + size_of::<T>()])
+}
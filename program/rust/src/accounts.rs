@@ -18,10 +18,18 @@ use {
     },
     solana_program::{
         account_info::AccountInfo,
-        program::invoke_signed,
+        program::{
+            invoke,
+            invoke_signed,
+        },
         program_error::ProgramError,
         pubkey::Pubkey,
-        system_instruction::create_account,
+        system_instruction::{
+            allocate,
+            assign,
+            create_account,
+            transfer,
+        },
     },
     std::{
         cell::RefMut,
@@ -34,25 +42,58 @@ use {
     std::borrow::BorrowMut,
 };
 
+#[cfg(any(test, feature = "library"))]
+mod checked_price;
+mod delegation;
 mod mapping;
 mod permission;
 mod price;
+#[cfg(any(test, feature = "library"))]
+mod price_math;
 mod product;
+#[cfg(any(test, feature = "library", feature = "serde"))]
+mod product_metadata;
+mod schedule;
+#[cfg(feature = "serde")]
+mod serde_helpers;
 
 // Some types only exist during use as a library.
+#[cfg(any(test, feature = "library"))]
+pub use checked_price::{
+    CheckedPrice,
+    PriceGuardError,
+    BPS_DENOMINATOR,
+};
 #[cfg(feature = "strum")]
 pub use price::MessageType;
-#[cfg(test)]
+#[cfg(any(test, feature = "library"))]
 pub use price::PriceCumulative;
+#[cfg(any(test, feature = "library"))]
+pub use price_math::Price;
 #[cfg(test)]
 pub use product::{
     account_has_key_values,
     create_pc_str_t,
 };
+#[cfg(any(test, feature = "library", feature = "serde"))]
+pub use product_metadata::{
+    ProductAccountData,
+    ProductMetadata,
+    ProductMetadataError,
+    MAX_PC_STR_LEN,
+    MAX_PRODUCT_METADATA_LEN,
+};
 pub use {
+    delegation::{
+        Delegate,
+        DelegationAccount,
+        MAX_DELEGATES,
+    },
     mapping::MappingAccount,
     permission::PermissionAccount,
     price::{
+        AggregationConfig,
+        AggregationMode,
         PriceAccount,
         PriceAccountFlags,
         PriceComponent,
@@ -65,6 +106,16 @@ pub use {
         update_product_metadata,
         ProductAccount,
     },
+    schedule::{
+        Holiday,
+        ScheduleAccount,
+        TradingSession,
+        MAX_HOLIDAYS,
+        MAX_SESSIONS,
+        MAX_UTC_OFFSET,
+        MINUTES_PER_DAY,
+        MINUTES_PER_WEEK,
+    },
 };
 
 // PDA seeds for accounts.
@@ -77,8 +128,15 @@ pub const PERMISSIONS_SEED: &str = "permissions";
 /// such that the caller can authenticate its origin.
 pub const UPD_PRICE_WRITE_SEED: &str = "upd_price_write";
 
+/// The delegation account of a publisher is the PDA `[DELEGATION_SEED, publisher_public_key]`.
+pub const DELEGATION_SEED: &str = "delegation";
+
+/// The schedule account of a price account is the PDA `[SCHEDULE_SEED, price_account_public_key]`.
+pub const SCHEDULE_SEED: &str = "schedule";
+
 #[repr(C)]
 #[derive(Copy, Clone, Zeroable, Pod)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountHeader {
     pub magic_number: u32,
     pub version:      u32,
@@ -135,8 +193,7 @@ pub trait PythAccount: Pod {
     }
 
     /// Creates PDA accounts only when needed, and initializes it as one of the Pyth accounts.
-    /// This PDA initialization assumes that the account has 0 lamports.
-    /// TO DO: Fix this once we can resize the program.
+    /// The PDA may already hold lamports, since anyone can transfer to its address.
     fn initialize_pda<'a>(
         account: &AccountInfo<'a>,
         funding_account: &AccountInfo<'a>,
@@ -164,6 +221,7 @@ pub trait PythAccount: Pod {
     }
 }
 
+/// Create the PDA `to` with `space` bytes owned by `owner`, funded by `from` up to `lamports`.
 fn create<'a>(
     from: &AccountInfo<'a>,
     to: &AccountInfo<'a>,
@@ -173,10 +231,35 @@ fn create<'a>(
     lamports: u64,
     seeds: &[&[u8]],
 ) -> Result<(), ProgramError> {
-    let create_instruction = create_account(from.key, to.key, lamports, try_convert(space)?, owner);
+    if to.lamports() == 0 {
+        let create_instruction =
+            create_account(from.key, to.key, lamports, try_convert(space)?, owner);
+        invoke_signed(
+            &create_instruction,
+            &[from.clone(), to.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+        return Ok(());
+    }
+
+    // `create_account` fails if the account already holds lamports, so only top it up and
+    // allocate and assign it instead. Otherwise, anyone could block the creation of a PDA by
+    // transferring lamports to its address.
+    let missing_lamports = lamports.saturating_sub(to.lamports());
+    if missing_lamports > 0 {
+        invoke(
+            &transfer(from.key, to.key, missing_lamports),
+            &[from.clone(), to.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &allocate(to.key, try_convert(space)?),
+        &[to.clone(), system_program.clone()],
+        &[seeds],
+    )?;
     invoke_signed(
-        &create_instruction,
-        &[from.clone(), to.clone(), system_program.clone()],
+        &assign(to.key, owner),
+        &[to.clone(), system_program.clone()],
         &[seeds],
     )?;
     Ok(())
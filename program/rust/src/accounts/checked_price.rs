@@ -0,0 +1,150 @@
+//! Safe reads of the price stored in a `PriceAccount` for programs that consume Pyth prices.
+//!
+//! The aggregate in `agg_` is only meaningful while its status is `PC_STATUS_TRADING`. Otherwise
+//! the latest usable price is the one from the last successful aggregation (`prev_price_`,
+//! `prev_conf_` and `prev_timestamp_`), the same fallback `as_price_feed_message` applies. The
+//! functions below apply this fallback and then check that the result is recent enough.
+use {
+    super::PriceAccount,
+    crate::c_oracle_header::PC_STATUS_TRADING,
+    solana_program::clock::Clock,
+};
+
+/// Basis points in a ratio of 1, used to express the maximum confidence to price ratio.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// A price that passed all the requested checks. The price is `price * 10^exponent` with a
+/// confidence interval of `conf * 10^exponent`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckedPrice {
+    pub price:        i64,
+    pub conf:         u64,
+    pub exponent:     i32,
+    /// Unix timestamp of the aggregation that produced this price
+    pub publish_time: i64,
+}
+
+/// The check that rejected a price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PriceGuardError {
+    /// The latest price is older than the maximum age.
+    #[error("Stale")]
+    Stale {
+        publish_time: i64,
+        current_time: i64,
+    },
+    /// The latest aggregation failed and the last successful one is older than the maximum age.
+    #[error("NotTrading")]
+    NotTrading { status: u32 },
+    /// The latest aggregation failed because there weren't enough valid quotes and the last
+    /// successful one is older than the maximum age.
+    #[error("TooFewPublishers")]
+    TooFewPublishers { num_qt: u32, min_pub: u8 },
+    /// The confidence interval is too wide compared to the price.
+    #[error("TooUncertain")]
+    TooUncertain { price: i64, conf: u64 },
+}
+
+impl CheckedPrice {
+    /// Checks that `conf / |price|` is at most `max_conf_ratio_bps / BPS_DENOMINATOR`.
+    pub fn check_conf_ratio(self, max_conf_ratio_bps: u64) -> Result<Self, PriceGuardError> {
+        let max_conf = u128::from(self.price.unsigned_abs()) * u128::from(max_conf_ratio_bps);
+        if u128::from(self.conf) * u128::from(BPS_DENOMINATOR) > max_conf {
+            return Err(PriceGuardError::TooUncertain {
+                price: self.price,
+                conf:  self.conf,
+            });
+        }
+        Ok(self)
+    }
+}
+
+impl PriceAccount {
+    /// Returns the latest aggregate price if it was published at most `max_age` seconds before
+    /// `clock.unix_timestamp`. When the current aggregate isn't trading, the price of the last
+    /// successful aggregation is used instead.
+    pub fn get_price_no_older_than(
+        &self,
+        clock: &Clock,
+        max_age: u64,
+    ) -> Result<CheckedPrice, PriceGuardError> {
+        let price = if self.agg_.status_ == PC_STATUS_TRADING {
+            CheckedPrice {
+                price:        self.agg_.price_,
+                conf:         self.agg_.conf_,
+                exponent:     self.exponent,
+                publish_time: self.timestamp_,
+            }
+        } else {
+            CheckedPrice {
+                price:        self.prev_price_,
+                conf:         self.prev_conf_,
+                exponent:     self.exponent,
+                publish_time: self.prev_timestamp_,
+            }
+        };
+        self.check_age(price, clock, max_age)
+    }
+
+    /// Like `get_price_no_older_than` but returns the exponentially-weighted moving average of the
+    /// price and confidence.
+    pub fn get_ema_price_no_older_than(
+        &self,
+        clock: &Clock,
+        max_age: u64,
+    ) -> Result<CheckedPrice, PriceGuardError> {
+        let publish_time = if self.agg_.status_ == PC_STATUS_TRADING {
+            self.timestamp_
+        } else {
+            self.prev_timestamp_
+        };
+        let price = CheckedPrice {
+            price: self.twap_.val_,
+            conf: self.twac_.val_ as u64,
+            exponent: self.exponent,
+            publish_time,
+        };
+        self.check_age(price, clock, max_age)
+    }
+
+    /// Like `get_price_no_older_than` but also rejects prices whose confidence interval is
+    /// larger than `max_conf_ratio_bps` basis points of the price.
+    pub fn get_price_no_older_than_with_max_conf_ratio(
+        &self,
+        clock: &Clock,
+        max_age: u64,
+        max_conf_ratio_bps: u64,
+    ) -> Result<CheckedPrice, PriceGuardError> {
+        self.get_price_no_older_than(clock, max_age)?
+            .check_conf_ratio(max_conf_ratio_bps)
+    }
+
+    fn check_age(
+        &self,
+        price: CheckedPrice,
+        clock: &Clock,
+        max_age: u64,
+    ) -> Result<CheckedPrice, PriceGuardError> {
+        let age = i128::from(clock.unix_timestamp) - i128::from(price.publish_time);
+        if age <= i128::from(max_age) {
+            return Ok(price);
+        }
+
+        // The price is stale, report why the current aggregate couldn't be used.
+        if self.agg_.status_ == PC_STATUS_TRADING {
+            Err(PriceGuardError::Stale {
+                publish_time: price.publish_time,
+                current_time: clock.unix_timestamp,
+            })
+        } else if self.num_qt_ == 0 || self.num_qt_ < u32::from(self.min_pub_) {
+            Err(PriceGuardError::TooFewPublishers {
+                num_qt:  self.num_qt_,
+                min_pub: self.min_pub_,
+            })
+        } else {
+            Err(PriceGuardError::NotTrading {
+                status: self.agg_.status_,
+            })
+        }
+    }
+}
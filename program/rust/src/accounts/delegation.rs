@@ -0,0 +1,75 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_DELEGATION,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Maximum number of delegates of a publisher
+pub const MAX_DELEGATES: usize = 8;
+
+/// A key allowed to publish prices on behalf of a publisher
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delegate {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
+    pub key:         Pubkey,
+    /// Last slot at which the delegate can publish, 0 if the delegation doesn't expire
+    pub expiry_slot: u64,
+}
+
+impl Delegate {
+    pub fn is_active(&self, slot: u64) -> bool {
+        self.expiry_slot == 0 || slot <= self.expiry_slot
+    }
+}
+
+/// This account lists the keys that can sign `UpdPrice` instructions on behalf of a publisher, so
+/// that the publisher's own key can be kept offline. There is one delegation account per
+/// publisher, at the PDA `[DELEGATION_SEED, publisher]`. Only the publisher can update it.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DelegationAccount {
+    /// pyth account header
+    pub header:        AccountHeader,
+    /// The publisher whose prices the delegates publish
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
+    pub publisher:     Pubkey,
+    /// Number of entries of `delegates` in use
+    pub num_delegates: u32,
+    pub unused_:       u32,
+    pub delegates:     [Delegate; MAX_DELEGATES],
+}
+
+impl DelegationAccount {
+    pub fn delegates(&self) -> &[Delegate] {
+        &self.delegates[..(self.num_delegates as usize).min(MAX_DELEGATES)]
+    }
+
+    /// Whether `key` can publish on behalf of the publisher at `slot`.
+    pub fn is_active_delegate(&self, key: &Pubkey, slot: u64) -> bool {
+        self.delegates()
+            .iter()
+            .any(|delegate| delegate.key == *key && delegate.is_active(slot))
+    }
+}
+
+impl PythAccount for DelegationAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_DELEGATION;
+    const INITIAL_SIZE: u32 = size_of::<DelegationAccount>() as u32;
+}
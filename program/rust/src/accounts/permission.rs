@@ -26,10 +26,15 @@ use {
 /// program. Only the upgrade authority of the program can update these permissions.
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PermissionAccount {
     /// pyth account header
     pub header:                  AccountHeader,
     /// An authority that can do any administrative task
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
     pub master_authority:        Pubkey,
     /// An authority that can  :
     /// - Add mapping accounts
@@ -38,23 +43,61 @@ pub struct PermissionAccount {
     /// - Delete price accounts
     /// - Delete product accounts
     /// - Update product accounts
+    /// - Reinitialize price accounts
+    /// - Set the trading schedules of price accounts
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
     pub data_curation_authority: Pubkey,
     /// An authority that can  :
     /// - Add publishers
     /// - Delete publishers
     /// - Set minimum number of publishers
+    /// - Set price account flags
+    /// - Rotate publisher keys
+    /// - Set the publishers of a price account in batch
+    /// - Pause and resume price accounts
+    /// - Set the aggregation configs of price accounts
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
     pub security_authority:      Pubkey,
 }
 
 impl PermissionAccount {
+    /// Whether `key` may execute `command`. The master authority can execute every permissioned
+    /// command, the other authorities only the commands listed in their documentation.
     pub fn is_authorized(&self, key: &Pubkey, command: OracleCommand) -> bool {
-        #[allow(clippy::match_like_matches_macro)]
-        match (*key, command) {
-            (pubkey, _) if pubkey == self.master_authority => true,
-            (pubkey, OracleCommand::ResizePriceAccount) if pubkey == self.security_authority => {
-                true
-            } // Allow for an admin key to resize the price account
-            _ => false,
+        use OracleCommand::*;
+
+        if *key == self.master_authority {
+            return true;
+        }
+        match command {
+            InitMapping | AddMapping | AddProduct | UpdProduct | AddPrice | InitPrice
+            | DelPrice | DelProduct | SetSchedule => *key == self.data_curation_authority,
+            AddPublisher | DelPublisher | SetMinPub | SetPriceFlags | RotatePublisher
+            | SetPublishers | PauseFeed | ResumeFeed | SetAggregationConfig => {
+                *key == self.security_authority
+            }
+            // Either permissionless, signed by the publisher, removed or reserved to the master
+            // authority
+            UpdPrice
+            | AggPrice
+            | InitTest
+            | UpdTest
+            | UpdPriceNoFailOnError
+            | ResizePriceAccount
+            | UpdPermissions
+            | SetMaxLatency
+            | InitPriceFeedIndex
+            | ResizeMapping
+            | AddDelegate
+            | DelDelegate
+            | UpdPriceBatch
+            | UpdPriceCompact => false,
         }
     }
 
@@ -30,11 +30,19 @@ mod price_pythnet {
     use {
         super::*,
         crate::c_oracle_header::{
+            PC_CORP_ACT_NONE,
             PC_MAX_SEND_LATENCY,
+            PC_NUM_COMP,
             PC_NUM_COMP_PYTHNET,
+            PC_STATUS_HALTED,
             PC_STATUS_TRADING,
         },
         bitflags::bitflags,
+        num_derive::{
+            FromPrimitive,
+            ToPrimitive,
+        },
+        num_traits::FromPrimitive,
     };
 
     /// Pythnet-only extended price account format. This extension is
@@ -43,62 +51,75 @@ mod price_pythnet {
     #[repr(C)]
     #[derive(Copy, Clone, Pod, Zeroable)]
     pub struct PriceAccountPythnet {
-        pub header:             AccountHeader,
+        pub header:              AccountHeader,
         /// Type of the price account
-        pub price_type:         u32,
+        pub price_type:          u32,
         /// Exponent for the published prices
-        pub exponent:           i32,
+        pub exponent:            i32,
         /// Current number of authorized publishers
-        pub num_:               u32,
+        pub num_:                u32,
         /// Number of valid quotes for the last aggregation
-        pub num_qt_:            u32,
+        pub num_qt_:             u32,
         /// Last slot with a succesful aggregation (status : TRADING)
-        pub last_slot_:         u64,
+        pub last_slot_:          u64,
         /// Second to last slot where aggregation was attempted
-        pub valid_slot_:        u64,
+        pub valid_slot_:         u64,
         /// Ema for price
-        pub twap_:              PriceEma,
+        pub twap_:               PriceEma,
         /// Ema for confidence
-        pub twac_:              PriceEma,
+        pub twac_:               PriceEma,
         /// Last time aggregation was attempted
-        pub timestamp_:         i64,
+        pub timestamp_:          i64,
         /// Minimum valid publisher quotes for a succesful aggregation
-        pub min_pub_:           u8,
-        pub message_sent_:      u8,
+        pub min_pub_:            u8,
+        pub message_sent_:       u8,
         /// Configurable max latency in slots between send and receive
-        pub max_latency_:       u8,
+        pub max_latency_:        u8,
         /// Various flags
-        pub flags:              PriceAccountFlags,
+        pub flags:               PriceAccountFlags,
         /// Globally unique price feed index used for publishing.
         /// Limited to 28 bites so that it can be packed together with trading status in a single u32.
-        pub feed_index:         u32,
+        pub feed_index:          u32,
         /// Corresponding product account
-        pub product_account:    Pubkey,
+        pub product_account:     Pubkey,
         /// Next price account in the list
-        pub next_price_account: Pubkey,
+        pub next_price_account:  Pubkey,
         /// Second to last slot where aggregation was succesful (i.e. status : TRADING)
-        pub prev_slot_:         u64,
+        pub prev_slot_:          u64,
         /// Aggregate price at prev_slot_
-        pub prev_price_:        i64,
+        pub prev_price_:         i64,
         /// Confidence interval at prev_slot_
-        pub prev_conf_:         u64,
+        pub prev_conf_:          u64,
         /// Timestamp of prev_slot_
-        pub prev_timestamp_:    i64,
+        pub prev_timestamp_:     i64,
         /// Last attempted aggregate results
-        pub agg_:               PriceInfo,
+        pub agg_:                PriceInfo,
         /// Publishers' price components. NOTE(2023-10-06): On Pythnet, not all
         /// PC_NUM_COMP_PYTHNET slots are used due to stack size
         /// issues in the C code. For iterating over price components,
-        /// PC_NUM_COMP must be used.
-        pub comp_:              [PriceComponent; PC_NUM_COMP_PYTHNET as usize],
+        /// PC_NUM_COMP must be used. The last slot holds `excluded_components` instead.
+        pub comp_:               [PriceComponent; PC_NUM_COMP_PYTHNET as usize - 1],
+        /// Bitmap of the components whose quote was excluded from the last aggregate by the
+        /// outlier filter, see `AggregationConfig::outlier_mads`: bit `i` is set if the quote of
+        /// `comp_[i]` was excluded. Cleared whenever the components are reordered.
+        pub excluded_components: u64,
+        pub unused_:             [u64; 11],
         /// Cumulative sums of aggregative price and confidence used to compute arithmetic moving averages
-        pub price_cumulative:   PriceCumulative,
+        pub price_cumulative:    PriceCumulative,
     }
 
     // Feed index is limited to 28 bites so that it can be packed
     // together with trading status in a single u32.
     pub const MAX_FEED_INDEX: u32 = (1 << 28) - 1;
 
+    // `excluded_components` and `unused_` take the place of the last price component, and the
+    // bitmap has a bit per component in use.
+    const _: () = assert!(
+        size_of::<PriceComponent>() == size_of::<u64>() * 12
+            && PC_NUM_COMP <= u64::BITS
+            && PC_NUM_COMP < PC_NUM_COMP_PYTHNET
+    );
+
     bitflags! {
         #[repr(C)]
         #[derive(Copy, Clone, Pod, Zeroable)]
@@ -108,6 +129,12 @@ mod price_pythnet {
             /// If unset, the program will remove old messages from its message buffer account
             /// and set this flag.
             const MESSAGE_BUFFER_CLEARED = 0b10;
+            /// If set, the feed is paused: the aggregate is held at `PC_STATUS_HALTED` instead of
+            /// being computed, while publishers can still update their components.
+            const PAUSED = 0b100;
+            /// If set, the aggregate is only computed during the trading hours of the price
+            /// account's `ScheduleAccount`, and held at `PC_STATUS_UNKNOWN` outside of them.
+            const SCHEDULED = 0b1000;
         }
     }
 
@@ -130,6 +157,45 @@ mod price_pythnet {
                 ema_conf: self.twac_.val_ as u64,
             }
         }
+        /// Set the aggregate status to `PC_STATUS_HALTED`, see `hold_aggregate`.
+        pub fn halt_aggregate(&mut self) {
+            self.hold_aggregate(PC_STATUS_HALTED);
+        }
+
+        /// Set the aggregate status to `status`, which mustn't be `PC_STATUS_TRADING`. Like a
+        /// failed aggregation, a trading aggregate becomes the previous price, so that messages
+        /// keep showing the last trading price with its publish time, and the corporate actions
+        /// are cleared. A new message is sent when the status changes.
+        pub fn hold_aggregate(&mut self, status: u32) {
+            if self.agg_.status_ == PC_STATUS_TRADING {
+                self.prev_slot_ = self.agg_.pub_slot_;
+                self.prev_price_ = self.agg_.price_;
+                self.prev_conf_ = self.agg_.conf_;
+                self.prev_timestamp_ = self.timestamp_;
+            }
+            self.agg_.corp_act_status_ = PC_CORP_ACT_NONE;
+            if self.agg_.status_ != status {
+                self.agg_.status_ = status;
+                self.message_sent_ = 0;
+            }
+        }
+
+        /// Hold the aggregate of a paused feed at `slot` instead of computing it, see
+        /// `skip_aggregate`.
+        pub fn skip_paused_aggregate(&mut self, slot: u64, timestamp: i64) {
+            self.skip_aggregate(slot, timestamp, PC_STATUS_HALTED);
+        }
+
+        /// Hold the aggregate at `status` for `slot` instead of computing it. The slots are
+        /// updated like in an aggregation, but the aggregate price, the EMAs and the cumulative
+        /// sums are left unchanged.
+        pub fn skip_aggregate(&mut self, slot: u64, timestamp: i64, status: u32) {
+            self.hold_aggregate(status);
+            self.valid_slot_ = self.agg_.pub_slot_;
+            self.agg_.pub_slot_ = slot;
+            self.timestamp_ = timestamp;
+        }
+
         /// This function gets triggered when there's a succesful aggregation and updates the cumulative sums
         pub fn update_price_cumulative(&mut self) {
             if self.agg_.status_ == PC_STATUS_TRADING {
@@ -176,18 +242,97 @@ mod price_pythnet {
     /// `(T.price_cumulative.price - t.price_cumulative.price) / (T.agg_.pub_slot_ - t.agg_.pub_slot_)`
     #[repr(C)]
     #[derive(Copy, Clone, Pod, Zeroable)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct PriceCumulative {
         /// Cumulative sum of price * slot_gap
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::accounts::serde_helpers::decimal_string")
+        )]
         pub price:          i128,
         /// Cumulative sum of conf * slot_gap
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::accounts::serde_helpers::decimal_string")
+        )]
         pub conf:           u128,
         /// Cumulative number of slots where the price wasn't recently updated (within
         /// PC_MAX_SEND_LATENCY slots). This field should be used to calculate the downtime
         /// as a percent of slots between two times `T` and `t` as follows:
         /// `(T.num_down_slots - t.num_down_slots) / (T.agg_.pub_slot_ - t.agg_.pub_slot_)`
         pub num_down_slots: u64,
-        /// Padding for alignment
-        pub unused:         u64,
+        /// Aggregation settings, in what used to be padding for alignment
+        pub aggregation:    AggregationConfig,
+    }
+
+    /// How the aggregate is computed from the valid component prices
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+    pub enum AggregationMode {
+        /// The model of the C code: the p25, p50 and p75 of the prices minus, at and plus the
+        /// confidence of every quote. The aggregate price is the p50 and the confidence is the
+        /// larger distance from it to the p25 or p75.
+        Model          = 0,
+        /// The median of the prices
+        Median         = 1,
+        /// The median of the prices, each weighted by the inverse of its confidence
+        WeightedMedian = 2,
+        /// The mean of the prices, without the lowest and highest quarter of them
+        TrimmedMean    = 3,
+    }
+
+    /// Aggregation settings of a price account. They are stored in the former padding of
+    /// `PriceCumulative` so that existing price accounts keep their size, and all zeros selects
+    /// the model of the C code. Set by `SetAggregationConfig`.
+    #[repr(C)]
+    #[cfg_attr(test, derive(Debug, PartialEq))]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct AggregationConfig {
+        /// An `AggregationMode`
+        pub mode:             u8,
+        /// If not 0, the valid quotes whose price is more than this many median absolute
+        /// deviations from the median price are excluded from the aggregate, unless that
+        /// deviation is 0.
+        pub outlier_mads:     u8,
+        /// If 1, the confidence of each quote is multiplied by the square root of its age in
+        /// slots, up to `PC_MAX_SEND_LATENCY` slots, before it enters the aggregate.
+        pub widen_stale_conf: u8,
+        pub unused_:          [u8; 5],
+    }
+
+    impl AggregationConfig {
+        /// A config that `is_valid`
+        pub fn new(mode: AggregationMode, outlier_mads: u8, widen_stale_conf: bool) -> Self {
+            AggregationConfig {
+                mode: mode as u8,
+                outlier_mads,
+                widen_stale_conf: u8::from(widen_stale_conf),
+                unused_: [0; 5],
+            }
+        }
+
+        /// Whether `mode` is an `AggregationMode`, `widen_stale_conf` is 0 or 1 and the unused
+        /// bytes are 0, which `SetAggregationConfig` requires.
+        pub fn is_valid(&self) -> bool {
+            AggregationMode::from_u8(self.mode).is_some()
+                && self.widen_stale_conf <= 1
+                && self.unused_ == [0; 5]
+        }
+
+        /// Whether the aggregate is computed exactly like the C code: `AggregationMode::Model`
+        /// without outlier filter or widening of stale confidences.
+        pub fn is_c_model(&self) -> bool {
+            self.mode() == AggregationMode::Model
+                && self.outlier_mads == 0
+                && self.widen_stale_conf == 0
+        }
+
+        /// The aggregation mode, `AggregationMode::Model` if `mode` isn't a valid
+        /// `AggregationMode`.
+        pub fn mode(&self) -> AggregationMode {
+            AggregationMode::from_u8(self.mode).unwrap_or(AggregationMode::Model)
+        }
     }
 
     impl PriceCumulative {
@@ -209,7 +354,12 @@ mod price_pythnet {
 #[repr(C)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PriceComponent {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
     pub pub_:    Pubkey,
     pub agg_:    PriceInfo,
     pub latest_: PriceInfo,
@@ -231,10 +381,19 @@ impl Arbitrary for PriceComponent {
 #[repr(C)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PriceInfo {
     pub price_:           i64,
     pub conf_:            u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::price_status")
+    )]
     pub status_:          u32,
+    /// Corporate actions affecting the price, a combination of the `PC_CORP_ACT_*` flags. For
+    /// the aggregate, the ones reported by a strict majority of the aggregated components, or
+    /// none if the aggregate isn't trading. They aren't part of the price feed messages, whose
+    /// layout is fixed: consumers read them from the price account.
     pub corp_act_status_: u32,
     pub pub_slot_:        u64,
 }
@@ -253,7 +412,9 @@ impl Arbitrary for PriceInfo {
 }
 
 #[repr(C)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PriceEma {
     pub val_:   i64,
     pub numer_: i64,
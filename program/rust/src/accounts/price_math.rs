@@ -0,0 +1,215 @@
+//! Fixed-point arithmetic on prices with a confidence interval.
+//!
+//! A `Price` stands for `price * 10^exponent` with a confidence interval of `conf * 10^exponent`.
+//! Operations are computed exactly in 128 bits and the result is then brought back into 64 bits
+//! by dropping the least significant digits and increasing the exponent, the same way `pd_scale`
+//! normalizes values in `pd.h`. Prices are rounded toward zero while confidence intervals are
+//! rounded up so that rounding never makes a price look more certain. Operations return
+//! `OracleError::IntegerCastingError` if the result can't be represented.
+use {
+    super::{
+        CheckedPrice,
+        PriceInfo,
+    },
+    crate::{
+        error::OracleError,
+        utils::try_convert,
+    },
+    std::cmp::Ordering,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Price {
+    pub price:    i64,
+    pub conf:     u64,
+    pub exponent: i32,
+}
+
+impl Price {
+    pub fn new(price: i64, conf: u64, exponent: i32) -> Self {
+        Price {
+            price,
+            conf,
+            exponent,
+        }
+    }
+
+    /// The price and confidence of `price_info`, which are expressed with the exponent of the
+    /// price account they come from.
+    pub fn from_price_info(price_info: &PriceInfo, exponent: i32) -> Self {
+        Price::new(price_info.price_, price_info.conf_, exponent)
+    }
+
+    /// Expresses the price with `exponent`. Lowering the exponent fails if the price or the
+    /// confidence overflow, raising it drops digits.
+    pub fn scale_to_exponent(&self, exponent: i32) -> Result<Price, OracleError> {
+        let diff = i64::from(self.exponent) - i64::from(exponent);
+        let (price, conf) = if diff >= 0 {
+            self.widen_to_exponent(exponent)?
+        } else {
+            // Dividing by more than 10^38 gives the same result as dividing by 10^38.
+            let factor = pow10((-diff).min(38))?;
+            (
+                i128::from(self.price) / factor,
+                div_ceil(u128::from(self.conf), factor.unsigned_abs()),
+            )
+        };
+        Ok(Price {
+            price: try_convert(price)?,
+            conf: try_convert(conf)?,
+            exponent,
+        })
+    }
+
+    /// Sum of two prices. The confidence of the result is the sum of the confidences.
+    pub fn add(&self, other: &Price) -> Result<Price, OracleError> {
+        let exponent = self.exponent.min(other.exponent);
+        let (price_1, conf_1) = self.widen_to_exponent(exponent)?;
+        let (price_2, conf_2) = other.widen_to_exponent(exponent)?;
+        normalize(
+            price_1
+                .checked_add(price_2)
+                .ok_or(OracleError::IntegerCastingError)?,
+            conf_1
+                .checked_add(conf_2)
+                .ok_or(OracleError::IntegerCastingError)?,
+            exponent,
+        )
+    }
+
+    /// Product of two prices. The confidence of the result is `|p1| * c2 + |p2| * c1`, the
+    /// first order error of the product.
+    pub fn mul(&self, other: &Price) -> Result<Price, OracleError> {
+        let price = i128::from(self.price) * i128::from(other.price);
+        let conf = u128::from(self.price.unsigned_abs()) * u128::from(other.conf)
+            + u128::from(other.price.unsigned_abs()) * u128::from(self.conf);
+        let exponent = i64::from(self.exponent) + i64::from(other.exponent);
+        normalize(price, conf, try_convert(exponent)?)
+    }
+
+    /// Quotient of two prices. The confidence of the result is `(c1 + |p1 / p2| * c2) / |p2|`,
+    /// the first order error of the quotient. Dividing by zero fails.
+    pub fn div(&self, other: &Price) -> Result<Price, OracleError> {
+        if other.price == 0 {
+            return Err(OracleError::IntegerCastingError);
+        }
+
+        // Compute the quotient with 18 extra digits, then bring it back into 64 bits before
+        // computing the confidence so the products below can't overflow.
+        const EXTRA_DIGITS: i64 = 18;
+        let exponent = i64::from(self.exponent) - i64::from(other.exponent) - EXTRA_DIGITS;
+        let divisor = u128::from(other.price.unsigned_abs());
+        let quotient = normalize(
+            i128::from(self.price) * pow10(EXTRA_DIGITS)? / i128::from(other.price),
+            0,
+            try_convert(exponent)?,
+        )?;
+
+        let dropped_digits = i64::from(quotient.exponent) - exponent;
+        let conf_1 = div_ceil(
+            u128::from(self.conf) * pow10(EXTRA_DIGITS - dropped_digits)?.unsigned_abs(),
+            divisor,
+        );
+        let conf_2 = div_ceil(
+            u128::from(quotient.price.unsigned_abs()) * u128::from(other.conf),
+            divisor,
+        );
+        normalize(
+            i128::from(quotient.price),
+            conf_1
+                .checked_add(conf_2)
+                .ok_or(OracleError::IntegerCastingError)?,
+            quotient.exponent,
+        )
+    }
+
+    /// Compares the values of two prices, ignoring their confidence. This is exact and works for
+    /// any pair of exponents.
+    // `Ord` isn't implemented because it wouldn't agree with `Eq`, which also compares the
+    // confidence and the representation.
+    #[allow(clippy::should_implement_trait)]
+    pub fn cmp(&self, other: &Price) -> Ordering {
+        let sign_ordering = self.price.signum().cmp(&other.price.signum());
+        if sign_ordering != Ordering::Equal || self.price == 0 {
+            return sign_ordering;
+        }
+
+        // Same sign, compare the magnitudes by scaling the price with the larger exponent.
+        let (larger, smaller, ordering_if_larger) = if self.exponent >= other.exponent {
+            (self, other, Ordering::Greater)
+        } else {
+            (other, self, Ordering::Less)
+        };
+        let diff = i64::from(larger.exponent) - i64::from(smaller.exponent);
+        let magnitude_ordering = match pow10(diff)
+            .ok()
+            .and_then(|x| i128::from(larger.price.unsigned_abs()).checked_mul(x))
+        {
+            Some(larger_magnitude) => {
+                match larger_magnitude.cmp(&i128::from(smaller.price.unsigned_abs())) {
+                    Ordering::Greater => ordering_if_larger,
+                    Ordering::Less => ordering_if_larger.reverse(),
+                    Ordering::Equal => Ordering::Equal,
+                }
+            }
+            // The scaled magnitude doesn't fit in 128 bits so it's larger than any 64 bit price
+            None => ordering_if_larger,
+        };
+
+        if self.price > 0 {
+            magnitude_ordering
+        } else {
+            magnitude_ordering.reverse()
+        }
+    }
+
+    /// The price and confidence with a lower `exponent`, in 128 bits.
+    fn widen_to_exponent(&self, exponent: i32) -> Result<(i128, u128), OracleError> {
+        let factor = pow10(i64::from(self.exponent) - i64::from(exponent))?;
+        Ok((
+            i128::from(self.price)
+                .checked_mul(factor)
+                .ok_or(OracleError::IntegerCastingError)?,
+            u128::from(self.conf)
+                .checked_mul(factor.unsigned_abs())
+                .ok_or(OracleError::IntegerCastingError)?,
+        ))
+    }
+}
+
+impl From<CheckedPrice> for Price {
+    fn from(price: CheckedPrice) -> Self {
+        Price::new(price.price, price.conf, price.exponent)
+    }
+}
+
+fn pow10(exponent: i64) -> Result<i128, OracleError> {
+    10i128
+        .checked_pow(try_convert(exponent)?)
+        .ok_or(OracleError::IntegerCastingError)
+}
+
+fn div_ceil(numerator: u128, denominator: u128) -> u128 {
+    let quotient = numerator / denominator;
+    if quotient * denominator == numerator {
+        quotient
+    } else {
+        quotient + 1
+    }
+}
+
+/// Drops digits from `price` and `conf` until both fit in 64 bits.
+fn normalize(mut price: i128, mut conf: u128, mut exponent: i32) -> Result<Price, OracleError> {
+    while i64::try_from(price).is_err() || u64::try_from(conf).is_err() {
+        price /= 10;
+        conf = div_ceil(conf, 10);
+        exponent = exponent
+            .checked_add(1)
+            .ok_or(OracleError::IntegerCastingError)?;
+    }
+    Ok(Price {
+        price: try_convert(price)?,
+        conf: try_convert(conf)?,
+        exponent,
+    })
+}
@@ -9,6 +9,7 @@ use {
             PC_PROD_ACC_SIZE,
         },
         deserialize::load_checked,
+        error::OracleError,
         instruction::CommandHeader,
         utils::{
             pyth_assert,
@@ -31,8 +32,13 @@ use {
 
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProductAccount {
     pub header:              AccountHeader,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
     pub first_price_account: Pubkey,
 }
 
@@ -59,7 +65,10 @@ pub fn update_product_metadata(
 
     let new_data_len = instruction_data.len() - size_of::<CommandHeader>();
     let max_data_len = try_convert::<_, usize>(PC_PROD_ACC_SIZE)? - size_of::<ProductAccount>();
-    pyth_assert(new_data_len <= max_data_len, ProgramError::InvalidArgument)?;
+    pyth_assert(
+        new_data_len <= max_data_len,
+        OracleError::InvalidProductMetadata.into(),
+    )?;
 
     let new_data = &instruction_data[size_of::<CommandHeader>()..instruction_data.len()];
     let mut idx = 0;
@@ -73,7 +82,10 @@ pub fn update_product_metadata(
     }
 
     // This assertion shouldn't ever fail, but be defensive.
-    pyth_assert(idx == new_data.len(), ProgramError::InvalidArgument)?;
+    pyth_assert(
+        idx == new_data.len(),
+        OracleError::InvalidProductMetadata.into(),
+    )?;
 
     {
         let mut data = product_account.try_borrow_mut_data()?;
@@ -95,11 +107,11 @@ pub fn update_product_metadata(
 /// the bytes of the `pc_str_t`.
 pub fn read_pc_str_t(source: &[u8]) -> Result<&[u8], ProgramError> {
     if source.is_empty() {
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidProductMetadata.into())
     } else {
         let tag_len: usize = try_convert(source[0])?;
         if tag_len + 1 > source.len() {
-            Err(ProgramError::InvalidArgument)
+            Err(OracleError::InvalidProductMetadata.into())
         } else {
             Ok(&source[..(1 + tag_len)])
         }
@@ -0,0 +1,244 @@
+//! Typed access to the key/value metadata stored in product accounts.
+//!
+//! The metadata region follows the `ProductAccount` header and is a list of `pc_str_t` strings
+//! (a length byte followed by the bytes of the string), alternating between keys and values:
+//! `[len(key1), ...key1, len(val1), ...val1, len(key2), ...key2, len(val2), ...val2, ...]`
+//!
+//! The program doesn't require the keys to be unique or the strings to be UTF-8, so neither does
+//! `ProductMetadata`: it keeps the raw bytes of every entry and only checks the sizes that the
+//! program checks.
+use {
+    super::ProductAccount,
+    crate::c_oracle_header::PC_PROD_ACC_SIZE,
+    bytemuck::{
+        bytes_of,
+        try_pod_read_unaligned,
+    },
+    std::{
+        borrow::Cow,
+        mem::size_of,
+    },
+};
+
+/// Maximum number of bytes of metadata that fit in a product account.
+pub const MAX_PRODUCT_METADATA_LEN: usize = PC_PROD_ACC_SIZE as usize - size_of::<ProductAccount>();
+
+/// Maximum length of a single key or value, limited by the length byte of `pc_str_t`.
+pub const MAX_PC_STR_LEN: usize = u8::MAX as usize;
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ProductMetadataError {
+    /// A key or value is longer than `MAX_PC_STR_LEN` bytes.
+    #[error("StringTooLong")]
+    StringTooLong { len: usize },
+    /// The encoded metadata doesn't fit in a product account.
+    #[error("MetadataTooLarge")]
+    MetadataTooLarge { len: usize, max: usize },
+    /// The string starting at `offset` extends past the end of the metadata.
+    #[error("TruncatedString")]
+    TruncatedString { offset: usize },
+    /// The metadata ends with a key that has no value.
+    #[error("MissingValue")]
+    MissingValue { key: String },
+    /// The account data is too small to contain a product account, or its header `size` is out of
+    /// bounds.
+    #[error("InvalidAccountData")]
+    InvalidAccountData,
+}
+
+/// Product metadata as an ordered list of key/value entries. Entries keep the order in which
+/// they were inserted (or read from the account), so parsing and encoding metadata gives back the
+/// same bytes. When a key appears more than once, the last entry wins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProductMetadata {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ProductMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the raw key/value region of a product account. Accepts everything `UpdProduct`
+    /// accepts, including duplicate keys and strings that aren't UTF-8.
+    pub fn parse(data: &[u8]) -> Result<Self, ProductMetadataError> {
+        if data.len() > MAX_PRODUCT_METADATA_LEN {
+            return Err(ProductMetadataError::MetadataTooLarge {
+                len: data.len(),
+                max: MAX_PRODUCT_METADATA_LEN,
+            });
+        }
+
+        let mut metadata = Self::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let key = read_string(data, &mut offset)?;
+            if offset == data.len() {
+                return Err(ProductMetadataError::MissingValue {
+                    key: String::from_utf8_lossy(&key).into_owned(),
+                });
+            }
+            let value = read_string(data, &mut offset)?;
+            metadata.entries.push((key, value));
+        }
+        Ok(metadata)
+    }
+
+    /// Parses the metadata of a product account given its full account data. Only the first
+    /// `header.size` bytes hold valid metadata, anything after that is left over from previous
+    /// updates.
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ProductMetadataError> {
+        let product_account = data
+            .get(..size_of::<ProductAccount>())
+            .and_then(|x| try_pod_read_unaligned::<ProductAccount>(x).ok())
+            .ok_or(ProductMetadataError::InvalidAccountData)?;
+        let size = product_account.header.size as usize;
+        let metadata = data
+            .get(size_of::<ProductAccount>()..size)
+            .ok_or(ProductMetadataError::InvalidAccountData)?;
+        Self::parse(metadata)
+    }
+
+    /// Encodes the metadata in the format expected by `add_product` and `upd_product`. Fails if a
+    /// key or value is too long or if the metadata doesn't fit in a product account.
+    pub fn encode(&self) -> Result<Vec<u8>, ProductMetadataError> {
+        let mut data = Vec::with_capacity(self.encoded_len());
+        for (key, value) in &self.entries {
+            write_string(&mut data, key)?;
+            write_string(&mut data, value)?;
+        }
+        if data.len() > MAX_PRODUCT_METADATA_LEN {
+            return Err(ProductMetadataError::MetadataTooLarge {
+                len: data.len(),
+                max: MAX_PRODUCT_METADATA_LEN,
+            });
+        }
+        Ok(data)
+    }
+
+    /// Number of bytes taken by the encoded metadata.
+    pub fn encoded_len(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(key, value)| 2 + key.len() + value.len())
+            .sum()
+    }
+
+    /// The value of `key`, with the bytes that aren't UTF-8 replaced by U+FFFD.
+    pub fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.get_bytes(key.as_bytes()).map(String::from_utf8_lossy)
+    }
+
+    /// The raw value of `key`.
+    pub fn get_bytes(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(x, _)| x == key)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Sets the value of `key`, returning the previous value. New keys are added at the end, while
+    /// existing keys keep the position of their first entry and lose their duplicates. The lengths
+    /// are checked by `encode`.
+    pub fn insert(
+        &mut self,
+        key: impl Into<Vec<u8>>,
+        value: impl Into<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        let key = key.into();
+        let index = self.entries.iter().position(|(x, _)| *x == key);
+        let previous = self.remove(&key);
+        let entry = (key, value.into());
+        match index {
+            Some(index) => self.entries.insert(index, entry),
+            None => self.entries.push(entry),
+        }
+        previous
+    }
+
+    /// Appends an entry, even if `key` already has one, like the program allows.
+    pub fn push(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        self.entries.push((key.into(), value.into()));
+    }
+
+    /// Removes every entry of `key`, returning its value.
+    pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let mut removed = None;
+        self.entries.retain_mut(|(x, value)| {
+            if x == key {
+                removed = Some(std::mem::take(value));
+                false
+            } else {
+                true
+            }
+        });
+        removed
+    }
+
+    /// The raw entries, in order and including duplicate keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_slice(), value.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A product account with the metadata that follows it in the account data. With the `serde`
+/// feature, its JSON form is the one of `ProductAccount` with a `metadata` field listing the
+/// `[key, value]` entries.
+#[derive(Clone)]
+pub struct ProductAccountData {
+    pub account:  ProductAccount,
+    pub metadata: ProductMetadata,
+}
+
+impl ProductAccountData {
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ProductMetadataError> {
+        let metadata = ProductMetadata::from_account_data(data)?;
+        let account = try_pod_read_unaligned(&data[..size_of::<ProductAccount>()])
+            .map_err(|_| ProductMetadataError::InvalidAccountData)?;
+        Ok(Self { account, metadata })
+    }
+
+    /// The `PC_PROD_ACC_SIZE` bytes of account data, with `header.size` set to the end of the
+    /// metadata. The bytes after the metadata are zero, even if the account held longer metadata
+    /// before.
+    pub fn to_account_data(&self) -> Result<Vec<u8>, ProductMetadataError> {
+        let metadata = self.metadata.encode()?;
+        let mut account = self.account;
+        account.header.size = (size_of::<ProductAccount>() + metadata.len()) as u32;
+
+        let mut data = bytes_of(&account).to_vec();
+        data.extend_from_slice(&metadata);
+        data.resize(PC_PROD_ACC_SIZE as usize, 0);
+        Ok(data)
+    }
+}
+
+fn read_string(data: &[u8], offset: &mut usize) -> Result<Vec<u8>, ProductMetadataError> {
+    let start = *offset + 1;
+    let end = start + usize::from(data[*offset]);
+    let bytes = data
+        .get(start..end)
+        .ok_or(ProductMetadataError::TruncatedString { offset: *offset })?;
+    *offset = end;
+    Ok(bytes.to_vec())
+}
+
+fn write_string(data: &mut Vec<u8>, s: &[u8]) -> Result<(), ProductMetadataError> {
+    if s.len() > MAX_PC_STR_LEN {
+        return Err(ProductMetadataError::StringTooLong { len: s.len() });
+    }
+    data.push(s.len() as u8);
+    data.extend_from_slice(s);
+    Ok(())
+}
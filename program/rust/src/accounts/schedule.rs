@@ -0,0 +1,139 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_SCHEDULE,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Maximum number of weekly trading sessions of a schedule
+pub const MAX_SESSIONS: usize = 16;
+/// Maximum number of holidays of a schedule
+pub const MAX_HOLIDAYS: usize = 32;
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+pub const MINUTES_PER_WEEK: u16 = 7 * MINUTES_PER_DAY;
+/// Largest offset from UTC of a schedule, in seconds
+pub const MAX_UTC_OFFSET: i32 = 14 * 60 * 60;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A weekly trading session, from `start_minute` included to `end_minute` excluded. Minutes are
+/// counted in the schedule's local time from Monday 00:00.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TradingSession {
+    pub start_minute: u16,
+    pub end_minute:   u16,
+}
+
+/// A day whose trading hours replace the weekly sessions. The market is open from `start_minute`
+/// included to `end_minute` excluded, counted in minutes from the local midnight, and closed all
+/// day if both are equal.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Holiday {
+    /// Local day, counted from 1970-01-01
+    pub day:          i32,
+    pub start_minute: u16,
+    pub end_minute:   u16,
+}
+
+/// The trading hours of a price account. Outside of them, the aggregate price isn't computed and
+/// its status is `PC_STATUS_UNKNOWN`. There is at most one schedule per price account, at the PDA
+/// `[SCHEDULE_SEED, price_account]`, and it only applies while the price account has the
+/// `SCHEDULED` flag. The price accounts of a product that trade at the same hours each have a copy
+/// of the schedule. Schedules aren't per product because the flag and the aggregate live in the
+/// price account: `UpdPrice` and the validator can then find the schedule from the price account
+/// alone, without the product account, which neither of them loads.
+///
+/// The offset from UTC is fixed: schedules of markets observing daylight saving time must be
+/// updated when it starts and ends.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleAccount {
+    /// pyth account header
+    pub header:        AccountHeader,
+    /// The price account following this schedule
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::accounts::serde_helpers::pubkey_base58")
+    )]
+    pub price_account: Pubkey,
+    /// Offset of the local time from UTC, in seconds
+    pub utc_offset:    i32,
+    /// Number of entries of `sessions` in use
+    pub num_sessions:  u8,
+    /// Number of entries of `holidays` in use
+    pub num_holidays:  u8,
+    pub unused_:       [u8; 2],
+    pub sessions:      [TradingSession; MAX_SESSIONS],
+    /// Sorted by day
+    pub holidays:      [Holiday; MAX_HOLIDAYS],
+}
+
+impl ScheduleAccount {
+    pub fn sessions(&self) -> &[TradingSession] {
+        &self.sessions[..usize::from(self.num_sessions).min(MAX_SESSIONS)]
+    }
+
+    pub fn holidays(&self) -> &[Holiday] {
+        &self.holidays[..usize::from(self.num_holidays).min(MAX_HOLIDAYS)]
+    }
+
+    /// Whether the market is open at `unix_timestamp`.
+    pub fn is_trading(&self, unix_timestamp: i64) -> bool {
+        let local_time = unix_timestamp.saturating_add(i64::from(self.utc_offset));
+        let day = local_time.div_euclid(SECONDS_PER_DAY);
+        // Both casts are lossless: the minute is less than MINUTES_PER_DAY and the weekday less
+        // than 7
+        let minute = (local_time.rem_euclid(SECONDS_PER_DAY) / 60) as u16;
+
+        if let Some(holiday) = self
+            .holidays()
+            .iter()
+            .find(|holiday| i64::from(holiday.day) == day)
+        {
+            return holiday.start_minute <= minute && minute < holiday.end_minute;
+        }
+
+        // 1970-01-01 was a Thursday
+        let weekday = (day + 3).rem_euclid(7) as u16;
+        let minute_of_week = weekday * MINUTES_PER_DAY + minute;
+        self.sessions().iter().any(|session| {
+            session.start_minute <= minute_of_week && minute_of_week < session.end_minute
+        })
+    }
+
+    /// Whether the sessions and holidays are well formed: sessions are non-empty and within the
+    /// week, holidays are within their day and sorted by strictly increasing day, and the offset
+    /// from UTC is at most `MAX_UTC_OFFSET`.
+    pub fn is_valid(&self) -> bool {
+        usize::from(self.num_sessions) <= MAX_SESSIONS
+            && usize::from(self.num_holidays) <= MAX_HOLIDAYS
+            && (-MAX_UTC_OFFSET..=MAX_UTC_OFFSET).contains(&self.utc_offset)
+            && self.sessions().iter().all(|session| {
+                session.start_minute < session.end_minute && session.end_minute <= MINUTES_PER_WEEK
+            })
+            && self.holidays().iter().all(|holiday| {
+                holiday.start_minute <= holiday.end_minute && holiday.end_minute <= MINUTES_PER_DAY
+            })
+            && self
+                .holidays()
+                .windows(2)
+                .all(|pair| pair[0].day < pair[1].day)
+    }
+}
+
+impl PythAccount for ScheduleAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_SCHEDULE;
+    const INITIAL_SIZE: u32 = size_of::<ScheduleAccount>() as u32;
+}
@@ -0,0 +1,428 @@
+//! Serde support for the on-chain account types, enabled with the `serde` feature.
+//!
+//! The JSON forms are meant to be read by humans and monitoring tools, so pubkeys are written in
+//! base58, statuses by their `PC_STATUS_*` name and flags as a list of names. Deserializing the
+//! output again yields the exact same account bytes, which makes it usable for test fixtures.
+
+use {
+    super::{
+        price::PriceCumulative,
+        AccountHeader,
+        MappingAccount,
+        PriceAccount,
+        PriceAccountFlags,
+        PriceComponent,
+        PriceEma,
+        PriceInfo,
+        ProductAccount,
+        ProductAccountData,
+        ProductMetadata,
+    },
+    crate::c_oracle_header::{
+        PC_MAP_TABLE_SIZE,
+        PC_STATUS_AUCTION,
+        PC_STATUS_HALTED,
+        PC_STATUS_IGNORED,
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+    bytemuck::Zeroable,
+    serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    },
+    solana_program::pubkey::Pubkey,
+    std::{
+        borrow::Cow,
+        mem::size_of,
+        str::FromStr,
+    },
+};
+
+/// (De)serializes a `Pubkey` as a base58 string.
+pub mod pubkey_base58 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(|_| D::Error::custom(format!("invalid pubkey: {s}")))
+    }
+}
+
+/// (De)serializes a number as a decimal string. Used for 128 bit integers, which most JSON
+/// consumers can't represent.
+pub mod decimal_string {
+    use {
+        super::*,
+        std::fmt::Display,
+    };
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T: FromStr, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| D::Error::custom(format!("invalid number: {s}")))
+    }
+}
+
+const STATUS_NAMES: [(u32, &str); 5] = [
+    (PC_STATUS_UNKNOWN, "PC_STATUS_UNKNOWN"),
+    (PC_STATUS_TRADING, "PC_STATUS_TRADING"),
+    (PC_STATUS_HALTED, "PC_STATUS_HALTED"),
+    (PC_STATUS_AUCTION, "PC_STATUS_AUCTION"),
+    (PC_STATUS_IGNORED, "PC_STATUS_IGNORED"),
+];
+
+/// (De)serializes a price status by its `PC_STATUS_*` name. Values without a name are written as
+/// plain numbers so that arbitrary account data survives a round trip.
+pub mod price_status {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum StatusRepr {
+        Name(String),
+        Code(u32),
+    }
+
+    pub fn serialize<S: Serializer>(status: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        match STATUS_NAMES.iter().find(|(code, _)| code == status) {
+            Some((_, name)) => serializer.serialize_str(name),
+            None => serializer.serialize_u32(*status),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        match StatusRepr::deserialize(deserializer)? {
+            StatusRepr::Name(name) => STATUS_NAMES
+                .iter()
+                .find(|(_, x)| *x == name)
+                .map(|(code, _)| *code)
+                .ok_or_else(|| D::Error::custom(format!("unknown price status: {name}"))),
+            StatusRepr::Code(code) => Ok(code),
+        }
+    }
+}
+
+/// Flags are written as a list of names. Bits without a name are kept as a single hex entry
+/// (e.g. `"0x80"`).
+impl Serialize for PriceAccountFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut names: Vec<String> = self.iter_names().map(|(x, _)| x.to_string()).collect();
+        let unknown_bits = self.bits() & !Self::all().bits();
+        if unknown_bits != 0 {
+            names.push(format!("{unknown_bits:#x}"));
+        }
+        names.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceAccountFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut flags = Self::empty();
+        for name in Vec::<String>::deserialize(deserializer)? {
+            let flag = match name.strip_prefix("0x") {
+                Some(hex) => u8::from_str_radix(hex, 16).ok().map(Self::from_bits_retain),
+                None => Self::from_name(&name),
+            };
+            flags |= flag.ok_or_else(|| D::Error::custom(format!("unknown price flag: {name}")))?;
+        }
+        Ok(flags)
+    }
+}
+
+/// Serde form of `PriceAccount`. `comp_` only holds the first `num_` components, the remaining
+/// slots and `unused_` are expected to be zero.
+#[derive(Serialize, Deserialize)]
+struct PriceAccountRepr<'a> {
+    header:              AccountHeader,
+    price_type:          u32,
+    exponent:            i32,
+    num_:                u32,
+    num_qt_:             u32,
+    last_slot_:          u64,
+    valid_slot_:         u64,
+    twap_:               PriceEma,
+    twac_:               PriceEma,
+    timestamp_:          i64,
+    min_pub_:            u8,
+    message_sent_:       u8,
+    max_latency_:        u8,
+    flags:               PriceAccountFlags,
+    feed_index:          u32,
+    #[serde(with = "pubkey_base58")]
+    product_account:     Pubkey,
+    #[serde(with = "pubkey_base58")]
+    next_price_account:  Pubkey,
+    prev_slot_:          u64,
+    prev_price_:         i64,
+    prev_conf_:          u64,
+    prev_timestamp_:     i64,
+    agg_:                PriceInfo,
+    comp_:               Cow<'a, [PriceComponent]>,
+    #[serde(default)]
+    excluded_components: u64,
+    price_cumulative:    PriceCumulative,
+}
+
+// The account types are large, these impls avoid the copies that `#[serde(into, try_from)]`
+// would make.
+impl Serialize for PriceAccount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let num_components = (self.num_ as usize).min(self.comp_.len());
+        PriceAccountRepr {
+            header:              self.header,
+            price_type:          self.price_type,
+            exponent:            self.exponent,
+            num_:                self.num_,
+            num_qt_:             self.num_qt_,
+            last_slot_:          self.last_slot_,
+            valid_slot_:         self.valid_slot_,
+            twap_:               self.twap_,
+            twac_:               self.twac_,
+            timestamp_:          self.timestamp_,
+            min_pub_:            self.min_pub_,
+            message_sent_:       self.message_sent_,
+            max_latency_:        self.max_latency_,
+            flags:               self.flags,
+            feed_index:          self.feed_index,
+            product_account:     self.product_account,
+            next_price_account:  self.next_price_account,
+            prev_slot_:          self.prev_slot_,
+            prev_price_:         self.prev_price_,
+            prev_conf_:          self.prev_conf_,
+            prev_timestamp_:     self.prev_timestamp_,
+            agg_:                self.agg_,
+            comp_:               Cow::Borrowed(&self.comp_[..num_components]),
+            excluded_components: self.excluded_components,
+            price_cumulative:    self.price_cumulative,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceAccount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PriceAccountRepr::deserialize(deserializer)?;
+        if repr.comp_.len() != repr.num_ as usize {
+            return Err(D::Error::custom(format!(
+                "expected {} price components, found {}",
+                repr.num_,
+                repr.comp_.len()
+            )));
+        }
+        let mut account = PriceAccount::zeroed();
+        if repr.comp_.len() > account.comp_.len() {
+            return Err(D::Error::custom(format!(
+                "at most {} price components are allowed",
+                account.comp_.len()
+            )));
+        }
+
+        account.header = repr.header;
+        account.price_type = repr.price_type;
+        account.exponent = repr.exponent;
+        account.num_ = repr.num_;
+        account.num_qt_ = repr.num_qt_;
+        account.last_slot_ = repr.last_slot_;
+        account.valid_slot_ = repr.valid_slot_;
+        account.twap_ = repr.twap_;
+        account.twac_ = repr.twac_;
+        account.timestamp_ = repr.timestamp_;
+        account.min_pub_ = repr.min_pub_;
+        account.message_sent_ = repr.message_sent_;
+        account.max_latency_ = repr.max_latency_;
+        account.flags = repr.flags;
+        account.feed_index = repr.feed_index;
+        account.product_account = repr.product_account;
+        account.next_price_account = repr.next_price_account;
+        account.prev_slot_ = repr.prev_slot_;
+        account.prev_price_ = repr.prev_price_;
+        account.prev_conf_ = repr.prev_conf_;
+        account.prev_timestamp_ = repr.prev_timestamp_;
+        account.agg_ = repr.agg_;
+        account.comp_[..repr.comp_.len()].copy_from_slice(&repr.comp_);
+        account.excluded_components = repr.excluded_components;
+        account.price_cumulative = repr.price_cumulative;
+        Ok(account)
+    }
+}
+
+/// Serde form of `MappingAccount`. `products_list` only holds the first `number_of_products`
+/// entries, the remaining slots are expected to be zero.
+#[derive(Serialize, Deserialize)]
+struct MappingAccountRepr<'a> {
+    header:               AccountHeader,
+    number_of_products:   u32,
+    unused_:              u32,
+    #[serde(with = "pubkey_base58")]
+    next_mapping_account: Pubkey,
+    #[serde(with = "pubkey_list_base58")]
+    products_list:        Cow<'a, [Pubkey]>,
+}
+
+impl Serialize for MappingAccount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let num_products = (self.number_of_products as usize).min(self.products_list.len());
+        MappingAccountRepr {
+            header:               self.header,
+            number_of_products:   self.number_of_products,
+            unused_:              self.unused_,
+            next_mapping_account: self.next_mapping_account,
+            products_list:        Cow::Borrowed(&self.products_list[..num_products]),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MappingAccount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MappingAccountRepr::deserialize(deserializer)?;
+        if repr.products_list.len() != repr.number_of_products as usize {
+            return Err(D::Error::custom(format!(
+                "expected {} products, found {}",
+                repr.number_of_products,
+                repr.products_list.len()
+            )));
+        }
+        if repr.products_list.len() > PC_MAP_TABLE_SIZE as usize {
+            return Err(D::Error::custom(format!(
+                "at most {PC_MAP_TABLE_SIZE} products are allowed"
+            )));
+        }
+
+        let mut account = MappingAccount::zeroed();
+        account.header = repr.header;
+        account.number_of_products = repr.number_of_products;
+        account.unused_ = repr.unused_;
+        account.next_mapping_account = repr.next_mapping_account;
+        account.products_list[..repr.products_list.len()].copy_from_slice(&repr.products_list);
+        Ok(account)
+    }
+}
+
+/// Serde form of a key or value of `ProductMetadata`: a string, or the list of its bytes if they
+/// aren't UTF-8.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PcStrRepr<'a> {
+    Utf8(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl<'a> From<&'a [u8]> for PcStrRepr<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => PcStrRepr::Utf8(Cow::Borrowed(s)),
+            Err(_) => PcStrRepr::Bytes(Cow::Borrowed(bytes)),
+        }
+    }
+}
+
+impl From<PcStrRepr<'_>> for Vec<u8> {
+    fn from(repr: PcStrRepr) -> Self {
+        match repr {
+            PcStrRepr::Utf8(s) => s.into_owned().into_bytes(),
+            PcStrRepr::Bytes(bytes) => bytes.into_owned(),
+        }
+    }
+}
+
+impl Serialize for ProductMetadata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.iter()
+                .map(|(key, value)| (PcStrRepr::from(key), PcStrRepr::from(value))),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for ProductMetadata {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut metadata = ProductMetadata::new();
+        for (key, value) in Vec::<(PcStrRepr, PcStrRepr)>::deserialize(deserializer)? {
+            metadata.push(key, value);
+        }
+        Ok(metadata)
+    }
+}
+
+/// Serde form of `ProductAccountData`: the fields of `ProductAccount` followed by the metadata.
+#[derive(Serialize, Deserialize)]
+struct ProductAccountDataRepr<'a> {
+    header:              AccountHeader,
+    #[serde(with = "pubkey_base58")]
+    first_price_account: Pubkey,
+    metadata:            Cow<'a, ProductMetadata>,
+}
+
+impl Serialize for ProductAccountData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProductAccountDataRepr {
+            header:              self.account.header,
+            first_price_account: self.account.first_price_account,
+            metadata:            Cow::Borrowed(&self.metadata),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProductAccountData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ProductAccountDataRepr::deserialize(deserializer)?;
+        let metadata_len = repr
+            .metadata
+            .encode()
+            .map_err(|e| D::Error::custom(format!("invalid metadata: {e:?}")))?
+            .len();
+        let size = size_of::<ProductAccount>() + metadata_len;
+        if repr.header.size as usize != size {
+            return Err(D::Error::custom(format!(
+                "expected a size of {size}, found {}",
+                repr.header.size
+            )));
+        }
+
+        Ok(ProductAccountData {
+            account:  ProductAccount {
+                header:              repr.header,
+                first_price_account: repr.first_price_account,
+            },
+            metadata: repr.metadata.into_owned(),
+        })
+    }
+}
+
+mod pubkey_list_base58 {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct Base58(#[serde(with = "pubkey_base58")] Pubkey);
+
+    pub fn serialize<S: Serializer>(keys: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(keys.iter().map(|x| Base58(*x)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Cow<'static, [Pubkey]>, D::Error> {
+        let keys = Vec::<Base58>::deserialize(deserializer)?;
+        Ok(keys.into_iter().map(|x| x.0).collect())
+    }
+}
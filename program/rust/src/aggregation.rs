@@ -0,0 +1,494 @@
+//! Rust implementation of the aggregation in `upd_aggregate.h`.
+//!
+//! With the `rust-aggregation` feature, `c_upd_aggregate` and `c_upd_twap` run this engine
+//! instead of the C code, so the program and the `library` feature build without the C
+//! toolchain. Every function here produces the same bits as its C counterpart, which the
+//! differential tests in `tests/test_rust_aggregation.rs` check.
+//!
+//! The engine also implements the other `AggregationMode`s, which price accounts can select
+//! instead of the model of the C code, and the outlier filter and the widening of the
+//! confidence of stale quotes of `AggregationConfig`.
+
+use crate::{
+    accounts::{
+        AggregationMode,
+        PriceAccount,
+        PriceEma,
+    },
+    c_oracle_header::{
+        PC_CORP_ACT_NONE,
+        PC_CORP_ACT_NUM_FLAGS,
+        PC_MAX_SEND_LATENCY,
+        PC_NUM_COMP,
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+};
+
+pub mod pd;
+
+use pd::{
+    Pd,
+    PD_EMA_DECAY,
+    PD_EMA_EXPO,
+    PD_EMA_MAX_DIFF,
+};
+
+/// `upd_aggregate`: aggregate the latest component prices of `price_account` at `slot`.
+/// Returns whether the aggregation succeeded, otherwise the aggregate status is set to
+/// `PC_STATUS_UNKNOWN`.
+pub fn upd_aggregate(price_account: &mut PriceAccount, slot: u64, timestamp: i64) -> bool {
+    upd_aggregate_with_mode(price_account, slot, timestamp, AggregationMode::Model)
+}
+
+/// A valid quote of the component at `index`: its price minus and plus its confidence don't
+/// overflow.
+#[derive(Clone, Copy, Default)]
+struct Quote {
+    price:  i64,
+    conf:   i64,
+    weight: u64,
+    index:  usize,
+}
+
+/// Same as `upd_aggregate`, computing the aggregate price and confidence of the valid quotes
+/// with `mode`. The quotes that are valid, the minimum number of publishers and the corporate
+/// actions don't depend on the mode, and `AggregationMode::Model` matches the C code.
+///
+/// If the outlier filter of the price account is set, the valid quotes more than
+/// `outlier_mads` median absolute deviations from their median price are excluded first: they
+/// don't count in `num_qt_`, and their components are set in
+/// `PriceAccount::excluded_components`. If the widening of stale confidences is set, the
+/// confidence of each component is widened with `widen_conf` before the quotes are checked, and
+/// the `agg_` of the component has the widened confidence. The C code does neither.
+pub fn upd_aggregate_with_mode(
+    price_account: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    mode: AggregationMode,
+) -> bool {
+    aggregate(price_account, slot, timestamp, mode, None)
+}
+
+/// Same as `upd_aggregate_with_mode` with the mode of `price_account`, weighting the samples of
+/// each component of the model by `weights`, in the order of `comp_`. Components beyond
+/// `weights` have a weight of 0. If no valid quote has a positive weight, the model is
+/// unweighted, and equal weights give the same aggregate as the unweighted model. Only
+/// `AggregationMode::Model` uses the weights.
+pub fn upd_aggregate_with_weights(
+    price_account: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    weights: &[u64],
+) -> bool {
+    let mode = price_account.price_cumulative.aggregation.mode();
+    aggregate(price_account, slot, timestamp, mode, Some(weights))
+}
+
+fn aggregate(
+    price_account: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    mode: AggregationMode,
+    weights: Option<&[u64]>,
+) -> bool {
+    // Update the value of the previous price, if it had TRADING status.
+    if price_account.agg_.status_ == PC_STATUS_TRADING {
+        price_account.prev_slot_ = price_account.agg_.pub_slot_;
+        price_account.prev_price_ = price_account.agg_.price_;
+        price_account.prev_conf_ = price_account.agg_.conf_;
+        price_account.prev_timestamp_ = price_account.timestamp_;
+    }
+
+    // update aggregate details ready for next slot
+    price_account.valid_slot_ = price_account.agg_.pub_slot_;
+    price_account.agg_.pub_slot_ = slot;
+    price_account.timestamp_ = timestamp;
+
+    let max_latency = if price_account.max_latency_ != 0 {
+        i64::from(price_account.max_latency_)
+    } else {
+        i64::from(PC_MAX_SEND_LATENCY)
+    };
+
+    // identify valid quotes
+    let mut numv: usize = 0;
+    let mut quotes = [Quote::default(); PC_NUM_COMP as usize];
+    // `num_` never exceeds `PC_NUM_COMP`, the size of `comp_` in the C struct
+    let num_comp = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    let widen_stale_conf = price_account.price_cumulative.aggregation.widen_stale_conf != 0;
+    for (i, component) in price_account.comp_[..num_comp].iter_mut().enumerate() {
+        // copy contributing price to aggregate snapshot
+        component.agg_ = component.latest_;
+        let slot_diff = (slot as i64).wrapping_sub(component.agg_.pub_slot_ as i64);
+        if widen_stale_conf {
+            component.agg_.conf_ = widen_conf(component.agg_.conf_, slot_diff);
+        }
+        let price = component.agg_.price_;
+        let conf = component.agg_.conf_ as i64;
+        // These checks ensure that price - conf and price + conf do not overflow
+        if component.agg_.status_ == PC_STATUS_TRADING
+            && 0 < conf
+            && i64::MIN + conf <= price
+            && price <= i64::MAX - conf
+            && slot_diff <= max_latency
+        {
+            let weight = match weights {
+                Some(weights) => weights.get(i).copied().unwrap_or(0),
+                None => 1,
+            };
+            quotes[numv] = Quote {
+                price,
+                conf,
+                weight,
+                index: i,
+            };
+            numv += 1;
+        }
+    }
+
+    // exclude outliers
+    let outlier_mads = price_account.price_cumulative.aggregation.outlier_mads;
+    let mut excluded_components = 0u64;
+    if outlier_mads != 0 && numv != 0 {
+        let numk = reject_outliers(&mut quotes[..numv], outlier_mads);
+        for quote in &quotes[numk..numv] {
+            excluded_components |= 1 << quote.index;
+        }
+        numv = numk;
+    }
+    price_account.excluded_components = excluded_components;
+
+    let mut ncorp = [0u32; PC_CORP_ACT_NUM_FLAGS as usize];
+    for quote in &quotes[..numv] {
+        let corp_act_status = price_account.comp_[quote.index].agg_.corp_act_status_;
+        for (j, count) in ncorp.iter_mut().enumerate() {
+            *count += (corp_act_status >> j) & 1;
+        }
+    }
+
+    // too few valid quotes
+    price_account.num_qt_ = numv as u32;
+    if numv == 0 || numv < usize::from(price_account.min_pub_) {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        price_account.agg_.corp_act_status_ = PC_CORP_ACT_NONE;
+        return false;
+    }
+
+    let quotes = &quotes[..numv];
+    let (agg_price, agg_conf) = match mode {
+        AggregationMode::Model => match weights {
+            Some(_) if quotes.iter().any(|quote| quote.weight > 0) => weighted_model(quotes),
+            _ => model(quotes),
+        },
+        AggregationMode::Median => weighted_median_model(quotes, |_| 1),
+        AggregationMode::WeightedMedian => {
+            weighted_median_model(quotes, |quote| u64::MAX / quote.conf as u64)
+        }
+        AggregationMode::TrimmedMean => trimmed_mean_model(quotes),
+    };
+    if agg_conf <= 0 {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        price_account.agg_.corp_act_status_ = PC_CORP_ACT_NONE;
+        return false;
+    }
+
+    // a corporate action is part of the aggregate if a strict majority of the valid quotes
+    // report it
+    let mut agg_corp_act = PC_CORP_ACT_NONE;
+    for (j, count) in ncorp.iter().enumerate() {
+        if 2 * *count as usize > numv {
+            agg_corp_act |= 1 << j;
+        }
+    }
+
+    // update status and publish slot of last trading status price
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.last_slot_ = slot;
+    price_account.agg_.price_ = agg_price;
+    price_account.agg_.conf_ = agg_conf as u64;
+    price_account.agg_.corp_act_status_ = agg_corp_act;
+    true
+}
+
+/// `decay_` of `qset_new`: the square roots of 0 to `PC_MAX_SEND_LATENCY`, times
+/// `CONF_DECAY[1]`, except for 0 which is the same as 1.
+const CONF_DECAY: [u64; PC_MAX_SEND_LATENCY as usize + 1] = [
+    1000000000, 1000000000, 1414213562, 1732050807, 2000000000, 2236067977, 2449489742, 2645751311,
+    2828427124, 3000000000, 3162277660, 3316624790, 3464101615, 3605551275, 3741657386, 3872983346,
+    4000000000, 4123105625, 4242640687, 4358898943, 4472135954, 4582575694, 4690415759, 4795831523,
+    4898979485, 5000000000,
+];
+
+/// Widen `conf`, the confidence of a quote published `slot_diff` slots ago, by the square root
+/// of its age: it stays the same up to 1 slot old, and is at most 5 times larger from
+/// `PC_MAX_SEND_LATENCY` slots old. Saturates at `u64::MAX`, which makes the quote invalid.
+pub fn widen_conf(conf: u64, slot_diff: i64) -> u64 {
+    let age = slot_diff.clamp(0, i64::from(PC_MAX_SEND_LATENCY)) as usize;
+    let widened = u128::from(conf) * u128::from(CONF_DECAY[age]) / u128::from(CONF_DECAY[1]);
+    u64::try_from(widened).unwrap_or(u64::MAX)
+}
+
+// The outlier filter and the models below get their own stack frames, since each of them needs
+// an array of samples.
+
+/// Move the quotes whose price is at most `k` median absolute deviations from the median price
+/// to the front of `quotes`, in their order, and return how many there are. The median and the
+/// deviations are computed exactly, without the rounding of `price_model_core`. At least half
+/// of the quotes are kept, since `k` is at least 1, and all of them if the median absolute
+/// deviation is 0, as when most prices are equal. `quotes` must not be empty.
+#[inline(never)]
+fn reject_outliers(quotes: &mut [Quote], k: u8) -> usize {
+    // twice the median, and twice the deviations from it
+    let mut prices = [0i64; PC_NUM_COMP as usize];
+    for (price, quote) in prices.iter_mut().zip(quotes.iter()) {
+        *price = quote.price;
+    }
+    let prices = &mut prices[..quotes.len()];
+    prices.sort_unstable();
+    let median2 = i128::from(prices[(prices.len() - 1) / 2]) + i128::from(prices[prices.len() / 2]);
+    let deviation2 = |quote: &Quote| (2 * i128::from(quote.price) - median2).unsigned_abs();
+
+    // four times the median absolute deviation
+    let mut deviations = [0u128; PC_NUM_COMP as usize];
+    for (deviation, quote) in deviations.iter_mut().zip(quotes.iter()) {
+        *deviation = deviation2(quote);
+    }
+    let deviations = &mut deviations[..quotes.len()];
+    deviations.sort_unstable();
+    let mad4 = deviations[(deviations.len() - 1) / 2] + deviations[deviations.len() / 2];
+    if mad4 == 0 {
+        // Any other price would be an outlier, however close to the median
+        return quotes.len();
+    }
+
+    let mut numk = 0;
+    for i in 0..quotes.len() {
+        if 2 * deviation2(&quotes[i]) <= u128::from(k) * mad4 {
+            quotes.swap(numk, i);
+            numk += 1;
+        }
+    }
+    numk
+}
+
+/// The model of the C code: the p50 of the prices minus, at and plus the confidence of the
+/// quotes, with the larger distance from it to the p25 or p75 as the confidence.
+#[inline(never)]
+fn model(quotes: &[Quote]) -> (i64, i64) {
+    let mut prcs = [0i64; PC_NUM_COMP as usize * 3];
+    for (samples, quote) in prcs.chunks_exact_mut(3).zip(quotes) {
+        samples[0] = quote.price - quote.conf;
+        samples[1] = quote.price;
+        samples[2] = quote.price + quote.conf;
+    }
+
+    // evaluate the model to get the p25/p50/p75 prices
+    let (agg_p25, agg_price, agg_p75) = price_model_core(&mut prcs[..quotes.len() * 3]);
+
+    // use the larger of the left and right confidences
+    let agg_conf_left = agg_price.wrapping_sub(agg_p25);
+    let agg_conf_right = agg_p75.wrapping_sub(agg_price);
+    (agg_price, agg_conf_left.max(agg_conf_right))
+}
+
+/// Same as `model`, with the samples of each quote weighted by its weight. Only the validator
+/// aggregates with weights, so the samples are allocated instead of taking stack space.
+fn weighted_model(quotes: &[Quote]) -> (i64, i64) {
+    let mut samples: Vec<(i64, u64)> = quotes
+        .iter()
+        .flat_map(|quote| {
+            [
+                (quote.price - quote.conf, quote.weight),
+                (quote.price, quote.weight),
+                (quote.price + quote.conf, quote.weight),
+            ]
+        })
+        .collect();
+
+    let (agg_p25, agg_price, agg_p75) = weighted_price_model_core(&mut samples);
+    let agg_conf_left = agg_price.wrapping_sub(agg_p25);
+    let agg_conf_right = agg_p75.wrapping_sub(agg_price);
+    (agg_price, agg_conf_left.max(agg_conf_right))
+}
+
+/// The weighted median of the prices, with the weighted median of the distances from it to the
+/// far end of the confidence interval of each quote as the confidence. The weights mustn't all
+/// be zero.
+#[inline(never)]
+fn weighted_median_model(quotes: &[Quote], weight: impl Fn(&Quote) -> u64) -> (i64, i64) {
+    let mut samples = [(0i64, 0u64); PC_NUM_COMP as usize];
+    let samples = &mut samples[..quotes.len()];
+
+    for (sample, quote) in samples.iter_mut().zip(quotes) {
+        *sample = (quote.price, weight(quote));
+    }
+    let agg_price = weighted_median(samples);
+
+    for (sample, quote) in samples.iter_mut().zip(quotes) {
+        *sample = (distance_to_interval(quote, agg_price), weight(quote));
+    }
+    (agg_price, weighted_median(samples))
+}
+
+/// The mean of the prices without the lowest and highest quarter of them, with the mean of the
+/// same quotes' distances from it to the far end of their confidence interval as the confidence.
+#[inline(never)]
+fn trimmed_mean_model(quotes: &[Quote]) -> (i64, i64) {
+    let mut samples = [Quote::default(); PC_NUM_COMP as usize];
+    let samples = &mut samples[..quotes.len()];
+    samples.copy_from_slice(quotes);
+    // Ordered by confidence among equal prices, so that the trimmed quotes don't depend on the
+    // order of the components
+    samples.sort_unstable_by_key(|quote| (quote.price, quote.conf));
+
+    // The same quotes as the p25 and p75 of `price_model_core`, so never empty
+    let trim = samples.len() >> 2;
+    let kept = &samples[trim..samples.len() - trim];
+    let agg_price = floor_mean(kept.iter().map(|quote| quote.price), kept.len());
+    let agg_conf = floor_mean(
+        kept.iter()
+            .map(|quote| distance_to_interval(quote, agg_price)),
+        kept.len(),
+    );
+    (agg_price, agg_conf)
+}
+
+/// `|quote.price - price| + quote.conf`, saturated to `i64::MAX`. Always positive.
+fn distance_to_interval(quote: &Quote, price: i64) -> i64 {
+    let distance = (i128::from(quote.price) - i128::from(price)).abs() + i128::from(quote.conf);
+    distance.min(i128::from(i64::MAX)) as i64
+}
+
+/// The floor of the mean of the `count` values, which can't overflow for up to `2^64` values.
+fn floor_mean(values: impl Iterator<Item = i64>, count: usize) -> i64 {
+    let sum: i128 = values.map(i128::from).sum();
+    sum.div_euclid(count as i128) as i64
+}
+
+/// Sort `samples`, pairs of a value and its weight, by value and return their weighted median:
+/// the first value where the cumulative weight reaches half of the total weight, or the floor of
+/// the average of that value and the next one with a positive weight if the cumulative weight is
+/// exactly half of the total. With equal weights, this is the median of `price_model_core`. The
+/// weights mustn't all be zero.
+pub fn weighted_median(samples: &mut [(i64, u64)]) -> i64 {
+    samples.sort_unstable_by_key(|&(value, _)| value);
+    let total: u128 = samples.iter().map(|&(_, weight)| u128::from(weight)).sum();
+
+    let mut cumulative: u128 = 0;
+    for (i, &(value, weight)) in samples.iter().enumerate() {
+        cumulative += u128::from(weight);
+        if 2 * cumulative == total {
+            // The remaining half of the weight is on later values
+            if let Some(&(next, _)) = samples[i + 1..].iter().find(|&&(_, weight)| weight > 0) {
+                return avg_2_int64(value, next);
+            }
+        }
+        if 2 * cumulative >= total {
+            return value;
+        }
+    }
+    // Unreachable with a positive total weight
+    samples[samples.len() - 1].0
+}
+
+/// Sort `samples`, pairs of a value and its weight, by value and return their weighted p25, p50
+/// and p75: the first value from the bottom and from the top where the cumulative weight
+/// exceeds a quarter of the total weight, and the `weighted_median`. With equal weights, these
+/// are the quantiles of `price_model_core`. The weights mustn't all be zero.
+pub fn weighted_price_model_core(samples: &mut [(i64, u64)]) -> (i64, i64, i64) {
+    let p50 = weighted_median(samples);
+    let total: u128 = samples.iter().map(|&(_, weight)| u128::from(weight)).sum();
+    let p25 = weighted_quartile(samples.iter(), total);
+    let p75 = weighted_quartile(samples.iter().rev(), total);
+    (p25, p50, p75)
+}
+
+/// The first of `samples` where the cumulative weight exceeds a quarter of `total`, their
+/// total weight.
+fn weighted_quartile<'a>(samples: impl Iterator<Item = &'a (i64, u64)>, total: u128) -> i64 {
+    let mut cumulative: u128 = 0;
+    let mut last = 0;
+    for &(value, weight) in samples {
+        cumulative += u128::from(weight);
+        last = value;
+        if 4 * cumulative > total {
+            break;
+        }
+    }
+    last
+}
+
+/// `price_model_core`: sort `quotes` and return their p25, p50 and p75. `quotes` must not be
+/// empty.
+pub fn price_model_core(quotes: &mut [i64]) -> (i64, i64, i64) {
+    // Equal integers are indistinguishable, so an unstable sort matches the C stable sort, and
+    // doesn't allocate.
+    quotes.sort_unstable();
+    let cnt = quotes.len();
+
+    let p25_idx = cnt >> 2;
+    let p25 = quotes[p25_idx];
+
+    let p50 = if cnt & 1 == 1 {
+        quotes[cnt >> 1]
+    } else {
+        avg_2_int64(quotes[(cnt >> 1) - 1], quotes[cnt >> 1])
+    };
+
+    let p75 = quotes[cnt - 1 - p25_idx];
+    (p25, p50, p75)
+}
+
+/// `avg_2_int64`: `floor((x + y) / 2)` without intermediate overflow.
+pub fn avg_2_int64(x: i64, y: i64) -> i64 {
+    (x >> 1) + (y >> 1) + (x & y & 1)
+}
+
+/// `upd_twap`: update the EMAs of the aggregate price and confidence, `nslots` after the
+/// previous aggregation.
+pub fn upd_twap(price_account: &mut PriceAccount, nslots: i64) {
+    let price = Pd::new_scale(price_account.agg_.price_, price_account.exponent);
+    let conf = Pd::new_scale(price_account.agg_.conf_ as i64, price_account.exponent);
+    upd_ema(
+        &mut price_account.twap_,
+        price,
+        conf,
+        nslots,
+        price_account.exponent,
+    );
+    upd_ema(
+        &mut price_account.twac_,
+        conf,
+        conf,
+        nslots,
+        price_account.exponent,
+    );
+}
+
+/// `upd_ema`: add `val`, weighted by the inverse of `conf`, to `ema`.
+pub fn upd_ema(ema: &mut PriceEma, val: Pd, conf: Pd, nslot: i64, expo: i32) {
+    let one = Pd::new(100_000_000, -8);
+    let cwgt = if conf.v != 0 { one.div(conf) } else { one };
+    let (numer, denom, val) = if nslot > PD_EMA_MAX_DIFF {
+        // initial condition
+        (val.mul(cwgt), cwgt, val)
+    } else {
+        // compute decay factor
+        let decay = Pd::new(PD_EMA_DECAY, PD_EMA_EXPO)
+            .mul(Pd::new(nslot, 0))
+            .add(one);
+
+        // compute numer/denom and new value from decay factor
+        let numer = Pd::load(ema.numer_).mul(decay).add(val.mul(cwgt));
+        let denom = Pd::load(ema.denom_).mul(decay).add(cwgt);
+        (numer, denom, numer.div(denom))
+    };
+
+    // adjust and store results
+    ema.val_ = val.adjust(expo).v;
+    if let (Some(numer), Some(denom)) = (numer.store(), denom.store()) {
+        ema.numer_ = numer;
+        ema.denom_ = denom;
+    }
+}
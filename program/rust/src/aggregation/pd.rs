@@ -0,0 +1,245 @@
+//! Decimal arithmetic of `pd.h`.
+//!
+//! A `Pd` stands for `v * 10^e`. Every operation reproduces its C counterpart exactly, including
+//! truncation and the wrapping of overflowing intermediate results, so that the Rust and C
+//! engines store the same bits.
+
+pub const PD_SCALE9: i64 = 1_000_000_000;
+/// Maximum number of slots between two EMA updates before the EMA is reset
+pub const PD_EMA_MAX_DIFF: i64 = 4145;
+/// Exponent of the EMA decay
+pub const PD_EMA_EXPO: i32 = -9;
+/// `1e9 * -ln(2) / 5921`
+pub const PD_EMA_DECAY: i64 = -117_065;
+pub const PC_FACTOR_SIZE: usize = 18;
+
+const EXP_BITS: u32 = 5;
+const EXP_MASK: i64 = (1 << EXP_BITS) - 1;
+
+/// Powers of 10, `qs->fact_` in `upd_aggregate.h`
+pub const FACTORS: [i64; PC_FACTOR_SIZE] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+];
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pd {
+    pub e: i32,
+    pub v: i64,
+}
+
+impl Pd {
+    /// `pd_new`
+    pub fn new(v: i64, e: i32) -> Self {
+        Pd { e, v }
+    }
+
+    /// `pd_new_scale`
+    pub fn new_scale(v: i64, e: i32) -> Self {
+        Pd::new(v, e).scale()
+    }
+
+    /// `pd_scale`: drop digits until the magnitude of `v` is below `2^28`.
+    pub fn scale(self) -> Self {
+        let neg = self.v < 0;
+        let mut v = if neg { self.v.wrapping_neg() } else { self.v };
+        let mut e = self.e;
+        while v >= 1 << 28 {
+            v /= 10;
+            e = e.wrapping_add(1);
+        }
+        Pd::new(if neg { v.wrapping_neg() } else { v }, e)
+    }
+
+    /// `pd_store`: pack into 59 bits of value and 5 bits of exponent, or `None` if it doesn't fit.
+    pub fn store(self) -> Option<i64> {
+        let mut v = self.v;
+        let mut e = self.e;
+        while v < -(1 << 58) {
+            v /= 10;
+            e = e.wrapping_add(1);
+        }
+        while v > (1 << 58) - 1 {
+            v /= 10;
+            e = e.wrapping_add(1);
+        }
+        while e < -(1 << (EXP_BITS - 1)) {
+            v /= 10;
+            e = e.wrapping_add(1);
+        }
+        while e > (1 << (EXP_BITS - 1)) - 1 {
+            v = v.wrapping_mul(10);
+            if !(-(1 << 58)..=(1 << 58) - 1).contains(&v) {
+                return None;
+            }
+            e -= 1;
+        }
+        Some((v << EXP_BITS) | (i64::from(e) & EXP_MASK))
+    }
+
+    /// `pd_load`: unpack a value packed by `store`.
+    pub fn load(n: i64) -> Self {
+        Pd::new(n >> EXP_BITS, (((n & EXP_MASK) << 59) >> 59) as i32).scale()
+    }
+
+    /// `pd_adjust`: express with exponent `e`, truncating dropped digits.
+    pub fn adjust(self, e: i32) -> Self {
+        let d = self.e.wrapping_sub(e);
+        let v = if d > 0 {
+            // Past its table of powers of 10, the C code reads the zeroed heap that follows it
+            self.v
+                .wrapping_mul(FACTORS.get(d as usize).copied().unwrap_or(0))
+        } else if d < 0 {
+            // Where the C code would divide by the zeroed heap, use the exact result
+            match FACTORS.get(d.unsigned_abs() as usize) {
+                Some(factor) => self.v / factor,
+                None => (0..d.unsigned_abs()).fold(self.v, |v, _| v / 10),
+            }
+        } else {
+            self.v
+        };
+        Pd::new(v, e)
+    }
+
+    /// `pd_mul`
+    pub fn mul(self, other: Pd) -> Self {
+        Pd::new(self.v.wrapping_mul(other.v), self.e.wrapping_add(other.e)).scale()
+    }
+
+    /// `pd_div`
+    pub fn div(self, other: Pd) -> Self {
+        if self.v == 0 {
+            return self;
+        }
+        let neg1 = self.v < 0;
+        let neg2 = other.v < 0;
+        let mut v1 = if neg1 { self.v.wrapping_neg() } else { self.v };
+        let v2 = if neg2 {
+            other.v.wrapping_neg()
+        } else {
+            other.v
+        };
+        let mut m: i32 = 0;
+        while (v1 as u64) & 0xffff_ffff_f000_0000 == 0 {
+            v1 = v1.wrapping_mul(10);
+            m += 1;
+        }
+        let mut v = v1.wrapping_mul(PD_SCALE9).wrapping_div(v2);
+        if neg1 {
+            v = v.wrapping_neg();
+        }
+        if neg2 {
+            v = v.wrapping_neg();
+        }
+        Pd::new(
+            v,
+            self.e.wrapping_sub(other.e).wrapping_sub(m).wrapping_sub(9),
+        )
+        .scale()
+    }
+
+    /// `pd_add`
+    pub fn add(self, other: Pd) -> Self {
+        let d = self.e.wrapping_sub(other.e);
+        let r = if d == 0 {
+            Pd::new(self.v.wrapping_add(other.v), self.e)
+        } else if d > 0 {
+            if d < 9 {
+                Pd::new(
+                    self.v
+                        .wrapping_mul(FACTORS[d as usize])
+                        .wrapping_add(other.v),
+                    other.e,
+                )
+            } else if d < PC_FACTOR_SIZE as i32 + 9 {
+                Pd::new(
+                    self.v
+                        .wrapping_mul(PD_SCALE9)
+                        .wrapping_add(other.v / FACTORS[d as usize - 9]),
+                    self.e.wrapping_sub(9),
+                )
+            } else {
+                self
+            }
+        } else {
+            let d = d.wrapping_neg();
+            if (0..9).contains(&d) {
+                Pd::new(
+                    self.v
+                        .wrapping_add(other.v.wrapping_mul(FACTORS[d as usize])),
+                    self.e,
+                )
+            } else if (0..PC_FACTOR_SIZE as i32 + 9).contains(&d) {
+                Pd::new(
+                    (self.v / FACTORS[d as usize - 9])
+                        .wrapping_add(other.v.wrapping_mul(PD_SCALE9)),
+                    other.e.wrapping_sub(9),
+                )
+            } else {
+                other
+            }
+        };
+        r.scale()
+    }
+
+    /// `pd_sub`
+    pub fn sub(self, other: Pd) -> Self {
+        let d = self.e.wrapping_sub(other.e);
+        let r = if d == 0 {
+            Pd::new(self.v.wrapping_sub(other.v), self.e)
+        } else if d > 0 {
+            if d < 9 {
+                Pd::new(
+                    self.v
+                        .wrapping_mul(FACTORS[d as usize])
+                        .wrapping_sub(other.v),
+                    other.e,
+                )
+            } else if d < PC_FACTOR_SIZE as i32 + 9 {
+                Pd::new(
+                    self.v
+                        .wrapping_mul(PD_SCALE9)
+                        .wrapping_sub(other.v / FACTORS[d as usize - 9]),
+                    self.e.wrapping_sub(9),
+                )
+            } else {
+                self
+            }
+        } else {
+            let d = d.wrapping_neg();
+            if (0..9).contains(&d) {
+                Pd::new(
+                    self.v
+                        .wrapping_sub(other.v.wrapping_mul(FACTORS[d as usize])),
+                    self.e,
+                )
+            } else if (0..PC_FACTOR_SIZE as i32 + 9).contains(&d) {
+                Pd::new(
+                    (self.v / FACTORS[d as usize - 9])
+                        .wrapping_sub(other.v.wrapping_mul(PD_SCALE9)),
+                    other.e.wrapping_sub(9),
+                )
+            } else {
+                Pd::new(other.v.wrapping_neg(), other.e)
+            }
+        };
+        r.scale()
+    }
+}
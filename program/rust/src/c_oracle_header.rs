@@ -4,7 +4,16 @@
 #![allow(dead_code)]
 
 // Bindings.rs is generated by build.rs to include things defined in bindings.h
+#[cfg(not(feature = "rust-aggregation"))]
 include!("../codegen/bindings.rs");
+#[cfg(feature = "rust-aggregation")]
+include!("c_oracle_header/constants.rs");
+
+/// The constants used with the `rust-aggregation` feature, to check them against the bindings
+#[cfg(all(test, not(feature = "rust-aggregation")))]
+pub mod checked_in {
+    include!("c_oracle_header/constants.rs");
+}
 
 /// If ci > price / PC_MAX_CI_DIVISOR, set publisher status to unknown.
 /// (e.g., 20 means ci must be < 5% of price)
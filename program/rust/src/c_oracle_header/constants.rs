@@ -0,0 +1,35 @@
+// The constants of `bindings.h` used by the Rust code, for builds with the `rust-aggregation`
+// feature, which don't generate the bindings since they don't need the C toolchain. They must
+// match `../c/src/oracle/oracle.h`, which `test_c_code::test_checked_in_constants` checks.
+
+pub type size_t = ::std::os::raw::c_ulong;
+
+pub const PC_MAGIC: u32 = 2712847316;
+pub const PC_VERSION: u32 = 2;
+pub const PC_MAX_SEND_LATENCY: u32 = 25;
+pub const PC_MAP_TABLE_SIZE: u32 = 5000;
+pub const PC_NUM_COMP_PYTHNET: u32 = 128;
+pub const PC_NUM_COMP: u32 = 64;
+pub const PC_PROD_ACC_SIZE: u32 = 512;
+pub const PC_PTYPE_UNKNOWN: u32 = 0;
+pub const PC_PTYPE_PRICE: u32 = 1;
+pub const PC_STATUS_UNKNOWN: u32 = 0;
+pub const PC_STATUS_TRADING: u32 = 1;
+pub const PC_STATUS_HALTED: u32 = 2;
+pub const PC_STATUS_AUCTION: u32 = 3;
+pub const PC_STATUS_IGNORED: u32 = 4;
+pub const PC_CORP_ACT_NONE: u32 = 0;
+pub const PC_CORP_ACT_SPLIT: u32 = 1;
+pub const PC_CORP_ACT_DIVIDEND: u32 = 2;
+pub const PC_CORP_ACT_SYMBOL_CHANGE: u32 = 4;
+pub const PC_CORP_ACT_ALL: u32 = 7;
+pub const PC_CORP_ACT_NUM_FLAGS: u32 = 3;
+pub const PC_ACCTYPE_MAPPING: u32 = 1;
+pub const PC_ACCTYPE_PRODUCT: u32 = 2;
+pub const PC_ACCTYPE_PRICE: u32 = 3;
+pub const PC_ACCTYPE_PERMISSIONS: u32 = 5;
+pub const PC_ACCTYPE_DELEGATION: u32 = 6;
+pub const PC_ACCTYPE_SCHEDULE: u32 = 7;
+pub const ZSTD_UPPER_BOUND: u64 = 12576;
+pub const PC_PRICE_T_COMP_OFFSET: size_t = 240;
+pub const PC_MAP_TABLE_T_PROD_OFFSET: size_t = 56;
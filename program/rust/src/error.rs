@@ -5,7 +5,7 @@ use {
 };
 
 /// Errors that may be returned by the oracle program
-#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
 pub enum OracleError {
     /// Generic catch all error
     #[error("Generic")]
@@ -58,6 +58,404 @@ pub enum OracleError {
     FeedIndexAlreadyInitialized    = 622,
     #[error("NoNeedToResize")]
     NoNeedToResize                 = 623,
+    #[error("DuplicatePublisher")]
+    DuplicatePublisher             = 624,
+    #[error("TooManyPublishers")]
+    TooManyPublishers              = 625,
+    #[error("PublisherNotFound")]
+    PublisherNotFound              = 626,
+    #[error("StalePublishingSlot")]
+    StalePublishingSlot            = 627,
+    #[error("FuturePublishingSlot")]
+    FuturePublishingSlot           = 628,
+    #[error("ProductHasPriceAccounts")]
+    ProductHasPriceAccounts        = 629,
+    #[error("ProductNotInMapping")]
+    ProductNotInMapping            = 630,
+    #[error("MappingAccountFull")]
+    MappingAccountFull             = 631,
+    #[error("PriceTypeMismatch")]
+    PriceTypeMismatch              = 632,
+    #[error("InvalidPriceType")]
+    InvalidPriceType               = 633,
+    #[error("InvalidExponent")]
+    InvalidExponent                = 634,
+    #[error("PriceAccountNotFirst")]
+    PriceAccountNotFirst           = 635,
+    #[error("ProductAccountMismatch")]
+    ProductAccountMismatch         = 636,
+    #[error("InvalidProductMetadata")]
+    InvalidProductMetadata         = 637,
+    #[error("InvalidPriceFlags")]
+    InvalidPriceFlags              = 638,
+    #[error("TooManyDelegates")]
+    TooManyDelegates               = 639,
+    #[error("DelegateNotFound")]
+    DelegateNotFound               = 640,
+    #[error("InvalidBatchSize")]
+    InvalidBatchSize               = 641,
+    #[error("InvalidCompactUpdate")]
+    InvalidCompactUpdate           = 642,
+    #[error("InvalidSchedule")]
+    InvalidSchedule                = 643,
+    /// No longer returned, unknown corporate-action flags are ignored
+    #[error("InvalidCorpActStatus")]
+    InvalidCorpActStatus           = 644,
+    #[error("InvalidAggregationConfig")]
+    InvalidAggregationConfig       = 645,
+    #[error("InvalidSetPublishersMode")]
+    InvalidSetPublishersMode       = 646,
+    #[error("InvalidPublisherList")]
+    InvalidPublisherList           = 647,
+    #[error("InvalidPublisherKey")]
+    InvalidPublisherKey            = 648,
+    #[error("RemoveInExactMode")]
+    RemoveInExactMode              = 649,
+    #[error("ScheduleAccountRequired")]
+    ScheduleAccountRequired        = 650,
+}
+
+impl OracleError {
+    /// Every error, in the order of their codes.
+    pub const ALL: [OracleError; 51] = [
+        OracleError::Generic,
+        OracleError::IntegerCastingError,
+        OracleError::UnknownCError,
+        OracleError::UnrecognizedInstruction,
+        OracleError::InvalidFundingAccount,
+        OracleError::InvalidSignableAccount,
+        OracleError::InvalidSystemAccount,
+        OracleError::InvalidWritableAccount,
+        OracleError::InvalidFreshAccount,
+        OracleError::InvalidInstructionVersion,
+        OracleError::InstructionDataTooShort,
+        OracleError::InstructionDataSliceMisaligned,
+        OracleError::AccountTooSmall,
+        OracleError::DeserializationError,
+        OracleError::InvalidUpgradeAuthority,
+        OracleError::InvalidPda,
+        OracleError::InvalidAccountHeader,
+        OracleError::InvalidNumberOfAccounts,
+        OracleError::InvalidReadableAccount,
+        OracleError::PermissionViolation,
+        OracleError::NeedsSuccesfulAggregation,
+        OracleError::MaxLastFeedIndexReached,
+        OracleError::FeedIndexAlreadyInitialized,
+        OracleError::NoNeedToResize,
+        OracleError::DuplicatePublisher,
+        OracleError::TooManyPublishers,
+        OracleError::PublisherNotFound,
+        OracleError::StalePublishingSlot,
+        OracleError::FuturePublishingSlot,
+        OracleError::ProductHasPriceAccounts,
+        OracleError::ProductNotInMapping,
+        OracleError::MappingAccountFull,
+        OracleError::PriceTypeMismatch,
+        OracleError::InvalidPriceType,
+        OracleError::InvalidExponent,
+        OracleError::PriceAccountNotFirst,
+        OracleError::ProductAccountMismatch,
+        OracleError::InvalidProductMetadata,
+        OracleError::InvalidPriceFlags,
+        OracleError::TooManyDelegates,
+        OracleError::DelegateNotFound,
+        OracleError::InvalidBatchSize,
+        OracleError::InvalidCompactUpdate,
+        OracleError::InvalidSchedule,
+        OracleError::InvalidCorpActStatus,
+        OracleError::InvalidAggregationConfig,
+        OracleError::InvalidSetPublishersMode,
+        OracleError::InvalidPublisherList,
+        OracleError::InvalidPublisherKey,
+        OracleError::RemoveInExactMode,
+        OracleError::ScheduleAccountRequired,
+    ];
+
+    /// The error returned as `ProgramError::Custom(code)`, if `code` belongs to the oracle.
+    pub fn from_custom_code(code: u32) -> Option<OracleError> {
+        OracleError::ALL.iter().copied().find(|e| *e as u32 == code)
+    }
+
+    /// The error of a failed transaction, given one of its log messages. The runtime logs custom
+    /// errors as `Program <id> failed: custom program error: 0x<code>`.
+    pub fn from_log_message(message: &str) -> Option<OracleError> {
+        let (_, code) = message.split_once("custom program error: 0x")?;
+        let code = code.split_whitespace().next()?;
+        OracleError::from_custom_code(u32::from_str_radix(code, 16).ok()?)
+    }
+
+    /// What went wrong, in a sentence.
+    pub fn description(&self) -> &'static str {
+        match self {
+            OracleError::Generic => "The oracle program failed for an unspecified reason.",
+            OracleError::IntegerCastingError => {
+                "A value didn't fit in the integer type it was converted to."
+            }
+            OracleError::UnknownCError => "The C aggregation code returned an unexpected value.",
+            OracleError::UnrecognizedInstruction => {
+                "The command in the instruction header isn't an oracle instruction."
+            }
+            OracleError::InvalidFundingAccount => {
+                "The funding account isn't a writable signer owned by the system program."
+            }
+            OracleError::InvalidSignableAccount => {
+                "An account that must sign the transaction didn't."
+            }
+            OracleError::InvalidSystemAccount => "The system program account is incorrect.",
+            OracleError::InvalidWritableAccount => {
+                "An account that must be writable and owned by the oracle program isn't, or isn't \
+                 rent exempt."
+            }
+            OracleError::InvalidFreshAccount => {
+                "An account that should be new already contains oracle data."
+            }
+            OracleError::InvalidInstructionVersion => {
+                "The instruction header has a version other than the program's."
+            }
+            OracleError::InstructionDataTooShort => {
+                "The instruction data is shorter than the instruction's arguments."
+            }
+            OracleError::InstructionDataSliceMisaligned => {
+                "The instruction data isn't aligned for the instruction's arguments."
+            }
+            OracleError::AccountTooSmall => "An account is smaller than the data stored in it.",
+            OracleError::DeserializationError => "An account or instruction couldn't be parsed.",
+            OracleError::InvalidUpgradeAuthority => {
+                "The signer isn't the upgrade authority of the oracle program."
+            }
+            OracleError::InvalidPda => {
+                "An account isn't the program derived address the instruction expects."
+            }
+            OracleError::InvalidAccountHeader => {
+                "An account has the wrong magic number, version or account type."
+            }
+            OracleError::InvalidNumberOfAccounts => {
+                "The instruction was given the wrong number of accounts."
+            }
+            OracleError::InvalidReadableAccount => {
+                "An account that must be readable by the oracle program isn't."
+            }
+            OracleError::PermissionViolation => {
+                "The signer isn't allowed to perform this instruction on this account."
+            }
+            OracleError::NeedsSuccesfulAggregation => {
+                "The price account has never had a successful aggregation."
+            }
+            OracleError::MaxLastFeedIndexReached => "Every feed index has been assigned.",
+            OracleError::FeedIndexAlreadyInitialized => {
+                "The price account already has a feed index."
+            }
+            OracleError::NoNeedToResize => "The mapping account already has its final size.",
+            OracleError::DuplicatePublisher => {
+                "The publisher is already in the price account's publisher list."
+            }
+            OracleError::TooManyPublishers => "The price account's publisher list is full.",
+            OracleError::PublisherNotFound => {
+                "The publisher isn't in the price account's publisher list."
+            }
+            OracleError::StalePublishingSlot => {
+                "The publishing slot isn't more recent than the publisher's latest price."
+            }
+            OracleError::FuturePublishingSlot => "The publishing slot is after the current slot.",
+            OracleError::ProductHasPriceAccounts => "The product account still has price accounts.",
+            OracleError::ProductNotInMapping => {
+                "The product account isn't listed in the mapping account."
+            }
+            OracleError::MappingAccountFull => "The mapping account's product list is full.",
+            OracleError::PriceTypeMismatch => {
+                "The price type doesn't match the price account's price type."
+            }
+            OracleError::InvalidPriceType => "The price type is unknown.",
+            OracleError::InvalidExponent => "The exponent is outside the permitted range.",
+            OracleError::PriceAccountNotFirst => {
+                "The price account isn't the first price account of the product."
+            }
+            OracleError::ProductAccountMismatch => {
+                "The price account belongs to a different product account."
+            }
+            OracleError::InvalidProductMetadata => {
+                "The product metadata isn't a list of key/value strings that fits in the account."
+            }
+            OracleError::InvalidPriceFlags => {
+                "The flags are unknown, set and cleared at once, or would leave the price account \
+                 in an invalid state."
+            }
+            OracleError::TooManyDelegates => {
+                "The delegation account already lists the maximum number of delegates."
+            }
+            OracleError::DelegateNotFound => "The delegate isn't listed in the delegation account.",
+            OracleError::InvalidBatchSize => {
+                "The batch is empty, too large, or its data isn't a whole number of entries."
+            }
+            OracleError::InvalidCompactUpdate => {
+                "The compact update isn't 4 canonical varints, or its publishing slot is before \
+                 slot 0."
+            }
+            OracleError::InvalidSchedule => {
+                "The schedule has too many entries, a session or holiday outside of its week or \
+                 day, unsorted holidays, or an offset from UTC beyond MAX_UTC_OFFSET."
+            }
+            OracleError::InvalidCorpActStatus => {
+                "The corporate-action status of the price has bits outside of the PC_CORP_ACT_* \
+                 flags. No longer returned, these bits are now ignored."
+            }
+            OracleError::InvalidAggregationConfig => {
+                "The aggregation config has a mode that isn't an AggregationMode, a \
+                 widen_stale_conf other than 0 or 1, or unused bytes that aren't 0, or isn't the \
+                 model of the C code on a program built without the Rust aggregation engine."
+            }
+            OracleError::InvalidSetPublishersMode => {
+                "The mode of SetPublishers isn't a SetPublishersMode."
+            }
+            OracleError::InvalidPublisherList => {
+                "The publishers of SetPublishers aren't a whole number of keys, or not as many as \
+                 the number of publishers to add and remove."
+            }
+            OracleError::InvalidPublisherKey => {
+                "The publisher is the default key or one of the keys reserved to enable and \
+                 disable ACCUMULATOR_V2."
+            }
+            OracleError::RemoveInExactMode => {
+                "SetPublishers in the exact mode lists publishers to remove."
+            }
+            OracleError::ScheduleAccountRequired => {
+                "The price account has the SCHEDULED flag but the update doesn't provide its \
+                 schedule account."
+            }
+        }
+    }
+
+    /// What the sender of the transaction can do about it.
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            OracleError::Generic | OracleError::UnknownCError => {
+                "Inspect the transaction logs and report the failure to the oracle maintainers."
+            }
+            OracleError::IntegerCastingError => {
+                "Check that the values in the instruction are within range."
+            }
+            OracleError::UnrecognizedInstruction
+            | OracleError::InvalidInstructionVersion
+            | OracleError::InstructionDataTooShort
+            | OracleError::InstructionDataSliceMisaligned
+            | OracleError::DeserializationError => {
+                "Build the instruction with a client that matches the deployed program version."
+            }
+            OracleError::InvalidFundingAccount => {
+                "Pass a system-owned signer as the funding account and mark it writable."
+            }
+            OracleError::InvalidSignableAccount => "Have the account sign the transaction.",
+            OracleError::InvalidSystemAccount => "Pass the system program account.",
+            OracleError::InvalidWritableAccount => {
+                "Mark the account writable and check that it's an oracle account with enough \
+                 lamports to be rent exempt."
+            }
+            OracleError::InvalidFreshAccount => {
+                "Use a new, zeroed account owned by the oracle program."
+            }
+            OracleError::AccountTooSmall | OracleError::NoNeedToResize => {
+                "Check the account's size against the size the program expects."
+            }
+            OracleError::InvalidUpgradeAuthority => {
+                "Sign with the oracle program's upgrade authority."
+            }
+            OracleError::InvalidPda | OracleError::InvalidReadableAccount => {
+                "Derive the account address with the seeds the instruction expects."
+            }
+            OracleError::InvalidAccountHeader => {
+                "Check that each account is passed in the position the instruction expects."
+            }
+            OracleError::InvalidNumberOfAccounts => {
+                "Pass the accounts listed in the instruction's documentation."
+            }
+            OracleError::PermissionViolation => {
+                "Sign with the authority of the account, or add the publisher to the price account."
+            }
+            OracleError::NeedsSuccesfulAggregation => {
+                "Wait for the price account to aggregate a price before retrying."
+            }
+            OracleError::MaxLastFeedIndexReached => "Report the failure to the oracle maintainers.",
+            OracleError::FeedIndexAlreadyInitialized => "No action is needed.",
+            OracleError::DuplicatePublisher => "No action is needed, the publisher is already set.",
+            OracleError::TooManyPublishers => {
+                "Remove a publisher from the price account before adding a new one."
+            }
+            OracleError::PublisherNotFound => {
+                "Check the publisher key, it may already have been removed."
+            }
+            OracleError::StalePublishingSlot => {
+                "Publish with a slot after the one of the publisher's latest price."
+            }
+            OracleError::FuturePublishingSlot => {
+                "Publish with a slot that isn't after the current slot."
+            }
+            OracleError::ProductHasPriceAccounts => {
+                "Delete the product's price accounts before deleting the product account."
+            }
+            OracleError::ProductNotInMapping => {
+                "Pass the mapping account that lists the product account."
+            }
+            OracleError::MappingAccountFull => {
+                "Resize the mapping account or add the product to another mapping account."
+            }
+            OracleError::PriceTypeMismatch => "Pass the price type of the price account.",
+            OracleError::InvalidPriceType => "Use a known price type such as price.",
+            OracleError::InvalidExponent => "Use an exponent within the permitted range.",
+            OracleError::PriceAccountNotFirst => {
+                "Delete the product's price accounts in order, starting from the first one."
+            }
+            OracleError::ProductAccountMismatch => {
+                "Pass the product account that the price account points to."
+            }
+            OracleError::InvalidProductMetadata => {
+                "Encode each key and value as a length-prefixed string of at most 255 bytes and \
+                 keep the metadata within the account size."
+            }
+            OracleError::InvalidPriceFlags => {
+                "Only set flags that can be changed by instruction, don't both set and clear a flag \
+                 and clear MESSAGE_BUFFER_CLEARED together with ACCUMULATOR_V2."
+            }
+            OracleError::TooManyDelegates => "Remove an unused or expired delegate first.",
+            OracleError::DelegateNotFound => {
+                "Check the delegate key and that the delegation account belongs to the publisher."
+            }
+            OracleError::InvalidBatchSize => {
+                "Send between 1 and MAX_UPD_PRICE_BATCH_SIZE entries after the command header."
+            }
+            OracleError::InvalidCompactUpdate => {
+                "Encode the update with CompactUpdPrice against the latest component price."
+            }
+            OracleError::InvalidSchedule => {
+                "Check the schedule with ScheduleAccount::is_valid before sending it."
+            }
+            OracleError::InvalidCorpActStatus => {
+                "Report corporate actions as a combination of PC_CORP_ACT_SPLIT, \
+                 PC_CORP_ACT_DIVIDEND and PC_CORP_ACT_SYMBOL_CHANGE, or PC_CORP_ACT_NONE."
+            }
+            OracleError::InvalidAggregationConfig => {
+                "Build the config with AggregationConfig::new, and only select another model than \
+                 the C one on a program built with the rust-aggregation feature."
+            }
+            OracleError::InvalidSetPublishersMode => {
+                "Use SetPublishersMode::Update or SetPublishersMode::Exact."
+            }
+            OracleError::InvalidPublisherList => {
+                "Build the instruction with builders::set_publishers or \
+                 builders::set_exact_publishers."
+            }
+            OracleError::InvalidPublisherKey => {
+                "Use the key of the publisher, and SetPriceFlags to change ACCUMULATOR_V2."
+            }
+            OracleError::RemoveInExactMode => {
+                "List the publishers to keep as publishers to add, the others are removed."
+            }
+            OracleError::ScheduleAccountRequired => {
+                "Pass the schedule account after the delegation account, see \
+                 builders::upd_price_scheduled. UpdPriceBatch can't update scheduled price \
+                 accounts."
+            }
+        }
+    }
 }
 
 impl From<OracleError> for ProgramError {
@@ -0,0 +1,430 @@
+//! Events emitted by the oracle program with `sol_log_data`.
+//!
+//! Each event is logged as a single data field made of a version byte, a byte identifying the
+//! kind of event and the little endian encoding of the event's fields, in declaration order. The
+//! runtime shows data fields in the transaction logs as `Program data: <base64>`, which
+//! `parse_events` turns back into events.
+#[cfg(any(test, feature = "library"))]
+use crate::error::OracleError;
+use solana_program::{
+    log::sol_log_data,
+    pubkey::Pubkey,
+};
+
+/// Version of the event layout, stored in the first byte of every event.
+pub const EVENT_VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A new aggregate price was computed, successfully or not.
+    AggregateUpdated {
+        price_account: Pubkey,
+        price:         i64,
+        conf:          u64,
+        exponent:      i32,
+        status:        u32,
+        num_qt:        u32,
+        pub_slot:      u64,
+        timestamp:     i64,
+        ema_price:     i64,
+        ema_conf:      i64,
+    },
+    PublisherAdded {
+        price_account: Pubkey,
+        publisher:     Pubkey,
+    },
+    PublisherRemoved {
+        price_account: Pubkey,
+        publisher:     Pubkey,
+    },
+    PermissionsUpdated {
+        master_authority:        Pubkey,
+        data_curation_authority: Pubkey,
+        security_authority:      Pubkey,
+    },
+    MinPubSet {
+        price_account: Pubkey,
+        min_pub:       u8,
+    },
+    MaxLatencySet {
+        price_account: Pubkey,
+        max_latency:   u8,
+    },
+    ProductDeleted {
+        mapping_account: Pubkey,
+        product_account: Pubkey,
+    },
+    PriceDeleted {
+        product_account: Pubkey,
+        price_account:   Pubkey,
+    },
+    /// The flags of a price account changed. `flags` holds the new `PriceAccountFlags` bits.
+    PriceFlagsSet {
+        price_account: Pubkey,
+        flags:         u8,
+    },
+    PublisherRotated {
+        price_account: Pubkey,
+        old_publisher: Pubkey,
+        new_publisher: Pubkey,
+    },
+    /// A delegate was added or its expiry slot changed
+    DelegateAdded {
+        publisher:   Pubkey,
+        delegate:    Pubkey,
+        expiry_slot: u64,
+    },
+    DelegateRemoved {
+        publisher: Pubkey,
+        delegate:  Pubkey,
+    },
+    /// The aggregate of the price account is held at `PC_STATUS_HALTED` until it is resumed
+    FeedPaused {
+        price_account: Pubkey,
+    },
+    FeedResumed {
+        price_account: Pubkey,
+    },
+    /// The trading schedule of a price account was created or replaced
+    ScheduleSet {
+        price_account: Pubkey,
+        num_sessions:  u8,
+        num_holidays:  u8,
+    },
+    /// The aggregation config of a price account was replaced, see `AggregationConfig`
+    AggregationConfigSet {
+        price_account:    Pubkey,
+        mode:             u8,
+        outlier_mads:     u8,
+        widen_stale_conf: u8,
+    },
+}
+
+// Discriminators of the events. New events must use new values.
+const AGGREGATE_UPDATED: u8 = 0;
+const PUBLISHER_ADDED: u8 = 1;
+const PUBLISHER_REMOVED: u8 = 2;
+const PERMISSIONS_UPDATED: u8 = 3;
+const MIN_PUB_SET: u8 = 4;
+const MAX_LATENCY_SET: u8 = 5;
+const PRODUCT_DELETED: u8 = 6;
+const PRICE_DELETED: u8 = 7;
+const PRICE_FLAGS_SET: u8 = 8;
+const PUBLISHER_ROTATED: u8 = 9;
+const DELEGATE_ADDED: u8 = 10;
+const DELEGATE_REMOVED: u8 = 11;
+const FEED_PAUSED: u8 = 12;
+const FEED_RESUMED: u8 = 13;
+const SCHEDULE_SET: u8 = 14;
+const AGGREGATION_CONFIG_SET: u8 = 15;
+
+impl Event {
+    /// Log the event so that it shows up in the transaction logs.
+    pub fn emit(&self) {
+        sol_log_data(&[&self.to_bytes()]);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![EVENT_VERSION];
+        match *self {
+            Event::AggregateUpdated {
+                price_account,
+                price,
+                conf,
+                exponent,
+                status,
+                num_qt,
+                pub_slot,
+                timestamp,
+                ema_price,
+                ema_conf,
+            } => {
+                bytes.push(AGGREGATE_UPDATED);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.extend_from_slice(&price.to_le_bytes());
+                bytes.extend_from_slice(&conf.to_le_bytes());
+                bytes.extend_from_slice(&exponent.to_le_bytes());
+                bytes.extend_from_slice(&status.to_le_bytes());
+                bytes.extend_from_slice(&num_qt.to_le_bytes());
+                bytes.extend_from_slice(&pub_slot.to_le_bytes());
+                bytes.extend_from_slice(&timestamp.to_le_bytes());
+                bytes.extend_from_slice(&ema_price.to_le_bytes());
+                bytes.extend_from_slice(&ema_conf.to_le_bytes());
+            }
+            Event::PublisherAdded {
+                price_account,
+                publisher,
+            } => {
+                bytes.push(PUBLISHER_ADDED);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.extend_from_slice(publisher.as_ref());
+            }
+            Event::PublisherRemoved {
+                price_account,
+                publisher,
+            } => {
+                bytes.push(PUBLISHER_REMOVED);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.extend_from_slice(publisher.as_ref());
+            }
+            Event::PermissionsUpdated {
+                master_authority,
+                data_curation_authority,
+                security_authority,
+            } => {
+                bytes.push(PERMISSIONS_UPDATED);
+                bytes.extend_from_slice(master_authority.as_ref());
+                bytes.extend_from_slice(data_curation_authority.as_ref());
+                bytes.extend_from_slice(security_authority.as_ref());
+            }
+            Event::MinPubSet {
+                price_account,
+                min_pub,
+            } => {
+                bytes.push(MIN_PUB_SET);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.push(min_pub);
+            }
+            Event::MaxLatencySet {
+                price_account,
+                max_latency,
+            } => {
+                bytes.push(MAX_LATENCY_SET);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.push(max_latency);
+            }
+            Event::ProductDeleted {
+                mapping_account,
+                product_account,
+            } => {
+                bytes.push(PRODUCT_DELETED);
+                bytes.extend_from_slice(mapping_account.as_ref());
+                bytes.extend_from_slice(product_account.as_ref());
+            }
+            Event::PriceDeleted {
+                product_account,
+                price_account,
+            } => {
+                bytes.push(PRICE_DELETED);
+                bytes.extend_from_slice(product_account.as_ref());
+                bytes.extend_from_slice(price_account.as_ref());
+            }
+            Event::PriceFlagsSet {
+                price_account,
+                flags,
+            } => {
+                bytes.push(PRICE_FLAGS_SET);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.push(flags);
+            }
+            Event::PublisherRotated {
+                price_account,
+                old_publisher,
+                new_publisher,
+            } => {
+                bytes.push(PUBLISHER_ROTATED);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.extend_from_slice(old_publisher.as_ref());
+                bytes.extend_from_slice(new_publisher.as_ref());
+            }
+            Event::DelegateAdded {
+                publisher,
+                delegate,
+                expiry_slot,
+            } => {
+                bytes.push(DELEGATE_ADDED);
+                bytes.extend_from_slice(publisher.as_ref());
+                bytes.extend_from_slice(delegate.as_ref());
+                bytes.extend_from_slice(&expiry_slot.to_le_bytes());
+            }
+            Event::DelegateRemoved {
+                publisher,
+                delegate,
+            } => {
+                bytes.push(DELEGATE_REMOVED);
+                bytes.extend_from_slice(publisher.as_ref());
+                bytes.extend_from_slice(delegate.as_ref());
+            }
+            Event::FeedPaused { price_account } => {
+                bytes.push(FEED_PAUSED);
+                bytes.extend_from_slice(price_account.as_ref());
+            }
+            Event::FeedResumed { price_account } => {
+                bytes.push(FEED_RESUMED);
+                bytes.extend_from_slice(price_account.as_ref());
+            }
+            Event::ScheduleSet {
+                price_account,
+                num_sessions,
+                num_holidays,
+            } => {
+                bytes.push(SCHEDULE_SET);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.push(num_sessions);
+                bytes.push(num_holidays);
+            }
+            Event::AggregationConfigSet {
+                price_account,
+                mode,
+                outlier_mads,
+                widen_stale_conf,
+            } => {
+                bytes.push(AGGREGATION_CONFIG_SET);
+                bytes.extend_from_slice(price_account.as_ref());
+                bytes.push(mode);
+                bytes.push(outlier_mads);
+                bytes.push(widen_stale_conf);
+            }
+        }
+        bytes
+    }
+
+    #[cfg(any(test, feature = "library"))]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Event, OracleError> {
+        let mut reader = Reader(bytes);
+        if reader.u8()? != EVENT_VERSION {
+            return Err(OracleError::DeserializationError);
+        }
+        let event = match reader.u8()? {
+            AGGREGATE_UPDATED => Event::AggregateUpdated {
+                price_account: reader.pubkey()?,
+                price:         i64::from_le_bytes(reader.array()?),
+                conf:          u64::from_le_bytes(reader.array()?),
+                exponent:      i32::from_le_bytes(reader.array()?),
+                status:        u32::from_le_bytes(reader.array()?),
+                num_qt:        u32::from_le_bytes(reader.array()?),
+                pub_slot:      u64::from_le_bytes(reader.array()?),
+                timestamp:     i64::from_le_bytes(reader.array()?),
+                ema_price:     i64::from_le_bytes(reader.array()?),
+                ema_conf:      i64::from_le_bytes(reader.array()?),
+            },
+            PUBLISHER_ADDED => Event::PublisherAdded {
+                price_account: reader.pubkey()?,
+                publisher:     reader.pubkey()?,
+            },
+            PUBLISHER_REMOVED => Event::PublisherRemoved {
+                price_account: reader.pubkey()?,
+                publisher:     reader.pubkey()?,
+            },
+            PERMISSIONS_UPDATED => Event::PermissionsUpdated {
+                master_authority:        reader.pubkey()?,
+                data_curation_authority: reader.pubkey()?,
+                security_authority:      reader.pubkey()?,
+            },
+            MIN_PUB_SET => Event::MinPubSet {
+                price_account: reader.pubkey()?,
+                min_pub:       reader.u8()?,
+            },
+            MAX_LATENCY_SET => Event::MaxLatencySet {
+                price_account: reader.pubkey()?,
+                max_latency:   reader.u8()?,
+            },
+            PRODUCT_DELETED => Event::ProductDeleted {
+                mapping_account: reader.pubkey()?,
+                product_account: reader.pubkey()?,
+            },
+            PRICE_DELETED => Event::PriceDeleted {
+                product_account: reader.pubkey()?,
+                price_account:   reader.pubkey()?,
+            },
+            PRICE_FLAGS_SET => Event::PriceFlagsSet {
+                price_account: reader.pubkey()?,
+                flags:         reader.u8()?,
+            },
+            PUBLISHER_ROTATED => Event::PublisherRotated {
+                price_account: reader.pubkey()?,
+                old_publisher: reader.pubkey()?,
+                new_publisher: reader.pubkey()?,
+            },
+            DELEGATE_ADDED => Event::DelegateAdded {
+                publisher:   reader.pubkey()?,
+                delegate:    reader.pubkey()?,
+                expiry_slot: u64::from_le_bytes(reader.array()?),
+            },
+            DELEGATE_REMOVED => Event::DelegateRemoved {
+                publisher: reader.pubkey()?,
+                delegate:  reader.pubkey()?,
+            },
+            FEED_PAUSED => Event::FeedPaused {
+                price_account: reader.pubkey()?,
+            },
+            FEED_RESUMED => Event::FeedResumed {
+                price_account: reader.pubkey()?,
+            },
+            SCHEDULE_SET => Event::ScheduleSet {
+                price_account: reader.pubkey()?,
+                num_sessions:  reader.u8()?,
+                num_holidays:  reader.u8()?,
+            },
+            AGGREGATION_CONFIG_SET => Event::AggregationConfigSet {
+                price_account:    reader.pubkey()?,
+                mode:             reader.u8()?,
+                outlier_mads:     reader.u8()?,
+                widen_stale_conf: reader.u8()?,
+            },
+            _ => return Err(OracleError::DeserializationError),
+        };
+        if !reader.0.is_empty() {
+            return Err(OracleError::DeserializationError);
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(any(test, feature = "library"))]
+struct Reader<'a>(&'a [u8]);
+
+#[cfg(any(test, feature = "library"))]
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], OracleError> {
+        if self.0.len() < N {
+            return Err(OracleError::DeserializationError);
+        }
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, OracleError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey, OracleError> {
+        Ok(Pubkey::new_from_array(self.array()?))
+    }
+}
+
+/// The events logged by `program_id` in the logs of a transaction, in order. Data logged by other
+/// programs, including programs invoked by the oracle, and data that isn't an event of a known
+/// version are skipped.
+#[cfg(any(test, feature = "library"))]
+pub fn parse_events<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<Event> {
+    let program_id = program_id.to_string();
+    let mut invocations: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() == Some(&program_id.as_str()) {
+                events.extend(
+                    data.split(' ')
+                        .filter_map(|field| base64::decode(field).ok())
+                        .filter_map(|bytes| Event::from_bytes(&bytes).ok()),
+                );
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => invocations.push(program),
+                (Some(_), Some("success")) => {
+                    invocations.pop();
+                }
+                (Some(_), Some(word)) if word.starts_with("failed") => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
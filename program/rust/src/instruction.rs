@@ -1,5 +1,6 @@
 use {
     crate::{
+        accounts::AggregationConfig,
         c_oracle_header::PC_VERSION,
         deserialize::load,
         error::OracleError,
@@ -13,12 +14,21 @@ use {
         ToPrimitive,
     },
     num_traits::FromPrimitive,
-    solana_program::pubkey::Pubkey,
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
 };
 
+#[cfg(any(test, feature = "library"))]
+pub mod builders;
+pub mod compact;
+#[cfg(any(test, feature = "library"))]
+pub mod decoder;
+
 /// WARNING : NEW COMMANDS SHOULD BE ADDED AT THE END OF THE LIST
 #[repr(i32)]
-#[derive(PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 pub enum OracleCommand {
     /// Initialize first mapping list account
     // account[0] funding account       [signer writable]
@@ -111,6 +121,67 @@ pub enum OracleCommand {
     InitPriceFeedIndex    = 19,
     // account[0] mapping account [writable]
     ResizeMapping         = 20,
+    /// Set and clear price account flags
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    SetPriceFlags         = 21,
+    /// Replace a publisher's key, keeping its component price
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    RotatePublisher       = 22,
+    /// Allow a key to publish prices on behalf of the publisher, creating the publisher's
+    /// delegation account if needed. Requires the `delegation` feature.
+    // account[0] publisher account     [signer writable]
+    // account[1] delegation account    [writable]
+    // account[2] system program        []
+    AddDelegate           = 23,
+    /// Revoke a delegate of the publisher. Requires the `delegation` feature.
+    // account[0] publisher account     [signer writable]
+    // account[1] delegation account    [writable]
+    DelDelegate           = 24,
+    /// Publish component prices for several price accounts. Requires the `batch-updates`
+    /// feature.
+    // account[0] funding account       [signer writable]
+    // account[1] sysvar_clock account  []
+    // account[2] delegation account    [] (only if signed by a delegate)
+    // account[2..] price accounts      [writable]
+    UpdPriceBatch         = 25,
+    /// Publish component price, encoded relative to the latest component price and the clock.
+    /// Requires the `batch-updates` feature.
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] sysvar_clock account  []
+    UpdPriceCompact       = 26,
+    /// Add and remove several publishers of a price account at once
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    SetPublishers         = 27,
+    /// Hold the aggregate of a price account at `PC_STATUS_HALTED`, while publishers can still
+    /// update their components
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    PauseFeed             = 28,
+    /// Resume the aggregation of a paused price account
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    ResumeFeed            = 29,
+    /// Create or replace the trading schedule of a price account and set its `SCHEDULED` flag
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] schedule account      [writable]
+    // account[3] system program        []
+    // account[4] permissions account   []
+    SetSchedule           = 30,
+    /// Set how the aggregate of a price account is computed, see `AggregationConfig`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    SetAggregationConfig  = 31,
 }
 
 #[repr(C)]
@@ -120,6 +191,15 @@ pub struct CommandHeader {
     pub command: i32,
 }
 
+impl From<OracleCommand> for CommandHeader {
+    fn from(val: OracleCommand) -> Self {
+        CommandHeader {
+            version: PC_VERSION,
+            command: val as i32,
+        }
+    }
+}
+
 pub fn load_command_header_checked(data: &[u8]) -> Result<OracleCommand, OracleError> {
     let command_header = load::<CommandHeader>(data)?;
 
@@ -147,6 +227,67 @@ pub struct AddPublisherArgs {
 
 pub type DelPublisherArgs = AddPublisherArgs;
 
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct AddDelegateArgs {
+    pub header:      CommandHeader,
+    pub delegate:    Pubkey,
+    /// Last slot at which the delegate can publish, 0 if the delegation doesn't expire
+    pub expiry_slot: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct DelDelegateArgs {
+    pub header:   CommandHeader,
+    pub delegate: Pubkey,
+}
+
+/// How `SetPublishers` interprets its list of publishers.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum SetPublishersMode {
+    /// Add the publishers to add and remove the publishers to remove. Fails if a publisher to add
+    /// is already a publisher, or if a publisher to remove isn't.
+    Update = 0,
+    /// Make the publishers to add the exact publisher set, adding and removing publishers as
+    /// needed. There must be no publishers to remove.
+    Exact  = 1,
+}
+
+/// Arguments of `SetPublishers`, followed by `num_add` then `num_remove` publisher keys.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublishersArgs {
+    pub header:     CommandHeader,
+    /// A `SetPublishersMode`
+    pub mode:       u8,
+    pub num_add:    u8,
+    pub num_remove: u8,
+    pub unused_:    u8,
+}
+
+/// Arguments of `SetSchedule`, followed by `num_sessions` `TradingSession`s then `num_holidays`
+/// `Holiday`s.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetScheduleArgs {
+    pub header:       CommandHeader,
+    /// Offset of the local time from UTC, in seconds
+    pub utc_offset:   i32,
+    pub num_sessions: u8,
+    pub num_holidays: u8,
+    pub unused_:      [u8; 2],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct RotatePublisherArgs {
+    pub header:        CommandHeader,
+    pub old_publisher: Pubkey,
+    pub new_publisher: Pubkey,
+}
+
 #[repr(C)]
 #[derive(Zeroable, Clone, Copy, Pod)]
 pub struct SetMinPubArgs {
@@ -160,12 +301,120 @@ pub struct SetMinPubArgs {
 pub struct UpdPriceArgs {
     pub header:          CommandHeader,
     pub status:          u32,
-    pub unused_:         u32,
+    /// Corporate actions affecting the price, a combination of the `PC_CORP_ACT_*` flags. The
+    /// aggregate reports the corporate actions reported by a majority of the components it
+    /// aggregates. Other bits are ignored.
+    pub corp_act_status: u32,
     pub price:           i64,
     pub confidence:      u64,
     pub publishing_slot: u64,
 }
 
+/// Maximum number of prices in an `UpdPriceBatch` instruction, such that the return data of the
+/// instruction fits in `MAX_RETURN_DATA`.
+pub const MAX_UPD_PRICE_BATCH_SIZE: usize = 32;
+
+/// One price of an `UpdPriceBatch` instruction. The instruction data is a `CommandHeader` followed
+/// by one entry per price account, in the order of the price accounts.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UpdPriceBatchEntry {
+    pub status:          u32,
+    pub corp_act_status: u32,
+    pub price:           i64,
+    pub confidence:      u64,
+    pub publishing_slot: u64,
+}
+
+/// Version of the `UpdPriceReturnData` layout, stored in its first byte.
+pub const UPD_PRICE_RETURN_DATA_VERSION: u8 = 1;
+
+/// What an `UpdPrice` instruction did with the publisher's component price.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ComponentUpdate {
+    /// The instruction didn't carry a component price, or it failed before storing it
+    NotUpdated = 0,
+    /// The price was stored with the status sent by the publisher
+    Accepted   = 1,
+    /// The price was stored with `PC_STATUS_IGNORED` because its confidence interval is too wide
+    Ignored    = 2,
+}
+
+/// Summary of an `UpdPrice` or `UpdPriceNoFailOnError` instruction, set as the return data of
+/// the instruction. The return data of `UpdPriceBatch` is the concatenation of the summaries of
+/// each price, in the order of the price accounts.
+///
+/// Encoded in little endian as `version: u8`, `component: u8`, `aggregate_triggered: u8`,
+/// `message_sent: u8`, `agg_status: u32`, `agg_price: i64`, `num_qt: u32` and `error: u64`, where
+/// `error` is 0 when the instruction succeeded and `u64::from(ProgramError)` otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdPriceReturnData {
+    pub component:           ComponentUpdate,
+    /// Whether the instruction was the first one of the slot and aggregated the previous slot.
+    /// `agg_status` tells whether the aggregation succeeded.
+    pub aggregate_triggered: bool,
+    /// Whether the price was sent to the message buffer program
+    pub message_sent:        bool,
+    pub agg_status:          u32,
+    pub agg_price:           i64,
+    pub num_qt:              u32,
+    /// The error swallowed by `UpdPriceNoFailOnError`. The other fields are unset in that case.
+    pub error:               Option<ProgramError>,
+}
+
+impl UpdPriceReturnData {
+    pub const LEN: usize = 28;
+
+    /// The summary of an instruction that failed with `error`.
+    pub fn from_error(error: ProgramError) -> Self {
+        UpdPriceReturnData {
+            component:           ComponentUpdate::NotUpdated,
+            aggregate_triggered: false,
+            message_sent:        false,
+            agg_status:          0,
+            agg_price:           0,
+            num_qt:              0,
+            error:               Some(error),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = UPD_PRICE_RETURN_DATA_VERSION;
+        bytes[1] = self.component as u8;
+        bytes[2] = u8::from(self.aggregate_triggered);
+        bytes[3] = u8::from(self.message_sent);
+        bytes[4..8].copy_from_slice(&self.agg_status.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.agg_price.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.num_qt.to_le_bytes());
+        let error = self.error.clone().map_or(0, u64::from);
+        bytes[20..28].copy_from_slice(&error.to_le_bytes());
+        bytes
+    }
+
+    #[cfg(any(test, feature = "library"))]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OracleError> {
+        let bytes: &[u8; Self::LEN] = bytes
+            .try_into()
+            .map_err(|_| OracleError::DeserializationError)?;
+        if bytes[0] != UPD_PRICE_RETURN_DATA_VERSION || bytes[2] > 1 || bytes[3] > 1 {
+            return Err(OracleError::DeserializationError);
+        }
+        let error = u64::from_le_bytes(bytes[20..28].try_into().unwrap());
+        Ok(UpdPriceReturnData {
+            component:           ComponentUpdate::from_u8(bytes[1])
+                .ok_or(OracleError::DeserializationError)?,
+            aggregate_triggered: bytes[2] == 1,
+            message_sent:        bytes[3] == 1,
+            agg_status:          u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            agg_price:           i64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            num_qt:              u32::from_le_bytes(bytes[16..20].try_into().unwrap()),
+            error:               (error != 0).then(|| ProgramError::from(error)),
+        })
+    }
+}
+
 #[repr(C)]
 #[derive(Zeroable, Pod, Copy, Clone)]
 pub struct UpdPermissionsArgs {
@@ -182,3 +431,23 @@ pub struct SetMaxLatencyArgs {
     pub max_latency: u8,
     pub unused_:     [u8; 3],
 }
+
+/// Arguments of `SetAggregationConfig`. `config` replaces the whole aggregation config of the
+/// price account and must be valid, see `AggregationConfig::is_valid`.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAggregationConfigArgs {
+    pub header: CommandHeader,
+    pub config: AggregationConfig,
+}
+
+/// Arguments of `SetPriceFlags`. The bits of `PriceAccountFlags` in `set_flags` are set and the
+/// ones in `clear_flags` are cleared, the other flags are left unchanged.
+#[repr(C)]
+#[derive(Zeroable, Clone, Copy, Pod)]
+pub struct SetPriceFlagsArgs {
+    pub header:      CommandHeader,
+    pub set_flags:   u8,
+    pub clear_flags: u8,
+    pub unused_:     [u8; 2],
+}
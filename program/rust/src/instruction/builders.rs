@@ -0,0 +1,908 @@
+//! Builders for the instructions of the oracle program.
+//!
+//! Each function returns a ready-to-sign `Instruction` whose accounts match the layout expected by
+//! the corresponding processor in `processor/`. Accounts owned by the oracle (mapping, product and
+//! price accounts) are passed as writable non-signers: the funding account is the only signer and
+//! its authority is checked against the permissions account.
+use {
+    crate::{
+        accounts::{
+            AggregationConfig,
+            Holiday,
+            TradingSession,
+            DELEGATION_SEED,
+            PERMISSIONS_SEED,
+            SCHEDULE_SEED,
+            UPD_PRICE_WRITE_SEED,
+        },
+        instruction::{
+            compact::CompactUpdPrice,
+            AddDelegateArgs,
+            AddPriceArgs,
+            AddPublisherArgs,
+            CommandHeader,
+            DelDelegateArgs,
+            DelPublisherArgs,
+            InitPriceArgs,
+            OracleCommand,
+            RotatePublisherArgs,
+            SetAggregationConfigArgs,
+            SetMaxLatencyArgs,
+            SetMinPubArgs,
+            SetPriceFlagsArgs,
+            SetPublishersArgs,
+            SetPublishersMode,
+            SetScheduleArgs,
+            UpdPermissionsArgs,
+            UpdPriceArgs,
+            UpdPriceBatchEntry,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        cast_slice,
+    },
+    solana_program::{
+        bpf_loader_upgradeable,
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        pubkey::Pubkey,
+        system_program,
+        sysvar::clock,
+    },
+};
+
+/// Accounts of the message buffer program that `upd_price` can optionally send the price messages
+/// to via CPI. The oracle PDA signing the CPI is derived from `program_id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageBufferAccounts {
+    pub program_id:          Pubkey,
+    pub whitelist:           Pubkey,
+    pub message_buffer_data: Pubkey,
+}
+
+/// Address of the permissions account of the oracle program `program_id`.
+pub fn find_permissions_account(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id).0
+}
+
+/// Address of the PDA that signs the `upd_price` CPI into `message_buffer_program_id`.
+pub fn find_upd_price_write_authority(
+    program_id: &Pubkey,
+    message_buffer_program_id: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            UPD_PRICE_WRITE_SEED.as_bytes(),
+            &message_buffer_program_id.to_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Address of the delegation account of `publisher`.
+pub fn find_delegation_account(program_id: &Pubkey, publisher: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DELEGATION_SEED.as_bytes(), publisher.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Address of the schedule account of `price_account`.
+pub fn find_schedule_account(program_id: &Pubkey, price_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SCHEDULE_SEED.as_bytes(), price_account.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Address of the programdata account of the upgradeable program `program_id`.
+pub fn find_programdata_account(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Builds an instruction consisting of a bare `CommandHeader` for an administrative command whose
+/// accounts are `[funding_account, ...accounts, permissions_account]`.
+fn permissioned_instruction(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Instruction {
+    let mut account_metas = vec![AccountMeta::new(*funding_account, true)];
+    account_metas.extend(accounts.iter().map(|key| AccountMeta::new(*key, false)));
+    account_metas.push(AccountMeta::new_readonly(
+        find_permissions_account(program_id),
+        false,
+    ));
+    Instruction::new_with_bytes(*program_id, data, account_metas)
+}
+
+/// Initialize first mapping list account
+pub fn init_mapping(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    mapping_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitMapping.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*mapping_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Initialize and add new product reference data account. `metadata` is the encoded list of
+/// key-value pairs appended to the instruction (see `update_product_metadata`), it may be empty.
+pub fn add_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    mapping_account: &Pubkey,
+    product_account: &Pubkey,
+    metadata: &[u8],
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::AddProduct.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*mapping_account, *product_account],
+        &[bytes_of(&cmd), metadata].concat(),
+    )
+}
+
+/// Update product account, overwriting its metadata with `metadata`
+pub fn upd_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    metadata: &[u8],
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::UpdProduct.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*product_account],
+        &[bytes_of(&cmd), metadata].concat(),
+    )
+}
+
+/// Add new price account to a product account
+pub fn add_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    price_account: &Pubkey,
+    exponent: i32,
+    price_type: u32,
+) -> Instruction {
+    let cmd = AddPriceArgs {
+        header: OracleCommand::AddPrice.into(),
+        exponent,
+        price_type,
+    };
+    let mut instruction = permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*product_account, *price_account],
+        bytes_of(&cmd),
+    );
+    // The new price feed index is reserved in the permissions account
+    if let Some(permissions_account) = instruction.accounts.last_mut() {
+        permissions_account.is_writable = true;
+    }
+    instruction
+}
+
+/// (Re)initialize price account
+pub fn init_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    exponent: i32,
+    price_type: u32,
+) -> Instruction {
+    let cmd = InitPriceArgs {
+        header: OracleCommand::InitPrice.into(),
+        exponent,
+        price_type,
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Add publisher to symbol account
+pub fn add_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = AddPublisherArgs {
+        header:    OracleCommand::AddPublisher.into(),
+        publisher: *publisher,
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Delete publisher from symbol account
+pub fn del_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = DelPublisherArgs {
+        header:    OracleCommand::DelPublisher.into(),
+        publisher: *publisher,
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Builds the account list shared by `UpdPrice`, `UpdPriceNoFailOnError` and `AggPrice`.
+/// `signer` is the publisher, or one of its delegates if `delegation_account` is provided. With a
+/// `schedule_account` but no `delegation_account`, the price account fills the delegation slot.
+fn upd_price_account_metas(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    price_account: &Pubkey,
+    delegation_account: Option<Pubkey>,
+    schedule_account: Option<Pubkey>,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Vec<AccountMeta> {
+    let mut account_metas = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(*price_account, false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+    match (delegation_account, schedule_account) {
+        (Some(delegation_account), _) => {
+            account_metas.push(AccountMeta::new_readonly(delegation_account, false))
+        }
+        (None, Some(_)) => account_metas.push(AccountMeta::new(*price_account, false)),
+        (None, None) => {}
+    }
+    if let Some(schedule_account) = schedule_account {
+        account_metas.push(AccountMeta::new_readonly(schedule_account, false));
+    }
+    if let Some(message_buffer_accounts) = message_buffer_accounts {
+        account_metas.extend([
+            AccountMeta::new_readonly(message_buffer_accounts.program_id, false),
+            AccountMeta::new_readonly(message_buffer_accounts.whitelist, false),
+            AccountMeta::new_readonly(
+                find_upd_price_write_authority(program_id, &message_buffer_accounts.program_id),
+                false,
+            ),
+            AccountMeta::new(message_buffer_accounts.message_buffer_data, false),
+        ]);
+    }
+    account_metas
+}
+
+/// Publish component price. If `message_buffer_accounts` is provided, the price messages are also
+/// sent to the message buffer program.
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(
+            program_id,
+            publisher,
+            price_account,
+            None,
+            None,
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Publish component price along with the corporate actions affecting it, a combination of the
+/// `PC_CORP_ACT_*` flags. `upd_price` reports no corporate action.
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price_with_corp_act(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    corp_act_status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(
+            program_id,
+            publisher,
+            price_account,
+            None,
+            None,
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Publish component price in a price account that has a trading schedule. The schedule account is
+/// needed for the update to aggregate the price account. If `message_buffer_accounts` is provided,
+/// the price messages are also sent to the message buffer program.
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price_scheduled(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(
+            program_id,
+            publisher,
+            price_account,
+            None,
+            Some(find_schedule_account(program_id, price_account)),
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Publish component price on behalf of `publisher`, signed by one of its delegates. If
+/// `message_buffer_accounts` is provided, the price messages are also sent to the message buffer
+/// program.
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price_as_delegate(
+    program_id: &Pubkey,
+    delegate: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(
+            program_id,
+            delegate,
+            price_account,
+            Some(find_delegation_account(program_id, publisher)),
+            None,
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Builds an `UpdPriceBatch` instruction publishing `prices`, a list of price accounts and the
+/// price to publish in each of them.
+fn upd_price_batch_instruction(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    delegation_account: Option<Pubkey>,
+    prices: &[(Pubkey, UpdPriceBatchEntry)],
+) -> Instruction {
+    let header: CommandHeader = OracleCommand::UpdPriceBatch.into();
+    let entries: Vec<UpdPriceBatchEntry> = prices.iter().map(|(_, entry)| *entry).collect();
+    let mut data = bytes_of(&header).to_vec();
+    data.extend_from_slice(cast_slice(&entries));
+
+    let mut account_metas = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+    if let Some(delegation_account) = delegation_account {
+        account_metas.push(AccountMeta::new_readonly(delegation_account, false));
+    }
+    account_metas.extend(
+        prices
+            .iter()
+            .map(|(price_account, _)| AccountMeta::new(*price_account, false)),
+    );
+    Instruction::new_with_bytes(*program_id, &data, account_metas)
+}
+
+/// Publish component prices for several price accounts at once
+pub fn upd_price_batch(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    prices: &[(Pubkey, UpdPriceBatchEntry)],
+) -> Instruction {
+    upd_price_batch_instruction(program_id, publisher, None, prices)
+}
+
+/// Publish component prices for several price accounts at once on behalf of `publisher`, signed
+/// by one of its delegates
+pub fn upd_price_batch_as_delegate(
+    program_id: &Pubkey,
+    delegate: &Pubkey,
+    publisher: &Pubkey,
+    prices: &[(Pubkey, UpdPriceBatchEntry)],
+) -> Instruction {
+    upd_price_batch_instruction(
+        program_id,
+        delegate,
+        Some(find_delegation_account(program_id, publisher)),
+        prices,
+    )
+}
+
+/// Publish component price encoded relative to the publisher's latest component price and to the
+/// clock slot, see `CompactUpdPrice::new`. If `message_buffer_accounts` is provided, the price
+/// messages are also sent to the message buffer program.
+pub fn upd_price_compact(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    update: &CompactUpdPrice,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let header: CommandHeader = OracleCommand::UpdPriceCompact.into();
+    let mut data = bytes_of(&header).to_vec();
+    data.extend_from_slice(&update.to_bytes());
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        upd_price_account_metas(
+            program_id,
+            publisher,
+            price_account,
+            None,
+            None,
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Publish component price, never returning an error even if the update failed
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price_no_fail_on_error(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    message_buffer_accounts: Option<&MessageBufferAccounts>,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPriceNoFailOnError.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(
+            program_id,
+            publisher,
+            price_account,
+            None,
+            None,
+            message_buffer_accounts,
+        ),
+    )
+}
+
+/// Compute aggregate price without updating the publisher's component
+pub fn agg_price(program_id: &Pubkey, publisher: &Pubkey, price_account: &Pubkey) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header:          OracleCommand::AggPrice.into(),
+        status:          0,
+        corp_act_status: 0,
+        price:           0,
+        confidence:      0,
+        publishing_slot: 0,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        upd_price_account_metas(program_id, publisher, price_account, None, None, None),
+    )
+}
+
+/// Set min publishers
+pub fn set_min_pub(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    minimum_publishers: u8,
+) -> Instruction {
+    let cmd = SetMinPubArgs {
+        header: OracleCommand::SetMinPub.into(),
+        minimum_publishers,
+        unused_: [0; 3],
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Deletes a price account. `price_account` must be the first price account of `product_account`.
+pub fn del_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::DelPrice.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*product_account, *price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Deletes a product account. The product account must not have any price accounts.
+pub fn del_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    mapping_account: &Pubkey,
+    product_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::DelProduct.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*mapping_account, *product_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Update authorities. Must be signed by the upgrade authority of the program.
+pub fn upd_permissions(
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+    master_authority: &Pubkey,
+    data_curation_authority: &Pubkey,
+    security_authority: &Pubkey,
+) -> Instruction {
+    let cmd = UpdPermissionsArgs {
+        header:                  OracleCommand::UpdPermissions.into(),
+        master_authority:        *master_authority,
+        data_curation_authority: *data_curation_authority,
+        security_authority:      *security_authority,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new_readonly(find_programdata_account(program_id), false),
+            AccountMeta::new(find_permissions_account(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set max latency
+pub fn set_max_latency(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    max_latency: u8,
+) -> Instruction {
+    let cmd = SetMaxLatencyArgs {
+        header: OracleCommand::SetMaxLatency.into(),
+        max_latency,
+        unused_: [0; 3],
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Set the `PriceAccountFlags` bits in `set_flags` and clear the ones in `clear_flags`
+pub fn set_price_flags(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    set_flags: u8,
+    clear_flags: u8,
+) -> Instruction {
+    let cmd = SetPriceFlagsArgs {
+        header: OracleCommand::SetPriceFlags.into(),
+        set_flags,
+        clear_flags,
+        unused_: [0; 2],
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Replace `old_publisher` with `new_publisher` in `price_account`. `funding_account` is either
+/// `old_publisher` or an authority allowed to rotate publishers.
+pub fn rotate_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    old_publisher: &Pubkey,
+    new_publisher: &Pubkey,
+) -> Instruction {
+    let cmd = RotatePublisherArgs {
+        header:        OracleCommand::RotatePublisher.into(),
+        old_publisher: *old_publisher,
+        new_publisher: *new_publisher,
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+fn set_publishers_instruction(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    mode: SetPublishersMode,
+    add: &[Pubkey],
+    remove: &[Pubkey],
+) -> Instruction {
+    let cmd = SetPublishersArgs {
+        header:     OracleCommand::SetPublishers.into(),
+        mode:       mode as u8,
+        num_add:    add.len() as u8,
+        num_remove: remove.len() as u8,
+        unused_:    0,
+    };
+    let mut data = bytes_of(&cmd).to_vec();
+    data.extend_from_slice(cast_slice(add));
+    data.extend_from_slice(cast_slice(remove));
+    permissioned_instruction(program_id, funding_account, &[*price_account], &data)
+}
+
+/// Add the publishers in `add` to `price_account` and remove the ones in `remove`, in a single
+/// instruction.
+pub fn set_publishers(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    add: &[Pubkey],
+    remove: &[Pubkey],
+) -> Instruction {
+    set_publishers_instruction(
+        program_id,
+        funding_account,
+        price_account,
+        SetPublishersMode::Update,
+        add,
+        remove,
+    )
+}
+
+/// Make `publishers` the exact set of publishers of `price_account`. The publishers that are
+/// already publishers keep their component prices.
+pub fn set_exact_publishers(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publishers: &[Pubkey],
+) -> Instruction {
+    set_publishers_instruction(
+        program_id,
+        funding_account,
+        price_account,
+        SetPublishersMode::Exact,
+        publishers,
+        &[],
+    )
+}
+
+/// Pause `price_account`, holding its aggregate at `PC_STATUS_HALTED` until it is resumed
+pub fn pause_feed(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::PauseFeed.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Resume a paused `price_account`
+pub fn resume_feed(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::ResumeFeed.into();
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Set the trading schedule of `price_account`, creating its schedule account if needed.
+/// `utc_offset` is the offset of the schedule's local time from UTC, in seconds.
+pub fn set_schedule(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    utc_offset: i32,
+    sessions: &[TradingSession],
+    holidays: &[Holiday],
+) -> Instruction {
+    let cmd = SetScheduleArgs {
+        header: OracleCommand::SetSchedule.into(),
+        utc_offset,
+        num_sessions: sessions.len() as u8,
+        num_holidays: holidays.len() as u8,
+        unused_: [0; 2],
+    };
+    let mut data = bytes_of(&cmd).to_vec();
+    data.extend_from_slice(cast_slice(sessions));
+    data.extend_from_slice(cast_slice(holidays));
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new(find_schedule_account(program_id, price_account), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(find_permissions_account(program_id), false),
+        ],
+    )
+}
+
+/// Replace the aggregation config of `price_account`, which says how its aggregate is computed.
+/// The program rejects `config` if it isn't valid, see `AggregationConfig::is_valid`.
+pub fn set_aggregation_config(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    config: AggregationConfig,
+) -> Instruction {
+    let cmd = SetAggregationConfigArgs {
+        header: OracleCommand::SetAggregationConfig.into(),
+        config,
+    };
+    permissioned_instruction(
+        program_id,
+        funding_account,
+        &[*price_account],
+        bytes_of(&cmd),
+    )
+}
+
+/// Allow `delegate` to publish prices on behalf of `publisher` until `expiry_slot` (0 for no
+/// expiry). Creates the delegation account of the publisher if needed.
+pub fn add_delegate(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    delegate: &Pubkey,
+    expiry_slot: u64,
+) -> Instruction {
+    let cmd = AddDelegateArgs {
+        header: OracleCommand::AddDelegate.into(),
+        delegate: *delegate,
+        expiry_slot,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        vec![
+            AccountMeta::new(*publisher, true),
+            AccountMeta::new(find_delegation_account(program_id, publisher), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Revoke a delegate of `publisher`
+pub fn del_delegate(program_id: &Pubkey, publisher: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let cmd = DelDelegateArgs {
+        header:   OracleCommand::DelDelegate.into(),
+        delegate: *delegate,
+    };
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        vec![
+            AccountMeta::new(*publisher, true),
+            AccountMeta::new(find_delegation_account(program_id, publisher), false),
+        ],
+    )
+}
+
+/// Resize mapping account to the current `MappingAccount` size. Anyone can pay for this
+/// instruction, so the mapping account is the only account.
+pub fn resize_mapping(program_id: &Pubkey, mapping_account: &Pubkey) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::ResizeMapping.into();
+    Instruction::new_with_bytes(
+        *program_id,
+        bytes_of(&cmd),
+        vec![AccountMeta::new(*mapping_account, false)],
+    )
+}
@@ -0,0 +1,154 @@
+//! Compact encoding of the arguments of `UpdPriceCompact`.
+//!
+//! Consecutive updates of a publisher usually differ by a few ticks, so instead of the full
+//! price, confidence and publishing slot of `UpdPriceArgs`, `UpdPriceCompact` carries them
+//! relative to the publisher's latest component price (`comp_[i].latest_`) and to the clock. The
+//! instruction data is a `CommandHeader` followed by 4 LEB128 varints:
+//! - the status,
+//! - the zigzag-encoded difference between the new price and the latest price,
+//! - the zigzag-encoded difference between the new confidence and the latest confidence,
+//! - the number of slots between the publishing slot and the clock slot.
+//!
+//! The corporate-action status isn't encoded: compact updates report no corporate action, like
+//! `builders::upd_price`, so that a one-off corporate action doesn't stick to the later updates.
+//! Corporate actions are reported with `UpdPrice`.
+//!
+//! Differences wrap around, so that any price and confidence can be encoded. Note that the deltas
+//! are applied to the latest price stored in the price account when the instruction executes, so
+//! a publisher must encode against the last update that landed.
+use crate::{
+    accounts::PriceInfo,
+    c_oracle_header::PC_CORP_ACT_NONE,
+    error::OracleError,
+    instruction::{
+        CommandHeader,
+        OracleCommand,
+        UpdPriceArgs,
+    },
+};
+
+/// Maximum length of the LEB128 encoding of a `u64`
+const MAX_VARINT_LEN: usize = 10;
+
+/// The arguments of `UpdPriceCompact`, before they are resolved against the price account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompactUpdPrice {
+    pub status:      u32,
+    pub price_delta: i64,
+    pub conf_delta:  i64,
+    /// `clock_slot - publishing_slot`
+    pub slot_lag:    u64,
+}
+
+impl CompactUpdPrice {
+    #[cfg(any(test, feature = "library"))]
+    /// Encode an update of a component whose latest price is `latest`, for an instruction
+    /// executed at `clock_slot`. Returns `None` if `publishing_slot` is after `clock_slot`, since
+    /// such an update would be rejected anyway.
+    pub fn new(
+        latest: &PriceInfo,
+        clock_slot: u64,
+        status: u32,
+        price: i64,
+        confidence: u64,
+        publishing_slot: u64,
+    ) -> Option<Self> {
+        Some(CompactUpdPrice {
+            status,
+            price_delta: price.wrapping_sub(latest.price_),
+            conf_delta: confidence.wrapping_sub(latest.conf_) as i64,
+            slot_lag: clock_slot.checked_sub(publishing_slot)?,
+        })
+    }
+
+    /// The `UpdPriceArgs` of the update, given the latest price of the component and the clock
+    /// slot.
+    pub fn to_upd_price_args(
+        &self,
+        version: u32,
+        latest: &PriceInfo,
+        clock_slot: u64,
+    ) -> Result<UpdPriceArgs, OracleError> {
+        Ok(UpdPriceArgs {
+            header:          CommandHeader {
+                version,
+                command: OracleCommand::UpdPrice as i32,
+            },
+            status:          self.status,
+            corp_act_status: PC_CORP_ACT_NONE,
+            price:           latest.price_.wrapping_add(self.price_delta),
+            confidence:      latest.conf_.wrapping_add(self.conf_delta as u64),
+            publishing_slot: clock_slot
+                .checked_sub(self.slot_lag)
+                .ok_or(OracleError::InvalidCompactUpdate)?,
+        })
+    }
+
+    #[cfg(any(test, feature = "library"))]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 * MAX_VARINT_LEN);
+        write_varint(&mut bytes, u64::from(self.status));
+        write_varint(&mut bytes, zigzag_encode(self.price_delta));
+        write_varint(&mut bytes, zigzag_encode(self.conf_delta));
+        write_varint(&mut bytes, self.slot_lag);
+        bytes
+    }
+
+    /// Decode the bytes following the `CommandHeader`, which must contain exactly the 4 varints.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, OracleError> {
+        let status = u32::try_from(read_varint(&mut bytes)?)
+            .map_err(|_| OracleError::InvalidCompactUpdate)?;
+        let price_delta = zigzag_decode(read_varint(&mut bytes)?);
+        let conf_delta = zigzag_decode(read_varint(&mut bytes)?);
+        let slot_lag = read_varint(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(OracleError::InvalidCompactUpdate);
+        }
+        Ok(CompactUpdPrice {
+            status,
+            price_delta,
+            conf_delta,
+            slot_lag,
+        })
+    }
+}
+
+#[cfg(any(test, feature = "library"))]
+pub fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(any(test, feature = "library"))]
+pub fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Read a varint from the start of `bytes` and advance `bytes` past it. Only the shortest
+/// encoding of each value is accepted, so that the encoding of an update is unique.
+pub fn read_varint(bytes: &mut &[u8]) -> Result<u64, OracleError> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate().take(MAX_VARINT_LEN) {
+        let payload = u64::from(byte & 0x7f);
+        // The 10th byte only holds the last bit of the value
+        if i == MAX_VARINT_LEN - 1 && payload > 1 {
+            return Err(OracleError::InvalidCompactUpdate);
+        }
+        value |= payload << (7 * i);
+        if byte & 0x80 == 0 {
+            if i > 0 && payload == 0 {
+                return Err(OracleError::InvalidCompactUpdate);
+            }
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(OracleError::InvalidCompactUpdate)
+}
@@ -0,0 +1,858 @@
+//! Decoding of raw oracle instructions into a typed `OracleInstruction`.
+//!
+//! This is the inverse of `builders`: given the instruction data and the account keys of an
+//! instruction sent to the oracle program, it recovers the command, its arguments and the role of
+//! each account. The checks mirror the ones performed by the processors before they touch any
+//! account, so an instruction that decodes successfully is well-formed (but may still fail on-chain,
+//! for example because of permissions).
+use {
+    crate::{
+        accounts::{
+            AggregationMode,
+            Holiday,
+            TradingSession,
+        },
+        c_oracle_header::PC_VERSION,
+        error::OracleError,
+        instruction::{
+            compact::CompactUpdPrice,
+            load_command_header_checked,
+            AddDelegateArgs,
+            AddPriceArgs,
+            AddPublisherArgs,
+            CommandHeader,
+            DelDelegateArgs,
+            OracleCommand,
+            RotatePublisherArgs,
+            SetAggregationConfigArgs,
+            SetMaxLatencyArgs,
+            SetMinPubArgs,
+            SetPriceFlagsArgs,
+            SetPublishersArgs,
+            SetPublishersMode,
+            SetScheduleArgs,
+            UpdPermissionsArgs,
+            UpdPriceArgs,
+            UpdPriceBatchEntry,
+            MAX_UPD_PRICE_BATCH_SIZE,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        pod_read_unaligned,
+        try_pod_read_unaligned,
+        Pod,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        pubkey::Pubkey,
+        sysvar::clock,
+    },
+    std::mem::size_of,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error(
+        "{actual} bytes of instruction data, shorter than a command header ({expected} bytes)"
+    )]
+    CommandHeaderTooShort { expected: usize, actual: usize },
+    #[error("{actual} bytes of instruction data for {command:?}, expected at least {expected}")]
+    InstructionDataTooShort {
+        command:  OracleCommand,
+        expected: usize,
+        actual:   usize,
+    },
+    #[error("{actual} bytes of instruction data for {command:?}, expected {expected}")]
+    InvalidInstructionDataLength {
+        command:  OracleCommand,
+        expected: usize,
+        actual:   usize,
+    },
+    #[error("instruction version {0} isn't supported, expected {}", PC_VERSION)]
+    InvalidInstructionVersion(u32),
+    #[error("command {0} isn't an oracle command")]
+    UnrecognizedInstruction(i32),
+    /// The command exists but is no longer processed by the oracle program.
+    #[error("{0:?} is no longer processed by the oracle program")]
+    RemovedInstruction(OracleCommand),
+    #[error("{command:?} doesn't take {actual} accounts")]
+    InvalidNumberOfAccounts {
+        command: OracleCommand,
+        actual:  usize,
+    },
+    #[error("the instruction data of UpdPriceCompact isn't a compact update")]
+    InvalidCompactUpdate,
+    #[error("{0} isn't a mode of SetPublishers")]
+    InvalidSetPublishersMode(u8),
+    /// The aggregation config isn't valid, see `AggregationConfig::is_valid`.
+    #[error("the aggregation config of SetAggregationConfig isn't valid")]
+    InvalidAggregationConfig,
+}
+
+/// Optional accounts of `upd_price` used to send the price messages to the message buffer program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageBufferAccountKeys {
+    pub program_id:          Pubkey,
+    pub whitelist:           Pubkey,
+    pub oracle_auth_pda:     Pubkey,
+    pub message_buffer_data: Pubkey,
+}
+
+/// Accounts of `UpdPrice`, `UpdPriceNoFailOnError`, `UpdPriceCompact` and `AggPrice`. The
+/// instruction accepts 3 accounts, 4 accounts (a legacy layout where the third account is ignored,
+/// or a delegated update) or 7 accounts (with the message buffer accounts), plus one for delegated
+/// updates and one more for the schedule account of a scheduled price account. The schedule account
+/// always comes after the delegation slot, which holds the price account if the publisher signs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpdPriceAccountKeys {
+    /// The signer: the publisher, or one of its delegates if `delegation_account` is set
+    pub publisher:               Pubkey,
+    pub price_account:           Pubkey,
+    pub clock_account:           Pubkey,
+    pub delegation_account:      Option<Pubkey>,
+    pub schedule_account:        Option<Pubkey>,
+    pub message_buffer_accounts: Option<MessageBufferAccountKeys>,
+}
+
+/// A decoded oracle instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OracleInstruction {
+    InitMapping {
+        funding_account:     Pubkey,
+        mapping_account:     Pubkey,
+        permissions_account: Pubkey,
+    },
+    AddProduct {
+        funding_account:     Pubkey,
+        mapping_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+        /// Encoded product metadata, see `update_product_metadata`
+        metadata:            Vec<u8>,
+    },
+    UpdProduct {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+        /// Encoded product metadata, see `update_product_metadata`
+        metadata:            Vec<u8>,
+    },
+    AddPrice {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        exponent:            i32,
+        price_type:          u32,
+    },
+    AddPublisher {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        publisher:           Pubkey,
+    },
+    DelPublisher {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        publisher:           Pubkey,
+    },
+    UpdPrice {
+        accounts:        UpdPriceAccountKeys,
+        status:          u32,
+        corp_act_status: u32,
+        price:           i64,
+        confidence:      u64,
+        publishing_slot: u64,
+    },
+    AggPrice {
+        accounts: UpdPriceAccountKeys,
+    },
+    InitPrice {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        exponent:            i32,
+        price_type:          u32,
+    },
+    SetMinPub {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        minimum_publishers:  u8,
+    },
+    UpdPriceNoFailOnError {
+        accounts:        UpdPriceAccountKeys,
+        status:          u32,
+        corp_act_status: u32,
+        price:           i64,
+        confidence:      u64,
+        publishing_slot: u64,
+    },
+    DelPrice {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+    },
+    DelProduct {
+        funding_account:     Pubkey,
+        mapping_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+    },
+    UpdPermissions {
+        upgrade_authority:       Pubkey,
+        programdata_account:     Pubkey,
+        permissions_account:     Pubkey,
+        system_program:          Pubkey,
+        master_authority:        Pubkey,
+        data_curation_authority: Pubkey,
+        security_authority:      Pubkey,
+    },
+    SetMaxLatency {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        max_latency:         u8,
+    },
+    ResizeMapping {
+        mapping_account: Pubkey,
+    },
+    SetPriceFlags {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        set_flags:           u8,
+        clear_flags:         u8,
+    },
+    RotatePublisher {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        old_publisher:       Pubkey,
+        new_publisher:       Pubkey,
+    },
+    AddDelegate {
+        publisher:          Pubkey,
+        delegation_account: Pubkey,
+        system_program:     Pubkey,
+        delegate:           Pubkey,
+        expiry_slot:        u64,
+    },
+    DelDelegate {
+        publisher:          Pubkey,
+        delegation_account: Pubkey,
+        delegate:           Pubkey,
+    },
+    UpdPriceBatch {
+        funding_account:    Pubkey,
+        clock_account:      Pubkey,
+        delegation_account: Option<Pubkey>,
+        price_accounts:     Vec<Pubkey>,
+        entries:            Vec<UpdPriceBatchEntry>,
+    },
+    UpdPriceCompact {
+        accounts: UpdPriceAccountKeys,
+        update:   CompactUpdPrice,
+    },
+    SetPublishers {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        mode:                SetPublishersMode,
+        add:                 Vec<Pubkey>,
+        remove:              Vec<Pubkey>,
+    },
+    PauseFeed {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+    },
+    ResumeFeed {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+    },
+    SetSchedule {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        schedule_account:    Pubkey,
+        system_program:      Pubkey,
+        permissions_account: Pubkey,
+        utc_offset:          i32,
+        sessions:            Vec<TradingSession>,
+        holidays:            Vec<Holiday>,
+    },
+    SetAggregationConfig {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        mode:                AggregationMode,
+        outlier_mads:        u8,
+        widen_stale_conf:    bool,
+    },
+}
+
+/// Read the arguments `T` of `command` from the beginning of `data`. `data` doesn't need to be
+/// aligned. Trailing bytes are allowed, for the commands whose processor ignores them: `AddPrice`,
+/// `InitPrice`, `UpdPermissions` and the `UpdPrice` family have always accepted them, so rejecting
+/// them here would reject instructions that succeed on-chain.
+fn read_args<T: Pod>(command: OracleCommand, data: &[u8]) -> Result<T, DecodeError> {
+    let too_short = DecodeError::InstructionDataTooShort {
+        command,
+        expected: size_of::<T>(),
+        actual: data.len(),
+    };
+    let bytes = data.get(..size_of::<T>()).ok_or(too_short)?;
+    try_pod_read_unaligned(bytes).map_err(|_| too_short)
+}
+
+/// Read the arguments `T` of `command`, which must span all of `data`, for commands whose
+/// processor rejects trailing bytes.
+fn read_exact_args<T: Pod>(command: OracleCommand, data: &[u8]) -> Result<T, DecodeError> {
+    let args = read_args::<T>(command, data)?;
+    if data.len() != size_of::<T>() {
+        return Err(DecodeError::InvalidInstructionDataLength {
+            command,
+            expected: size_of::<T>(),
+            actual: data.len(),
+        });
+    }
+    Ok(args)
+}
+
+/// The delegation account in the delegation slot, unless the price account fills it.
+fn delegation_slot(price_account: Pubkey, key: Pubkey) -> Option<Pubkey> {
+    Some(key).filter(|key| *key != price_account)
+}
+
+fn decode_upd_price_accounts(
+    command: OracleCommand,
+    account_keys: &[Pubkey],
+) -> Result<UpdPriceAccountKeys, DecodeError> {
+    match *account_keys {
+        [publisher, price_account, clock_account] => Ok(UpdPriceAccountKeys {
+            publisher,
+            price_account,
+            clock_account,
+            delegation_account: None,
+            schedule_account: None,
+            message_buffer_accounts: None,
+        }),
+        // The processor tells the layouts apart by the owner of the last account, which the keys
+        // don't give. The legacy layout ends with the clock sysvar instead.
+        [publisher, price_account, _, clock_account] if clock_account == clock::id() => {
+            Ok(UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account,
+                delegation_account: None,
+                schedule_account: None,
+                message_buffer_accounts: None,
+            })
+        }
+        [publisher, price_account, clock_account, delegation_account] => Ok(UpdPriceAccountKeys {
+            publisher,
+            price_account,
+            clock_account,
+            delegation_account: Some(delegation_account),
+            schedule_account: None,
+            message_buffer_accounts: None,
+        }),
+        [publisher, price_account, clock_account, delegation_account, schedule_account] => {
+            Ok(UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account,
+                delegation_account: delegation_slot(price_account, delegation_account),
+                schedule_account: Some(schedule_account),
+                message_buffer_accounts: None,
+            })
+        }
+        [publisher, price_account, clock_account, program_id, whitelist, oracle_auth_pda, message_buffer_data] => {
+            Ok(UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account,
+                delegation_account: None,
+                schedule_account: None,
+                message_buffer_accounts: Some(MessageBufferAccountKeys {
+                    program_id,
+                    whitelist,
+                    oracle_auth_pda,
+                    message_buffer_data,
+                }),
+            })
+        }
+        [publisher, price_account, clock_account, delegation_account, program_id, whitelist, oracle_auth_pda, message_buffer_data] => {
+            Ok(UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account,
+                delegation_account: Some(delegation_account),
+                schedule_account: None,
+                message_buffer_accounts: Some(MessageBufferAccountKeys {
+                    program_id,
+                    whitelist,
+                    oracle_auth_pda,
+                    message_buffer_data,
+                }),
+            })
+        }
+        [publisher, price_account, clock_account, delegation_account, schedule_account, program_id, whitelist, oracle_auth_pda, message_buffer_data] => {
+            Ok(UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account,
+                delegation_account: delegation_slot(price_account, delegation_account),
+                schedule_account: Some(schedule_account),
+                message_buffer_accounts: Some(MessageBufferAccountKeys {
+                    program_id,
+                    whitelist,
+                    oracle_auth_pda,
+                    message_buffer_data,
+                }),
+            })
+        }
+        _ => Err(DecodeError::InvalidNumberOfAccounts {
+            command,
+            actual: account_keys.len(),
+        }),
+    }
+}
+
+/// Decode the instruction `data` sent to the oracle program together with the keys of the
+/// instruction's accounts, in order.
+pub fn decode_instruction(
+    data: &[u8],
+    account_keys: &[Pubkey],
+) -> Result<OracleInstruction, DecodeError> {
+    use OracleCommand::*;
+
+    let header: CommandHeader = data
+        .get(..size_of::<CommandHeader>())
+        .map(pod_read_unaligned)
+        .ok_or(DecodeError::CommandHeaderTooShort {
+            expected: size_of::<CommandHeader>(),
+            actual:   data.len(),
+        })?;
+    let command = load_command_header_checked(bytes_of(&header)).map_err(|err| match err {
+        OracleError::InvalidInstructionVersion => {
+            DecodeError::InvalidInstructionVersion(header.version)
+        }
+        _ => DecodeError::UnrecognizedInstruction(header.command),
+    })?;
+
+    let invalid_number_of_accounts = DecodeError::InvalidNumberOfAccounts {
+        command,
+        actual: account_keys.len(),
+    };
+
+    match command {
+        InitMapping => match *account_keys {
+            [funding_account, mapping_account, permissions_account] => {
+                Ok(OracleInstruction::InitMapping {
+                    funding_account,
+                    mapping_account,
+                    permissions_account,
+                })
+            }
+            _ => Err(invalid_number_of_accounts),
+        },
+        AddProduct => match *account_keys {
+            [funding_account, mapping_account, product_account, permissions_account] => {
+                Ok(OracleInstruction::AddProduct {
+                    funding_account,
+                    mapping_account,
+                    product_account,
+                    permissions_account,
+                    metadata: data[size_of::<CommandHeader>()..].to_vec(),
+                })
+            }
+            _ => Err(invalid_number_of_accounts),
+        },
+        UpdProduct => match *account_keys {
+            [funding_account, product_account, permissions_account] => {
+                Ok(OracleInstruction::UpdProduct {
+                    funding_account,
+                    product_account,
+                    permissions_account,
+                    metadata: data[size_of::<CommandHeader>()..].to_vec(),
+                })
+            }
+            _ => Err(invalid_number_of_accounts),
+        },
+        AddPrice => {
+            let args = read_args::<AddPriceArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, product_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::AddPrice {
+                        funding_account,
+                        product_account,
+                        price_account,
+                        permissions_account,
+                        exponent: args.exponent,
+                        price_type: args.price_type,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        AddPublisher | DelPublisher => {
+            let args = read_exact_args::<AddPublisherArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    if command == AddPublisher {
+                        Ok(OracleInstruction::AddPublisher {
+                            funding_account,
+                            price_account,
+                            permissions_account,
+                            publisher: args.publisher,
+                        })
+                    } else {
+                        Ok(OracleInstruction::DelPublisher {
+                            funding_account,
+                            price_account,
+                            permissions_account,
+                            publisher: args.publisher,
+                        })
+                    }
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        UpdPrice | UpdPriceNoFailOnError | AggPrice => {
+            let args = read_args::<UpdPriceArgs>(command, data)?;
+            let accounts = decode_upd_price_accounts(command, account_keys)?;
+            Ok(match command {
+                AggPrice => OracleInstruction::AggPrice { accounts },
+                UpdPriceNoFailOnError => OracleInstruction::UpdPriceNoFailOnError {
+                    accounts,
+                    status: args.status,
+                    corp_act_status: args.corp_act_status,
+                    price: args.price,
+                    confidence: args.confidence,
+                    publishing_slot: args.publishing_slot,
+                },
+                _ => OracleInstruction::UpdPrice {
+                    accounts,
+                    status: args.status,
+                    corp_act_status: args.corp_act_status,
+                    price: args.price,
+                    confidence: args.confidence,
+                    publishing_slot: args.publishing_slot,
+                },
+            })
+        }
+        InitPrice => {
+            let args = read_args::<AddPriceArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::InitPrice {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        exponent: args.exponent,
+                        price_type: args.price_type,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        SetMinPub => {
+            let args = read_exact_args::<SetMinPubArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::SetMinPub {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        minimum_publishers: args.minimum_publishers,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        DelPrice => match *account_keys {
+            [funding_account, product_account, price_account, permissions_account] => {
+                Ok(OracleInstruction::DelPrice {
+                    funding_account,
+                    product_account,
+                    price_account,
+                    permissions_account,
+                })
+            }
+            _ => Err(invalid_number_of_accounts),
+        },
+        DelProduct => match *account_keys {
+            [funding_account, mapping_account, product_account, permissions_account] => {
+                Ok(OracleInstruction::DelProduct {
+                    funding_account,
+                    mapping_account,
+                    product_account,
+                    permissions_account,
+                })
+            }
+            _ => Err(invalid_number_of_accounts),
+        },
+        UpdPermissions => {
+            let args = read_args::<UpdPermissionsArgs>(command, data)?;
+            match *account_keys {
+                [upgrade_authority, programdata_account, permissions_account, system_program] => {
+                    Ok(OracleInstruction::UpdPermissions {
+                        upgrade_authority,
+                        programdata_account,
+                        permissions_account,
+                        system_program,
+                        master_authority: args.master_authority,
+                        data_curation_authority: args.data_curation_authority,
+                        security_authority: args.security_authority,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        SetMaxLatency => {
+            let args = read_exact_args::<SetMaxLatencyArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::SetMaxLatency {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        max_latency: args.max_latency,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        ResizeMapping => match *account_keys {
+            [mapping_account] => Ok(OracleInstruction::ResizeMapping { mapping_account }),
+            _ => Err(invalid_number_of_accounts),
+        },
+        SetPriceFlags => {
+            let args = read_exact_args::<SetPriceFlagsArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::SetPriceFlags {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        set_flags: args.set_flags,
+                        clear_flags: args.clear_flags,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        RotatePublisher => {
+            let args = read_exact_args::<RotatePublisherArgs>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::RotatePublisher {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        old_publisher: args.old_publisher,
+                        new_publisher: args.new_publisher,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        AddDelegate => {
+            let args = read_exact_args::<AddDelegateArgs>(command, data)?;
+            match *account_keys {
+                [publisher, delegation_account, system_program] => {
+                    Ok(OracleInstruction::AddDelegate {
+                        publisher,
+                        delegation_account,
+                        system_program,
+                        delegate: args.delegate,
+                        expiry_slot: args.expiry_slot,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        DelDelegate => {
+            let args = read_exact_args::<DelDelegateArgs>(command, data)?;
+            match *account_keys {
+                [publisher, delegation_account] => Ok(OracleInstruction::DelDelegate {
+                    publisher,
+                    delegation_account,
+                    delegate: args.delegate,
+                }),
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        UpdPriceBatch => {
+            // The processor accepts 1 to MAX_UPD_PRICE_BATCH_SIZE whole entries after the header.
+            let entries_data = &data[size_of::<CommandHeader>()..];
+            let num_entries = entries_data.len() / size_of::<UpdPriceBatchEntry>();
+            let valid_num_entries = num_entries.clamp(1, MAX_UPD_PRICE_BATCH_SIZE);
+            if entries_data.len() != valid_num_entries * size_of::<UpdPriceBatchEntry>() {
+                return Err(DecodeError::InvalidInstructionDataLength {
+                    command,
+                    expected: size_of::<CommandHeader>()
+                        + valid_num_entries * size_of::<UpdPriceBatchEntry>(),
+                    actual: data.len(),
+                });
+            }
+            let entries: Vec<UpdPriceBatchEntry> = entries_data
+                .chunks_exact(size_of::<UpdPriceBatchEntry>())
+                .map(pod_read_unaligned)
+                .collect();
+            match *account_keys {
+                [funding_account, clock_account, ref price_accounts @ ..]
+                    if price_accounts.len() == entries.len() =>
+                {
+                    Ok(OracleInstruction::UpdPriceBatch {
+                        funding_account,
+                        clock_account,
+                        delegation_account: None,
+                        price_accounts: price_accounts.to_vec(),
+                        entries,
+                    })
+                }
+                [funding_account, clock_account, delegation_account, ref price_accounts @ ..]
+                    if price_accounts.len() == entries.len() =>
+                {
+                    Ok(OracleInstruction::UpdPriceBatch {
+                        funding_account,
+                        clock_account,
+                        delegation_account: Some(delegation_account),
+                        price_accounts: price_accounts.to_vec(),
+                        entries,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        UpdPriceCompact => {
+            let update = CompactUpdPrice::from_bytes(&data[size_of::<CommandHeader>()..])
+                .map_err(|_| DecodeError::InvalidCompactUpdate)?;
+            let accounts = decode_upd_price_accounts(command, account_keys)?;
+            Ok(OracleInstruction::UpdPriceCompact { accounts, update })
+        }
+        SetPublishers => {
+            let args = read_args::<SetPublishersArgs>(command, data)?;
+            let num_add = usize::from(args.num_add);
+            let expected = size_of::<SetPublishersArgs>()
+                + (num_add + usize::from(args.num_remove)) * size_of::<Pubkey>();
+            if data.len() != expected {
+                return Err(DecodeError::InvalidInstructionDataLength {
+                    command,
+                    expected,
+                    actual: data.len(),
+                });
+            }
+            let publishers: Vec<Pubkey> = data[size_of::<SetPublishersArgs>()..]
+                .chunks_exact(size_of::<Pubkey>())
+                .map(pod_read_unaligned)
+                .collect();
+            let mode = SetPublishersMode::from_u8(args.mode)
+                .ok_or(DecodeError::InvalidSetPublishersMode(args.mode))?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::SetPublishers {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        mode,
+                        add: publishers[..num_add].to_vec(),
+                        remove: publishers[num_add..].to_vec(),
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        PauseFeed => {
+            read_exact_args::<CommandHeader>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::PauseFeed {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        ResumeFeed => {
+            read_exact_args::<CommandHeader>(command, data)?;
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::ResumeFeed {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        SetSchedule => {
+            let args = read_args::<SetScheduleArgs>(command, data)?;
+            let num_sessions = usize::from(args.num_sessions);
+            let sessions_end =
+                size_of::<SetScheduleArgs>() + num_sessions * size_of::<TradingSession>();
+            let expected = sessions_end + usize::from(args.num_holidays) * size_of::<Holiday>();
+            if data.len() != expected {
+                return Err(DecodeError::InvalidInstructionDataLength {
+                    command,
+                    expected,
+                    actual: data.len(),
+                });
+            }
+            let sessions: Vec<TradingSession> = data[size_of::<SetScheduleArgs>()..sessions_end]
+                .chunks_exact(size_of::<TradingSession>())
+                .map(pod_read_unaligned)
+                .collect();
+            let holidays: Vec<Holiday> = data[sessions_end..]
+                .chunks_exact(size_of::<Holiday>())
+                .map(pod_read_unaligned)
+                .collect();
+            match *account_keys {
+                [funding_account, price_account, schedule_account, system_program, permissions_account] => {
+                    Ok(OracleInstruction::SetSchedule {
+                        funding_account,
+                        price_account,
+                        schedule_account,
+                        system_program,
+                        permissions_account,
+                        utc_offset: args.utc_offset,
+                        sessions,
+                        holidays,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        SetAggregationConfig => {
+            let config = read_exact_args::<SetAggregationConfigArgs>(command, data)?.config;
+            if !config.is_valid() {
+                return Err(DecodeError::InvalidAggregationConfig);
+            }
+            match *account_keys {
+                [funding_account, price_account, permissions_account] => {
+                    Ok(OracleInstruction::SetAggregationConfig {
+                        funding_account,
+                        price_account,
+                        permissions_account,
+                        mode: config.mode(),
+                        outlier_mads: config.outlier_mads,
+                        widen_stale_conf: config.widen_stale_conf == 1,
+                    })
+                }
+                _ => Err(invalid_number_of_accounts),
+            }
+        }
+        AddMapping | InitTest | UpdTest | ResizePriceAccount | InitPriceFeedIndex => {
+            Err(DecodeError::RemovedInstruction(command))
+        }
+    }
+}
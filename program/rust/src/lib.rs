@@ -2,13 +2,24 @@
 #![allow(non_upper_case_globals)]
 
 mod accounts;
+#[cfg(any(not(target_arch = "bpf"), feature = "rust-aggregation"))]
+pub mod aggregation;
 mod c_oracle_header;
 mod deserialize;
 mod error;
+#[cfg(feature = "library")]
+pub mod events;
+#[cfg(not(feature = "library"))]
+mod events;
+#[cfg(feature = "library")]
+pub mod instruction;
+#[cfg(not(feature = "library"))]
 mod instruction;
 mod processor;
 mod utils;
 
+#[cfg(any(test, feature = "library"))]
+pub mod account_graph;
 #[cfg(any(test, feature = "library"))]
 pub mod validator;
 
@@ -18,9 +29,6 @@ pub use solana_program;
 #[cfg(test)]
 mod tests;
 
-#[cfg(feature = "debug")]
-mod log;
-
 // When compiled in `library` mode the on-chain definitions provided by this library are
 // exported. This is useful when other libraries wish to directly utilise the exact Solana specific
 // on-chain definitions of the Pyth program.
@@ -34,16 +42,28 @@ pub use accounts::MessageType;
 #[cfg(feature = "library")]
 pub use accounts::{
     AccountHeader,
+    AggregationConfig,
+    AggregationMode,
+    CheckedPrice,
     MappingAccount,
     PermissionAccount,
+    Price,
     PriceAccount,
     PriceAccountFlags,
     PriceComponent,
+    PriceCumulative,
     PriceEma,
+    PriceGuardError,
     PriceInfo,
     ProductAccount,
+    ProductAccountData,
+    ProductMetadata,
+    ProductMetadataError,
     PythAccount,
     PythOracleSerialize,
+    BPS_DENOMINATOR,
+    MAX_PC_STR_LEN,
+    MAX_PRODUCT_METADATA_LEN,
 };
 #[cfg(feature = "library")]
 pub use {
@@ -66,4 +86,10 @@ use {
 // We also generate bindings for the constants in oracle.h (as well as other things
 // included in bindings.h).
 
+// With the `rust-aggregation` feature, the C code isn't built at all: the aggregation runs the
+// Rust port in `aggregation.rs`, which the tests check against the C code, and the constants of
+// oracle.h are checked in instead of generated. Price accounts that
+// select another aggregation mode than the C model are always aggregated in Rust, so the default
+// BPF build, which leaves the Rust port out to keep the program small, only accepts the C model.
+
 entrypoint!(process_instruction);
@@ -24,19 +24,30 @@ use {
     },
 };
 
+mod add_delegate;
 mod add_price;
 mod add_product;
 mod add_publisher;
+mod del_delegate;
 mod del_price;
 mod del_product;
 mod del_publisher;
 mod init_mapping;
 mod init_price;
+mod pause_feed;
 mod resize_mapping;
+mod resume_feed;
+mod rotate_publisher;
+mod set_aggregation_config;
 mod set_max_latency;
 mod set_min_pub;
+mod set_price_flags;
+mod set_publishers;
+mod set_schedule;
 mod upd_permissions;
 mod upd_price;
+mod upd_price_batch;
+mod upd_price_compact;
 mod upd_product;
 
 #[cfg(test)]
@@ -50,28 +61,49 @@ use solana_program::{
     sysvar::Sysvar,
 };
 pub use {
+    add_delegate::add_delegate,
     add_price::add_price,
     add_product::add_product,
     add_publisher::add_publisher,
+    del_delegate::del_delegate,
     del_price::del_price,
     del_product::del_product,
     del_publisher::del_publisher,
     init_mapping::init_mapping,
     init_price::init_price,
+    pause_feed::pause_feed,
     resize_mapping::resize_mapping,
+    resume_feed::resume_feed,
+    rotate_publisher::rotate_publisher,
+    set_aggregation_config::set_aggregation_config,
     set_max_latency::set_max_latency,
     set_min_pub::set_min_pub,
+    set_price_flags::set_price_flags,
+    set_publishers::set_publishers,
+    set_schedule::set_schedule,
     upd_permissions::upd_permissions,
     upd_price::{
         c_upd_aggregate,
         c_upd_twap,
         find_publisher_index,
+        upd_aggregate,
         upd_price,
         upd_price_no_fail_on_error,
+        HAS_RUST_AGGREGATION,
     },
+    upd_price_batch::upd_price_batch,
+    upd_price_compact::upd_price_compact,
     upd_product::upd_product,
 };
 
+/// Whether the program includes `AddDelegate` and `DelDelegate`. Without them no delegation
+/// account can be created, so only publishers can sign their updates. The deployed program leaves
+/// them out to stay within its size limit.
+pub const HAS_DELEGATION: bool = cfg!(feature = "delegation");
+
+/// Whether the program includes `UpdPriceBatch` and `UpdPriceCompact`, left out of the deployed
+/// program like the delegation instructions.
+pub const HAS_BATCH_UPDATES: bool = cfg!(feature = "batch-updates");
 
 /// Dispatch to the right instruction in the oracle.
 pub fn process_instruction(
@@ -111,6 +143,24 @@ pub fn process_instruction(
             Err(OracleError::UnrecognizedInstruction.into())
         }
         ResizeMapping => resize_mapping(program_id, accounts, instruction_data),
+        SetPriceFlags => set_price_flags(program_id, accounts, instruction_data),
+        RotatePublisher => rotate_publisher(program_id, accounts, instruction_data),
+        AddDelegate if HAS_DELEGATION => add_delegate(program_id, accounts, instruction_data),
+        DelDelegate if HAS_DELEGATION => del_delegate(program_id, accounts, instruction_data),
+        UpdPriceBatch if HAS_BATCH_UPDATES => {
+            upd_price_batch(program_id, accounts, instruction_data)
+        }
+        UpdPriceCompact if HAS_BATCH_UPDATES => {
+            upd_price_compact(program_id, accounts, instruction_data)
+        }
+        AddDelegate | DelDelegate | UpdPriceBatch | UpdPriceCompact => {
+            Err(OracleError::UnrecognizedInstruction.into())
+        }
+        SetPublishers => set_publishers(program_id, accounts, instruction_data),
+        PauseFeed => pause_feed(program_id, accounts, instruction_data),
+        ResumeFeed => resume_feed(program_id, accounts, instruction_data),
+        SetSchedule => set_schedule(program_id, accounts, instruction_data),
+        SetAggregationConfig => set_aggregation_config(program_id, accounts, instruction_data),
     }
 }
 
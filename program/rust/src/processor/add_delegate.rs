@@ -0,0 +1,118 @@
+use {
+    crate::{
+        accounts::{
+            Delegate,
+            DelegationAccount,
+            PythAccount,
+            DELEGATION_SEED,
+            MAX_DELEGATES,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::AddDelegateArgs,
+        utils::{
+            check_valid_funding_account,
+            check_valid_writable_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program::check_id,
+    },
+    std::mem::size_of,
+};
+
+/// Allow a key to publish prices on behalf of the publisher, creating the publisher's delegation
+/// account if needed. If the key is already a delegate, only its expiry slot is updated.
+// account[0] publisher account     [signer writable]
+// account[1] delegation account    [writable]
+// account[2] system program        []
+pub fn add_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<AddDelegateArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<AddDelegateArgs>()
+            && cmd_args.delegate != Pubkey::default(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (publisher_account, delegation_account, system_program) = match accounts {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(publisher_account)?;
+
+    let (delegation_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[DELEGATION_SEED.as_bytes(), publisher_account.key.as_ref()],
+        program_id,
+    );
+    pyth_assert(
+        delegation_pda_address == *delegation_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+
+    DelegationAccount::initialize_pda(
+        delegation_account,
+        publisher_account,
+        system_program,
+        program_id,
+        &[
+            DELEGATION_SEED.as_bytes(),
+            publisher_account.key.as_ref(),
+            &[bump_seed],
+        ],
+        cmd_args.header.version,
+    )?;
+
+    check_valid_writable_account(program_id, delegation_account)?;
+
+    let mut delegation_data =
+        load_checked::<DelegationAccount>(delegation_account, cmd_args.header.version)?;
+    delegation_data.publisher = *publisher_account.key;
+
+    let num_delegates = try_convert::<u32, usize>(delegation_data.num_delegates)?;
+    match delegation_data.delegates[..num_delegates]
+        .iter()
+        .position(|delegate| delegate.key == cmd_args.delegate)
+    {
+        Some(index) => delegation_data.delegates[index].expiry_slot = cmd_args.expiry_slot,
+        None => {
+            pyth_assert(
+                num_delegates < MAX_DELEGATES,
+                OracleError::TooManyDelegates.into(),
+            )?;
+            delegation_data.delegates[num_delegates] = Delegate {
+                key:         cmd_args.delegate,
+                expiry_slot: cmd_args.expiry_slot,
+            };
+            delegation_data.num_delegates += 1;
+        }
+    }
+
+    Event::DelegateAdded {
+        publisher:   *publisher_account.key,
+        delegate:    cmd_args.delegate,
+        expiry_slot: cmd_args.expiry_slot,
+    }
+    .emit();
+    Ok(())
+}
@@ -28,7 +28,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
 };
@@ -48,7 +47,7 @@ pub fn add_price(
     check_exponent_range(cmd_args.exponent)?;
     pyth_assert(
         cmd_args.price_type != PC_PTYPE_UNKNOWN,
-        ProgramError::InvalidArgument,
+        OracleError::InvalidPriceType.into(),
     )?;
 
 
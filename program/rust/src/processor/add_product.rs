@@ -23,7 +23,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
     std::mem::{
@@ -70,7 +69,7 @@ pub fn add_product(
     // The mapping account must have free space to add the product account
     pyth_assert(
         mapping_data.number_of_products < PC_MAP_TABLE_SIZE,
-        ProgramError::InvalidArgument,
+        OracleError::MappingAccountFull.into(),
     )?;
 
     ProductAccount::initialize(new_product_account, hdr.version)?;
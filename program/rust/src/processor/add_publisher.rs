@@ -11,7 +11,9 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::AddPublisherArgs,
+        processor::set_price_flags::update_price_flags,
         utils::{
             check_permissioned_funding_account,
             check_valid_funding_account,
@@ -24,6 +26,7 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
+        msg,
         program_error::ProgramError,
         program_memory::{
             sol_memcmp,
@@ -72,27 +75,33 @@ pub fn add_publisher(
 
     let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
 
+    // Before `SetPriceFlags`, the `ACCUMULATOR_V2` flag was configured by adding these sentinel
+    // publishers. They are still accepted so that existing tooling keeps working.
     if cmd_args.publisher == Pubkey::from(ENABLE_ACCUMULATOR_V2) {
-        // Hack: we use add_publisher instruction to configure the `ACCUMULATOR_V2` flag. Using a new
-        // instruction would be cleaner but it would require more work in the tooling.
-        // These special cases can be removed along with the v1 aggregation code once the transition
-        // is complete.
-        price_data.flags.insert(PriceAccountFlags::ACCUMULATOR_V2);
-        return Ok(());
+        msg!("Deprecated: use SetPriceFlags to enable ACCUMULATOR_V2");
+        return update_price_flags(
+            price_account.key,
+            &mut price_data,
+            PriceAccountFlags::ACCUMULATOR_V2.bits(),
+            0,
+        );
     } else if cmd_args.publisher == Pubkey::from(DISABLE_ACCUMULATOR_V2) {
-        price_data
-            .flags
-            .remove(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
-        return Ok(());
+        msg!("Deprecated: use SetPriceFlags to disable ACCUMULATOR_V2");
+        return update_price_flags(
+            price_account.key,
+            &mut price_data,
+            0,
+            (PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED).bits(),
+        );
     }
 
     if price_data.num_ >= PC_NUM_COMP {
-        return Err(ProgramError::InvalidArgument);
+        return Err(OracleError::TooManyPublishers.into());
     }
 
     for i in 0..(try_convert::<u32, usize>(price_data.num_)?) {
         if cmd_args.publisher == price_data.comp_[i].pub_ {
-            return Err(ProgramError::InvalidArgument);
+            return Err(OracleError::DuplicatePublisher.into());
         }
     }
 
@@ -110,8 +119,16 @@ pub fn add_publisher(
         let num_comps = try_convert::<u32, usize>(price_data.num_)?;
         sort_price_comps(&mut price_data.comp_, num_comps)?;
     }
+    // The bitmap refers to the components by index
+    price_data.excluded_components = 0;
 
     price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
+
+    Event::PublisherAdded {
+        price_account: *price_account.key,
+        publisher:     cmd_args.publisher,
+    }
+    .emit();
     Ok(())
 }
 
@@ -165,7 +182,10 @@ fn heapsort(v: &mut [(Pubkey, usize)]) {
 ///
 /// num_publishers is the number of publishers in the list that should be sorted. It is explicitly
 /// passed to avoid callers mistake of passing the full slice which may contain uninitialized values.
-fn sort_price_comps(comps: &mut [PriceComponent], num_comps: usize) -> Result<(), ProgramError> {
+pub fn sort_price_comps(
+    comps: &mut [PriceComponent],
+    num_comps: usize,
+) -> Result<(), ProgramError> {
     let comps = comps
         .get_mut(..num_comps)
         .ok_or(ProgramError::InvalidArgument)?;
@@ -0,0 +1,76 @@
+use {
+    crate::{
+        accounts::{
+            Delegate,
+            DelegationAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::DelDelegateArgs,
+        utils::{
+            check_valid_funding_account,
+            check_valid_writable_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Revoke a delegate of the publisher
+// account[0] publisher account     [signer writable]
+// account[1] delegation account    [writable]
+pub fn del_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<DelDelegateArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<DelDelegateArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (publisher_account, delegation_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(publisher_account)?;
+    check_valid_writable_account(program_id, delegation_account)?;
+
+    let mut delegation_data =
+        load_checked::<DelegationAccount>(delegation_account, cmd_args.header.version)?;
+    pyth_assert(
+        delegation_data.publisher == *publisher_account.key,
+        OracleError::PermissionViolation.into(),
+    )?;
+
+    let num_delegates = try_convert::<u32, usize>(delegation_data.num_delegates)?;
+    let index = delegation_data.delegates[..num_delegates]
+        .iter()
+        .position(|delegate| delegate.key == cmd_args.delegate)
+        .ok_or(OracleError::DelegateNotFound)?;
+    delegation_data.delegates[index..num_delegates].rotate_left(1);
+    delegation_data.delegates[num_delegates - 1] = Delegate::zeroed();
+    delegation_data.num_delegates -= 1;
+
+    Event::DelegateRemoved {
+        publisher: *publisher_account.key,
+        delegate:  cmd_args.delegate,
+    }
+    .emit();
+    Ok(())
+}
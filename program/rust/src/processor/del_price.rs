@@ -8,6 +8,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::CommandHeader,
         utils::{
             check_permissioned_funding_account,
@@ -19,7 +20,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
 };
@@ -65,12 +65,12 @@ pub fn del_price(
         let price_data = load_checked::<PriceAccount>(price_account, cmd_args.version)?;
         pyth_assert(
             product_data.first_price_account == *price_account.key,
-            ProgramError::InvalidArgument,
+            OracleError::PriceAccountNotFirst.into(),
         )?;
 
         pyth_assert(
             price_data.product_account == *product_account.key,
-            ProgramError::InvalidArgument,
+            OracleError::ProductAccountMismatch.into(),
         )?;
 
         product_data.first_price_account = price_data.next_price_account;
@@ -83,5 +83,10 @@ pub fn del_price(
     **price_account.lamports.borrow_mut() = 0;
     **funding_account.lamports.borrow_mut() += lamports;
 
+    Event::PriceDeleted {
+        product_account: *product_account.key,
+        price_account:   *price_account.key,
+    }
+    .emit();
     Ok(())
 }
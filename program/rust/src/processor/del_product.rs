@@ -8,6 +8,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::CommandHeader,
         utils::{
             check_permissioned_funding_account,
@@ -20,7 +21,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
     std::mem::{
@@ -76,11 +76,11 @@ pub fn del_product(
         // This assertion is just to make the subtractions below simpler
         pyth_assert(
             mapping_data.number_of_products >= 1,
-            ProgramError::InvalidArgument,
+            OracleError::ProductNotInMapping.into(),
         )?;
         pyth_assert(
             product_data.first_price_account == Pubkey::default(),
-            ProgramError::InvalidArgument,
+            OracleError::ProductHasPriceAccounts.into(),
         )?;
 
         let product_key = product_account.key;
@@ -88,13 +88,13 @@ pub fn del_product(
             .products_list
             .iter()
             .position(|x| *x == *product_key)
-            .ok_or(ProgramError::InvalidArgument)?;
+            .ok_or(OracleError::ProductNotInMapping)?;
 
         let num_after_removal: usize = try_convert(
             mapping_data
                 .number_of_products
                 .checked_sub(1)
-                .ok_or(ProgramError::InvalidArgument)?,
+                .ok_or(OracleError::ProductNotInMapping)?,
         )?;
 
         let last_key_bytes = mapping_data.products_list[num_after_removal];
@@ -114,5 +114,10 @@ pub fn del_product(
     **product_account.lamports.borrow_mut() = 0;
     **funding_account.lamports.borrow_mut() += lamports;
 
+    Event::ProductDeleted {
+        mapping_account: *mapping_account.key,
+        product_account: *product_account.key,
+    }
+    .emit();
     Ok(())
 }
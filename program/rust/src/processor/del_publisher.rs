@@ -9,6 +9,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::DelPublisherArgs,
         utils::{
             check_permissioned_funding_account,
@@ -67,6 +68,8 @@ pub fn del_publisher(
                 price_data.comp_[j - 1] = price_data.comp_[j];
             }
             price_data.num_ -= 1;
+            // The bitmap refers to the components by index
+            price_data.excluded_components = 0;
             let current_index: usize = try_convert(price_data.num_)?;
             sol_memset(
                 bytes_of_mut(&mut price_data.comp_[current_index]),
@@ -74,8 +77,14 @@ pub fn del_publisher(
                 size_of::<PriceComponent>(),
             );
             price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
+
+            Event::PublisherRemoved {
+                price_account: *price_account.key,
+                publisher:     cmd_args.publisher,
+            }
+            .emit();
             return Ok(());
         }
     }
-    Err(ProgramError::InvalidArgument)
+    Err(OracleError::PublisherNotFound.into())
 }
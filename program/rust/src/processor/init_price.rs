@@ -22,7 +22,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         program_memory::sol_memset,
         pubkey::Pubkey,
     },
@@ -59,7 +58,7 @@ pub fn init_price(
     let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
     pyth_assert(
         price_data.price_type == cmd_args.price_type,
-        ProgramError::InvalidArgument,
+        OracleError::PriceTypeMismatch.into(),
     )?;
 
     price_data.exponent = cmd_args.exponent;
@@ -98,6 +97,7 @@ pub fn init_price(
             size_of::<PriceInfo>(),
         );
     }
+    price_data.excluded_components = 0;
 
     Ok(())
 }
@@ -0,0 +1,73 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Pause a price account. Until it is resumed, the aggregate is held at `PC_STATUS_HALTED`
+/// instead of being computed, while publishers can still update their components. Pausing a
+/// paused price account does nothing.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn pause_feed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        cmd_args,
+    )?;
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.version)?;
+    if !price_data.flags.contains(PriceAccountFlags::PAUSED) {
+        price_data.flags.insert(PriceAccountFlags::PAUSED);
+        // Consumers reading the account see the pause right away, without waiting for the next
+        // aggregation.
+        price_data.halt_aggregate();
+        Event::FeedPaused {
+            price_account: *price_account.key,
+        }
+        .emit();
+    }
+    Ok(())
+}
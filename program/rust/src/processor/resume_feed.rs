@@ -0,0 +1,70 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Resume a paused price account. The aggregate stays at `PC_STATUS_HALTED` until the next
+/// aggregation, which uses the components published while the feed was paused. Resuming a price
+/// account that isn't paused does nothing.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn resume_feed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        cmd_args,
+    )?;
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.version)?;
+    if price_data.flags.contains(PriceAccountFlags::PAUSED) {
+        price_data.flags.remove(PriceAccountFlags::PAUSED);
+        Event::FeedResumed {
+            price_account: *price_account.key,
+        }
+        .emit();
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::RotatePublisherArgs,
+        processor::add_publisher::{
+            sort_price_comps,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+        },
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            check_valid_permissions_account,
+            check_valid_writable_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Replace a publisher's key, keeping its component price
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+//
+// The funding account is either the old publisher key or an authority permissioned to rotate
+// publishers.
+pub fn rotate_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<RotatePublisherArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<RotatePublisherArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    pyth_assert(
+        cmd_args.new_publisher != Pubkey::default()
+            && cmd_args.new_publisher != Pubkey::from(ENABLE_ACCUMULATOR_V2)
+            && cmd_args.new_publisher != Pubkey::from(DISABLE_ACCUMULATOR_V2),
+        OracleError::InvalidPublisherKey.into(),
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    if *funding_account.key == cmd_args.old_publisher {
+        check_valid_permissions_account(program_id, permissions_account)?;
+        check_valid_writable_account(program_id, price_account)?;
+    } else {
+        check_permissioned_funding_account(
+            program_id,
+            price_account,
+            funding_account,
+            permissions_account,
+            &cmd_args.header,
+        )?;
+    }
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+
+    let index = price_data.comp_[..num_comps]
+        .iter()
+        .position(|comp| comp.pub_ == cmd_args.old_publisher)
+        .ok_or(OracleError::PublisherNotFound)?;
+    pyth_assert(
+        price_data.comp_[..num_comps]
+            .iter()
+            .all(|comp| comp.pub_ != cmd_args.new_publisher),
+        OracleError::DuplicatePublisher.into(),
+    )?;
+
+    // Only the key changes, the component's latest and aggregate prices move with it
+    price_data.comp_[index].pub_ = cmd_args.new_publisher;
+    sort_price_comps(&mut price_data.comp_, num_comps)?;
+    // The bitmap refers to the components by index
+    price_data.excluded_components = 0;
+
+    Event::PublisherRotated {
+        price_account: *price_account.key,
+        old_publisher: cmd_args.old_publisher,
+        new_publisher: cmd_args.new_publisher,
+    }
+    .emit();
+    Ok(())
+}
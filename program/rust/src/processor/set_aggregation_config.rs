@@ -0,0 +1,75 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::SetAggregationConfigArgs,
+        processor::HAS_RUST_AGGREGATION,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Replace the aggregation config of a price account, which says how its aggregate is computed.
+/// The new config applies from the next aggregation. Without the Rust engine, only the model of the
+/// C code is accepted.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn set_aggregation_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetAggregationConfigArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAggregationConfigArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    pyth_assert(
+        cmd.config.is_valid() && (HAS_RUST_AGGREGATION || cmd.config.is_c_model()),
+        OracleError::InvalidAggregationConfig.into(),
+    )?;
+
+    let mut price_account_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    price_account_data.price_cumulative.aggregation = cmd.config;
+
+    Event::AggregationConfigSet {
+        price_account:    *price_account.key,
+        mode:             cmd.config.mode,
+        outlier_mads:     cmd.config.outlier_mads,
+        widen_stale_conf: cmd.config.widen_stale_conf,
+    }
+    .emit();
+    Ok(())
+}
@@ -5,6 +5,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::SetMaxLatencyArgs,
         utils::{
             check_permissioned_funding_account,
@@ -54,5 +55,10 @@ pub fn set_max_latency(
     let mut price_account_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
     price_account_data.max_latency_ = cmd.max_latency;
 
+    Event::MaxLatencySet {
+        price_account: *price_account.key,
+        max_latency:   cmd.max_latency,
+    }
+    .emit();
     Ok(())
 }
@@ -5,6 +5,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::SetMinPubArgs,
         utils::{
             check_permissioned_funding_account,
@@ -55,5 +56,10 @@ pub fn set_min_pub(
     let mut price_account_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
     price_account_data.min_pub_ = cmd.minimum_publishers;
 
+    Event::MinPubSet {
+        price_account: *price_account.key,
+        min_pub:       cmd.minimum_publishers,
+    }
+    .emit();
     Ok(())
 }
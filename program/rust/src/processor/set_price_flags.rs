@@ -0,0 +1,116 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::SetPriceFlagsArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Flags that can be set by instruction. `MESSAGE_BUFFER_CLEARED` is managed by `upd_price` and
+/// can only be cleared, which makes the next update clear the message buffer again.
+const SETTABLE_FLAGS: PriceAccountFlags = PriceAccountFlags::ACCUMULATOR_V2;
+/// Flags that can be cleared by instruction. `PAUSED` is only changed by `PauseFeed` and
+/// `ResumeFeed`. `SCHEDULED` is set by `SetSchedule`, and clearing it stops following the
+/// schedule.
+const CLEARABLE_FLAGS: PriceAccountFlags = PriceAccountFlags::ACCUMULATOR_V2
+    .union(PriceAccountFlags::MESSAGE_BUFFER_CLEARED)
+    .union(PriceAccountFlags::SCHEDULED);
+
+/// Set and clear price account flags
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn set_price_flags(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPriceFlagsArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPriceFlagsArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    update_price_flags(
+        price_account.key,
+        &mut price_data,
+        cmd.set_flags,
+        cmd.clear_flags,
+    )
+}
+
+/// Set the flags in `set_flags` and clear the ones in `clear_flags`. Fails without changing the
+/// flags if a flag is unknown, can't be set or cleared by instruction, is both set and cleared, or
+/// if the resulting flags are inconsistent.
+pub fn update_price_flags(
+    price_account: &Pubkey,
+    price_data: &mut PriceAccount,
+    set_flags: u8,
+    clear_flags: u8,
+) -> ProgramResult {
+    let set_flags =
+        PriceAccountFlags::from_bits(set_flags).ok_or(OracleError::InvalidPriceFlags)?;
+    let clear_flags =
+        PriceAccountFlags::from_bits(clear_flags).ok_or(OracleError::InvalidPriceFlags)?;
+    pyth_assert(
+        SETTABLE_FLAGS.contains(set_flags)
+            && CLEARABLE_FLAGS.contains(clear_flags)
+            && !set_flags.intersects(clear_flags),
+        OracleError::InvalidPriceFlags.into(),
+    )?;
+
+    let mut flags = price_data.flags;
+    flags.remove(clear_flags);
+    flags.insert(set_flags);
+    // The message buffer is only cleared in accumulator v2 mode, the flag would be stale after
+    // switching back to v1 and prevent clearing the buffer when switching to v2 again.
+    pyth_assert(
+        flags.contains(PriceAccountFlags::ACCUMULATOR_V2)
+            || !flags.contains(PriceAccountFlags::MESSAGE_BUFFER_CLEARED),
+        OracleError::InvalidPriceFlags.into(),
+    )?;
+    price_data.flags = flags;
+
+    Event::PriceFlagsSet {
+        price_account: *price_account,
+        flags:         flags.bits(),
+    }
+    .emit();
+    Ok(())
+}
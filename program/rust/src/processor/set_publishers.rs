@@ -0,0 +1,188 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceComponent,
+            PythAccount,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::{
+            SetPublishersArgs,
+            SetPublishersMode,
+        },
+        processor::add_publisher::{
+            sort_price_comps,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+        },
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    bytemuck::{
+        try_cast_slice,
+        Zeroable,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Add and remove several publishers of a price account at once, sorting the publishers only
+/// once. Either all the changes are applied or none of them. The components of the publishers
+/// that are kept, including their latest prices, are left unchanged.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn set_publishers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetPublishersArgs>(instruction_data)?;
+    let mode =
+        SetPublishersMode::from_u8(cmd_args.mode).ok_or(OracleError::InvalidSetPublishersMode)?;
+    let publishers: &[Pubkey] = try_cast_slice(&instruction_data[size_of::<SetPublishersArgs>()..])
+        .map_err(|_| OracleError::InvalidPublisherList)?;
+    let num_add = usize::from(cmd_args.num_add);
+    pyth_assert(
+        publishers.len() == num_add + usize::from(cmd_args.num_remove),
+        OracleError::InvalidPublisherList.into(),
+    )?;
+    let (add, remove) = publishers.split_at(num_add);
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+    let current: Vec<Pubkey> = price_data.comp_[..num_comps]
+        .iter()
+        .map(|comp| comp.pub_)
+        .collect();
+
+    for (i, publisher) in add.iter().enumerate() {
+        pyth_assert(
+            *publisher != Pubkey::default()
+                && *publisher != Pubkey::from(ENABLE_ACCUMULATOR_V2)
+                && *publisher != Pubkey::from(DISABLE_ACCUMULATOR_V2),
+            OracleError::InvalidPublisherKey.into(),
+        )?;
+        pyth_assert(
+            !add[..i].contains(publisher),
+            OracleError::DuplicatePublisher.into(),
+        )?;
+    }
+
+    let (to_add, to_remove): (Vec<Pubkey>, Vec<Pubkey>) = match mode {
+        SetPublishersMode::Update => {
+            for publisher in add {
+                pyth_assert(
+                    !current.contains(publisher),
+                    OracleError::DuplicatePublisher.into(),
+                )?;
+            }
+            for (i, publisher) in remove.iter().enumerate() {
+                pyth_assert(
+                    !remove[..i].contains(publisher) && !add.contains(publisher),
+                    OracleError::DuplicatePublisher.into(),
+                )?;
+                pyth_assert(
+                    current.contains(publisher),
+                    OracleError::PublisherNotFound.into(),
+                )?;
+            }
+            (add.to_vec(), remove.to_vec())
+        }
+        SetPublishersMode::Exact => {
+            pyth_assert(remove.is_empty(), OracleError::RemoveInExactMode.into())?;
+            (
+                add.iter()
+                    .filter(|publisher| !current.contains(publisher))
+                    .copied()
+                    .collect(),
+                current
+                    .iter()
+                    .filter(|publisher| !add.contains(publisher))
+                    .copied()
+                    .collect(),
+            )
+        }
+    };
+
+    let new_num_comps = num_comps - to_remove.len() + to_add.len();
+    pyth_assert(
+        new_num_comps <= try_convert::<u32, usize>(PC_NUM_COMP)?,
+        OracleError::TooManyPublishers.into(),
+    )?;
+
+    // Remove publishers, keeping the components of the other publishers
+    let mut num_kept = 0;
+    for i in 0..num_comps {
+        if !to_remove.contains(&price_data.comp_[i].pub_) {
+            price_data.comp_[num_kept] = price_data.comp_[i];
+            num_kept += 1;
+        }
+    }
+    // Add publishers with empty components, and clear the components that are no longer used
+    for (i, publisher) in to_add.iter().enumerate() {
+        price_data.comp_[num_kept + i] = PriceComponent {
+            pub_: *publisher,
+            ..PriceComponent::zeroed()
+        };
+    }
+    for comp in price_data
+        .comp_
+        .iter_mut()
+        .take(num_comps)
+        .skip(new_num_comps)
+    {
+        *comp = PriceComponent::zeroed();
+    }
+
+    price_data.num_ = try_convert(new_num_comps)?;
+    sort_price_comps(&mut price_data.comp_, new_num_comps)?;
+    // The bitmap refers to the components by index
+    price_data.excluded_components = 0;
+    price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
+
+    for publisher in to_remove {
+        Event::PublisherRemoved {
+            price_account: *price_account.key,
+            publisher,
+        }
+        .emit();
+    }
+    for publisher in to_add {
+        Event::PublisherAdded {
+            price_account: *price_account.key,
+            publisher,
+        }
+        .emit();
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use {
+    crate::{
+        accounts::{
+            Holiday,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+            ScheduleAccount,
+            TradingSession,
+            SCHEDULE_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        events::Event,
+        instruction::SetScheduleArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            check_valid_writable_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::{
+        pod_read_unaligned,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program::check_id,
+    },
+    std::mem::size_of,
+};
+
+/// Create or replace the trading schedule of a price account, creating its schedule account if
+/// needed, and set the `SCHEDULED` flag of the price account. The flag can be cleared with
+/// `SetPriceFlags` to stop following the schedule, and is set again by the next `SetSchedule`.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] schedule account      [writable]
+// account[3] system program        []
+// account[4] permissions account   []
+pub fn set_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetScheduleArgs>(instruction_data)?;
+    let num_sessions = usize::from(cmd_args.num_sessions);
+    let num_holidays = usize::from(cmd_args.num_holidays);
+    let entries_data = &instruction_data[size_of::<SetScheduleArgs>()..];
+    pyth_assert(
+        entries_data.len()
+            == num_sessions * size_of::<TradingSession>() + num_holidays * size_of::<Holiday>(),
+        ProgramError::InvalidArgument,
+    )?;
+    let (sessions_data, holidays_data) =
+        entries_data.split_at(num_sessions * size_of::<TradingSession>());
+
+    let (funding_account, price_account, schedule_account, system_program, permissions_account) =
+        match accounts {
+            [x, y, s, z, p] => Ok((x, y, s, z, p)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    let mut schedule = ScheduleAccount {
+        price_account: *price_account.key,
+        utc_offset: cmd_args.utc_offset,
+        num_sessions: cmd_args.num_sessions,
+        num_holidays: cmd_args.num_holidays,
+        ..ScheduleAccount::zeroed()
+    };
+    for (session, data) in schedule
+        .sessions
+        .iter_mut()
+        .zip(sessions_data.chunks_exact(size_of::<TradingSession>()))
+    {
+        *session = pod_read_unaligned(data);
+    }
+    for (holiday, data) in schedule
+        .holidays
+        .iter_mut()
+        .zip(holidays_data.chunks_exact(size_of::<Holiday>()))
+    {
+        *holiday = pod_read_unaligned(data);
+    }
+    pyth_assert(schedule.is_valid(), OracleError::InvalidSchedule.into())?;
+
+    let (schedule_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[SCHEDULE_SEED.as_bytes(), price_account.key.as_ref()],
+        program_id,
+    );
+    pyth_assert(
+        schedule_pda_address == *schedule_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+
+    ScheduleAccount::initialize_pda(
+        schedule_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[
+            SCHEDULE_SEED.as_bytes(),
+            price_account.key.as_ref(),
+            &[bump_seed],
+        ],
+        cmd_args.header.version,
+    )?;
+
+    check_valid_writable_account(program_id, schedule_account)?;
+
+    {
+        let mut schedule_data =
+            load_checked::<ScheduleAccount>(schedule_account, cmd_args.header.version)?;
+        schedule.header = schedule_data.header;
+        *schedule_data = schedule;
+    }
+
+    load_checked::<PriceAccount>(price_account, cmd_args.header.version)?
+        .flags
+        .insert(PriceAccountFlags::SCHEDULED);
+
+    Event::ScheduleSet {
+        price_account: *price_account.key,
+        num_sessions:  cmd_args.num_sessions,
+        num_holidays:  cmd_args.num_holidays,
+    }
+    .emit();
+    Ok(())
+}
@@ -9,6 +9,7 @@ use {
             load,
             load_checked,
         },
+        events::Event,
         instruction::UpdPermissionsArgs,
         utils::{
             check_is_upgrade_authority_for_program,
@@ -81,5 +82,11 @@ pub fn upd_permissions(
     permissions_account_data.data_curation_authority = cmd_args.data_curation_authority;
     permissions_account_data.security_authority = cmd_args.security_authority;
 
+    Event::PermissionsUpdated {
+        master_authority:        cmd_args.master_authority,
+        data_curation_authority: cmd_args.data_curation_authority,
+        security_authority:      cmd_args.security_authority,
+    }
+    .emit();
     Ok(())
 }
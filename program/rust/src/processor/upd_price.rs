@@ -1,20 +1,32 @@
 use {
     crate::{
         accounts::{
+            DelegationAccount,
             PriceAccount,
             PriceAccountFlags,
             PriceComponent,
             PriceInfo,
             PythOracleSerialize,
+            ScheduleAccount,
             UPD_PRICE_WRITE_SEED,
         },
+        c_oracle_header::{
+            PC_CORP_ACT_ALL,
+            PC_STATUS_UNKNOWN,
+        },
         deserialize::{
             load,
             load_checked,
         },
-        instruction::UpdPriceArgs,
+        events::Event,
+        instruction::{
+            ComponentUpdate,
+            UpdPriceArgs,
+            UpdPriceReturnData,
+        },
         utils::{
             check_valid_funding_account,
+            check_valid_readable_account,
             check_valid_writable_account,
             get_status_for_conf_price_ratio,
             is_component_update,
@@ -31,7 +43,10 @@ use {
             AccountMeta,
             Instruction,
         },
-        program::invoke_signed,
+        program::{
+            invoke_signed,
+            set_return_data,
+        },
         program_error::ProgramError,
         program_memory::sol_memcmp,
         pubkey::Pubkey,
@@ -39,7 +54,7 @@ use {
     },
 };
 
-#[cfg(target_arch = "bpf")]
+#[cfg(all(target_arch = "bpf", not(feature = "rust-aggregation")))]
 #[link(name = "cpyth-bpf")]
 extern "C" {
     pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
@@ -48,7 +63,7 @@ extern "C" {
     pub fn c_upd_twap(_input: *mut u8, nslots: i64);
 }
 
-#[cfg(not(target_arch = "bpf"))]
+#[cfg(all(not(target_arch = "bpf"), not(feature = "rust-aggregation")))]
 #[link(name = "cpyth-native")]
 extern "C" {
     pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
@@ -57,12 +72,72 @@ extern "C" {
     pub fn c_upd_twap(_input: *mut u8, nslots: i64);
 }
 
+#[cfg(not(feature = "rust-aggregation"))]
 #[inline]
 pub unsafe fn c_upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool {
     c_upd_aggregate_pythnet(input, clock_slot, clock_timestamp)
 }
 
-/// Publish component price, never returning an error even if the update failed
+/// Same as the C `c_upd_aggregate`, computed by the Rust engine. `input` must point to a
+/// `PriceAccount`.
+#[cfg(feature = "rust-aggregation")]
+#[inline]
+pub unsafe fn c_upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool {
+    crate::aggregation::upd_aggregate(
+        &mut *(input as *mut PriceAccount),
+        clock_slot,
+        clock_timestamp,
+    )
+}
+
+/// Same as the C `c_upd_twap`, computed by the Rust engine. `input` must point to a
+/// `PriceAccount`.
+#[cfg(feature = "rust-aggregation")]
+#[inline]
+pub unsafe fn c_upd_twap(input: *mut u8, nslots: i64) {
+    crate::aggregation::upd_twap(&mut *(input as *mut PriceAccount), nslots)
+}
+
+/// Whether the program includes the Rust engine, which aggregates the price accounts whose
+/// `AggregationConfig` isn't the model of the C code. The default BPF build leaves it out to
+/// keep the program small.
+pub const HAS_RUST_AGGREGATION: bool =
+    cfg!(any(not(target_arch = "bpf"), feature = "rust-aggregation"));
+
+/// Aggregate the price account at `input` with its aggregation settings: the C code, or the
+/// Rust engine with the `rust-aggregation` feature, for `AggregationMode::Model` without outlier
+/// filter or widening of stale confidences, and the Rust engine otherwise. `input` must point to
+/// a `PriceAccount`.
+#[cfg(any(not(target_arch = "bpf"), feature = "rust-aggregation"))]
+pub unsafe fn upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool {
+    let aggregation = (*(input as *const PriceAccount))
+        .price_cumulative
+        .aggregation;
+    if aggregation.is_c_model() {
+        // No quote is excluded without the outlier filter
+        (*(input as *mut PriceAccount)).excluded_components = 0;
+        c_upd_aggregate(input, clock_slot, clock_timestamp)
+    } else {
+        crate::aggregation::upd_aggregate_with_mode(
+            &mut *(input as *mut PriceAccount),
+            clock_slot,
+            clock_timestamp,
+            aggregation.mode(),
+        )
+    }
+}
+
+/// Aggregate the price account at `input` with the C code. Without the Rust engine,
+/// `SetAggregationConfig` only accepts the model of the C code. `input` must point to a
+/// `PriceAccount`.
+#[cfg(all(target_arch = "bpf", not(feature = "rust-aggregation")))]
+pub unsafe fn upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool {
+    (*(input as *mut PriceAccount)).excluded_components = 0;
+    c_upd_aggregate(input, clock_slot, clock_timestamp)
+}
+
+/// Publish component price, never returning an error even if the update failed. The error is
+/// reported in the `UpdPriceReturnData` set as return data instead.
 // account[0] funding account       [signer writable]
 // account[1] price account         [writable]
 // account[2] sysvar_clock account  []
@@ -71,21 +146,39 @@ pub fn upd_price_no_fail_on_error(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    match upd_price(program_id, accounts, instruction_data) {
-        Err(_) => Ok(()),
-        Ok(value) => Ok(value),
-    }
+    let return_data = match process_upd_price(program_id, accounts, instruction_data) {
+        Err(error) => UpdPriceReturnData::from_error(error),
+        Ok(return_data) => return_data,
+    };
+    set_return_data(&return_data.to_bytes());
+    Ok(())
 }
 
 /// Update a publisher's price for the provided product. If this update is
 /// the first update in a slot, this operation will also trigger price aggregation
-/// and result in a new aggregate price in the account.
+/// and result in a new aggregate price in the account. If the feed is paused, the aggregate is
+/// held at `PC_STATUS_HALTED` instead, and outside of the trading hours of a scheduled feed at
+/// `PC_STATUS_UNKNOWN`.
 ///
 /// account[0] the publisher's account (funds the tx) [signer writable]
 ///            fails if the publisher's public key is not permissioned for the price account.
 /// account[1] the price account [writable]
 /// account[2] sysvar clock account []
 ///
+/// A delegate of the publisher can sign instead of the publisher. In that case, the publisher's
+/// delegation account follows the clock account, and the message buffer accounts below are
+/// shifted by one.
+///
+/// account[3] delegation account []
+///
+/// Likewise, if the price account has the `SCHEDULED` flag, its schedule account follows the
+/// delegation account. If the publisher signs, the price account is repeated in place of the
+/// delegation account. Updates of a scheduled price account that don't provide its schedule
+/// account fail with `ScheduleAccountRequired`.
+///
+/// account[3] delegation account, or the price account []
+/// account[4] schedule account []
+///
 /// The remaining accounts are *optional*. If provided, they cause this instruction to send a
 /// message containing the price data to the indicated program via CPI. This program is supposed
 /// to be the message buffer program, but it is caller-controlled.
@@ -97,42 +190,127 @@ pub fn upd_price_no_fail_on_error(
 ///            which allows the called-into program to authenticate that it is being invoked by the oracle
 ///            program. []
 /// account[6] message buffer data [writable]
+///
+/// On success, an `UpdPriceReturnData` describing the update is set as return data.
 pub fn upd_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    let return_data = process_upd_price(program_id, accounts, instruction_data)?;
+    set_return_data(&return_data.to_bytes());
+    Ok(())
+}
+
+fn process_upd_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<UpdPriceReturnData, ProgramError> {
     let cmd_args = load::<UpdPriceArgs>(instruction_data)?;
 
-    #[allow(unused_variables)]
-    let (funding_account, price_account, clock_account, maybe_accumulator_accounts) = match accounts
-    {
-        [x, y, z] => Ok((x, y, z, None)),
-        // Note: this version of the instruction exists for backward compatibility when publishers were including a
-        // now superfluous account in the instruction.
-        [x, y, _, z] => Ok((x, y, z, None)),
-        [x, y, z, a, b, c, d] => Ok((
-            x,
-            y,
-            z,
-            Some(MessageBufferAccounts {
-                program_id:          a,
-                whitelist:           b,
-                oracle_auth_pda:     c,
-                message_buffer_data: d,
-            }),
-        )),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let UpdPriceAccounts {
+        funding_account,
+        price_account,
+        clock_account,
+        delegation_account,
+        schedule_account,
+        message_buffer_accounts,
+    } = UpdPriceAccounts::from_accounts(program_id, accounts)?;
 
     check_valid_funding_account(funding_account)?;
-    check_valid_writable_account(program_id, price_account)?;
     // Check clock
     let clock = Clock::from_account_info(clock_account)?;
+    let publisher = get_publisher(
+        program_id,
+        funding_account,
+        delegation_account,
+        &clock,
+        cmd_args.header.version,
+    )?;
+
+    update_price(
+        program_id,
+        accounts,
+        price_account,
+        &publisher,
+        &clock,
+        cmd_args,
+        schedule_account,
+        message_buffer_accounts,
+    )
+}
+
+/// The publisher whose component is updated: the signer itself, or the publisher that delegated
+/// to the signer. Only oracle owned delegation accounts are accepted, and the oracle only creates
+/// them at the publisher's PDA, so there is no need to derive the PDA here.
+pub fn get_publisher(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo,
+    delegation_account: Option<&AccountInfo>,
+    clock: &Clock,
+    version: u32,
+) -> Result<Pubkey, ProgramError> {
+    match delegation_account {
+        None => Ok(*funding_account.key),
+        Some(delegation_account) => {
+            check_valid_readable_account(program_id, delegation_account)?;
+            let delegation_data = load_checked::<DelegationAccount>(delegation_account, version)?;
+            pyth_assert(
+                delegation_data.is_active_delegate(funding_account.key, clock.slot),
+                OracleError::PermissionViolation.into(),
+            )?;
+            Ok(delegation_data.publisher)
+        }
+    }
+}
+
+/// Whether the clock is within the trading hours of a scheduled price account. The schedule
+/// account is required, since the aggregate can be neither held nor updated without knowing the
+/// trading hours. The oracle only creates schedule accounts at the PDA of their price account, so
+/// checking the price account stored in the schedule is enough.
+fn is_trading_session(
+    program_id: &Pubkey,
+    price_account: &AccountInfo,
+    schedule_account: Option<&AccountInfo>,
+    clock: &Clock,
+    version: u32,
+) -> Result<bool, ProgramError> {
+    match schedule_account {
+        None => Err(OracleError::ScheduleAccountRequired.into()),
+        Some(schedule_account) => {
+            check_valid_readable_account(program_id, schedule_account)?;
+            let schedule_data = load_checked::<ScheduleAccount>(schedule_account, version)?;
+            pyth_assert(
+                schedule_data.price_account == *price_account.key,
+                OracleError::InvalidPda.into(),
+            )?;
+            Ok(schedule_data.is_trading(clock.unix_timestamp))
+        }
+    }
+}
+
+/// Store `publisher`'s component price in `price_account` and aggregate the previous slot if
+/// needed, once the signer and the clock have been checked.
+#[allow(clippy::too_many_arguments)]
+pub fn update_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price_account: &AccountInfo,
+    publisher: &Pubkey,
+    clock: &Clock,
+    cmd_args: &UpdPriceArgs,
+    schedule_account: Option<&AccountInfo>,
+    maybe_accumulator_accounts: Option<MessageBufferAccounts>,
+) -> Result<UpdPriceReturnData, ProgramError> {
+    check_valid_writable_account(program_id, price_account)?;
 
     let publisher_index: usize;
     let latest_aggregate_price: PriceInfo;
     let flags: PriceAccountFlags;
+    let mut aggregate_triggered = false;
+    let mut message_sent = false;
+    let mut component = ComponentUpdate::NotUpdated;
 
     // The price_data borrow happens in a scope because it must be
     // dropped before we borrow again as raw data pointer for the C
@@ -143,7 +321,7 @@ pub fn upd_price(
 
         publisher_index = match find_publisher_index(
             &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
-            funding_account.key,
+            publisher,
         ) {
             Some(index) => index,
             None => {
@@ -155,29 +333,59 @@ pub fn upd_price(
         let latest_publisher_price = price_data.comp_[publisher_index].latest_;
 
         // Check that publisher is publishing a more recent price
-        pyth_assert(
-            !is_component_update(cmd_args)?
-                || (cmd_args.publishing_slot > latest_publisher_price.pub_slot_
-                    && cmd_args.publishing_slot <= clock.slot),
-            ProgramError::InvalidArgument,
-        )?;
+        if is_component_update(cmd_args)? {
+            pyth_assert(
+                cmd_args.publishing_slot > latest_publisher_price.pub_slot_,
+                OracleError::StalePublishingSlot.into(),
+            )?;
+            pyth_assert(
+                cmd_args.publishing_slot <= clock.slot,
+                OracleError::FuturePublishingSlot.into(),
+            )?;
+        }
 
         flags = price_data.flags;
     }
 
     if !flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
+        let trading_session = if flags.contains(PriceAccountFlags::SCHEDULED) {
+            is_trading_session(
+                program_id,
+                price_account,
+                schedule_account,
+                clock,
+                cmd_args.header.version,
+            )?
+        } else {
+            true
+        };
+
         // Try to update the aggregate
         #[allow(unused_variables)]
         if clock.slot > latest_aggregate_price.pub_slot_ {
-            let updated = unsafe {
-                // NOTE: c_upd_aggregate must use a raw pointer to price
-                // data. Solana's `<account>.borrow_*` methods require exclusive
-                // access, i.e. no other borrow can exist for the account.
-                c_upd_aggregate(
-                    price_account.try_borrow_mut_data()?.as_mut_ptr(),
-                    clock.slot,
-                    clock.unix_timestamp,
-                )
+            let updated = if flags.contains(PriceAccountFlags::PAUSED) {
+                // The component is still updated below, but the aggregate is held until the feed
+                // is resumed.
+                load_checked::<PriceAccount>(price_account, cmd_args.header.version)?
+                    .skip_paused_aggregate(clock.slot, clock.unix_timestamp);
+                false
+            } else if !trading_session {
+                // Likewise outside of trading hours, until the next session starts.
+                load_checked::<PriceAccount>(price_account, cmd_args.header.version)?
+                    .skip_aggregate(clock.slot, clock.unix_timestamp, PC_STATUS_UNKNOWN);
+                false
+            } else {
+                aggregate_triggered = true;
+                unsafe {
+                    // NOTE: upd_aggregate must use a raw pointer to price
+                    // data. Solana's `<account>.borrow_*` methods require exclusive
+                    // access, i.e. no other borrow can exist for the account.
+                    upd_aggregate(
+                        price_account.try_borrow_mut_data()?.as_mut_ptr(),
+                        clock.slot,
+                        clock.unix_timestamp,
+                    )
+                }
             };
 
             // If the aggregate was successfully updated, calculate the difference and update TWAP.
@@ -198,10 +406,25 @@ pub fn upd_price(
                 price_data.message_sent_ = 0;
                 price_data.update_price_cumulative();
             }
+
+            let price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+            Event::AggregateUpdated {
+                price_account: *price_account.key,
+                price:         price_data.agg_.price_,
+                conf:          price_data.agg_.conf_,
+                exponent:      price_data.exponent,
+                status:        price_data.agg_.status_,
+                num_qt:        price_data.num_qt_,
+                pub_slot:      price_data.agg_.pub_slot_,
+                timestamp:     price_data.timestamp_,
+                ema_price:     price_data.twap_.val_,
+                ema_conf:      price_data.twac_.val_,
+            }
+            .emit();
         }
     }
 
-    // Reload price data as a struct after c_upd_aggregate() borrow is dropped
+    // Reload price data as a struct after upd_aggregate() borrow is dropped
     let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
 
     // Feature-gated accumulator-specific code, used only on pythnet/pythtest
@@ -271,6 +494,7 @@ pub fn upd_price(
             ];
 
             invoke_signed(&create_inputs_ix, accounts, &[auth_seeds_with_bump])?;
+            message_sent = true;
             price_data.message_sent_ = 1;
             if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
                 price_data
@@ -293,11 +517,27 @@ pub fn upd_price(
             publisher_price.price_ = cmd_args.price;
             publisher_price.conf_ = cmd_args.confidence;
             publisher_price.status_ = status;
+            // Publishers used to send anything in this field, so unknown flags are ignored
+            publisher_price.corp_act_status_ = cmd_args.corp_act_status & PC_CORP_ACT_ALL;
             publisher_price.pub_slot_ = cmd_args.publishing_slot;
         }
+
+        component = if status == cmd_args.status {
+            ComponentUpdate::Accepted
+        } else {
+            ComponentUpdate::Ignored
+        };
     }
 
-    Ok(())
+    Ok(UpdPriceReturnData {
+        component,
+        aggregate_triggered,
+        message_sent,
+        agg_status: price_data.agg_.status_,
+        agg_price: price_data.agg_.price_,
+        num_qt: price_data.num_qt_,
+        error: None,
+    })
 }
 
 /// Find the index of the publisher in the list of components.
@@ -360,13 +600,79 @@ pub fn find_publisher_index(comps: &[PriceComponent], key: &Pubkey) -> Option<us
 
 #[allow(dead_code)]
 // Wrapper struct for the accounts required to add data to the accumulator program.
-struct MessageBufferAccounts<'a, 'b: 'a> {
+pub struct MessageBufferAccounts<'a, 'b: 'a> {
     program_id:          &'a AccountInfo<'b>,
     whitelist:           &'a AccountInfo<'b>,
     oracle_auth_pda:     &'a AccountInfo<'b>,
     message_buffer_data: &'a AccountInfo<'b>,
 }
 
+/// The accounts of `UpdPrice`, in any of the layouts documented in `upd_price`.
+pub struct UpdPriceAccounts<'a, 'b: 'a> {
+    pub funding_account:         &'a AccountInfo<'b>,
+    pub price_account:           &'a AccountInfo<'b>,
+    pub clock_account:           &'a AccountInfo<'b>,
+    pub delegation_account:      Option<&'a AccountInfo<'b>>,
+    pub schedule_account:        Option<&'a AccountInfo<'b>>,
+    pub message_buffer_accounts: Option<MessageBufferAccounts<'a, 'b>>,
+}
+
+impl<'a, 'b: 'a> UpdPriceAccounts<'a, 'b> {
+    pub fn from_accounts(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, OracleError> {
+        let (
+            funding_account,
+            price_account,
+            clock_account,
+            oracle_accounts,
+            message_buffer_accounts,
+        ) = match accounts {
+            [x, y, z] => Ok((x, y, z, &[][..], None)),
+            // The delegation and schedule accounts are owned by the oracle, unlike the clock
+            // sysvar which comes last in the legacy layout below.
+            [x, y, z, e @ ..]
+                if e.len() <= 2 && e.iter().all(|account| account.owner == program_id) =>
+            {
+                Ok((x, y, z, e, None))
+            }
+            // Note: this version of the instruction exists for backward compatibility when
+            // publishers were including a now superfluous account in the instruction.
+            [x, y, _, z] => Ok((x, y, z, &[][..], None)),
+            [x, y, z, e @ .., a, b, c, d] if e.len() <= 2 => Ok((
+                x,
+                y,
+                z,
+                e,
+                Some(MessageBufferAccounts {
+                    program_id:          a,
+                    whitelist:           b,
+                    oracle_auth_pda:     c,
+                    message_buffer_data: d,
+                }),
+            )),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+        // The price account takes the place of the delegation account when only the schedule
+        // account is needed, so that each account has a fixed position.
+        let (delegation_account, schedule_account) = match oracle_accounts {
+            [e] => (Some(e), None),
+            [e, s] if e.key == price_account.key => (None, Some(s)),
+            [e, s] => (Some(e), Some(s)),
+            _ => (None, None),
+        };
+        Ok(UpdPriceAccounts {
+            funding_account,
+            price_account,
+            clock_account,
+            delegation_account,
+            schedule_account,
+            message_buffer_accounts,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {
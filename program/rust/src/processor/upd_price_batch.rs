@@ -0,0 +1,112 @@
+use {
+    super::upd_price::{
+        get_publisher,
+        update_price,
+    },
+    crate::{
+        deserialize::load,
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+            UpdPriceArgs,
+            UpdPriceBatchEntry,
+            UpdPriceReturnData,
+            MAX_UPD_PRICE_BATCH_SIZE,
+        },
+        utils::{
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::pod_read_unaligned,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    std::mem::size_of,
+};
+
+/// Publish the publisher's component prices of several price accounts. Each price goes through
+/// the same checks as in `UpdPrice` and triggers the aggregation of its price account if it's the
+/// first update of the slot. A price that fails doesn't fail the instruction, nor prevent the
+/// other prices from being published. This instruction doesn't take schedule accounts, so the
+/// prices of scheduled price accounts fail with `ScheduleAccountRequired`, see `upd_price`.
+///
+/// account[0] the publisher's account (funds the tx) [signer writable]
+/// account[1] sysvar clock account []
+/// account[2] the publisher's delegation account, only if a delegate signs []
+/// account[2..] (or account[3..]) price accounts [writable]
+///
+/// The instruction data is a `CommandHeader` followed by one `UpdPriceBatchEntry` per price
+/// account. The return data is the concatenation of the `UpdPriceReturnData` of each price.
+pub fn upd_price_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+    let entries_data = &instruction_data[size_of::<CommandHeader>()..];
+    let num_entries = entries_data.len() / size_of::<UpdPriceBatchEntry>();
+    pyth_assert(
+        entries_data.len() == num_entries * size_of::<UpdPriceBatchEntry>()
+            && num_entries > 0
+            && num_entries <= MAX_UPD_PRICE_BATCH_SIZE,
+        OracleError::InvalidBatchSize.into(),
+    )?;
+
+    let (funding_account, clock_account, delegation_account, price_accounts) = match accounts {
+        [x, z, prices @ ..] if prices.len() == num_entries => Ok((x, z, None, prices)),
+        [x, z, e, prices @ ..] if prices.len() == num_entries => Ok((x, z, Some(e), prices)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    let clock = Clock::from_account_info(clock_account)?;
+    let publisher = get_publisher(
+        program_id,
+        funding_account,
+        delegation_account,
+        &clock,
+        hdr.version,
+    )?;
+
+    let mut return_data = Vec::with_capacity(num_entries * UpdPriceReturnData::LEN);
+    for (price_account, entry_data) in price_accounts
+        .iter()
+        .zip(entries_data.chunks_exact(size_of::<UpdPriceBatchEntry>()))
+    {
+        let entry: UpdPriceBatchEntry = pod_read_unaligned(entry_data);
+        let cmd_args = UpdPriceArgs {
+            header:          CommandHeader {
+                version: hdr.version,
+                command: OracleCommand::UpdPrice as i32,
+            },
+            status:          entry.status,
+            corp_act_status: entry.corp_act_status,
+            price:           entry.price,
+            confidence:      entry.confidence,
+            publishing_slot: entry.publishing_slot,
+        };
+        let price_return_data = match update_price(
+            program_id,
+            accounts,
+            price_account,
+            &publisher,
+            &clock,
+            &cmd_args,
+            None,
+            None,
+        ) {
+            Err(error) => UpdPriceReturnData::from_error(error),
+            Ok(price_return_data) => price_return_data,
+        };
+        return_data.extend_from_slice(&price_return_data.to_bytes());
+    }
+    set_return_data(&return_data);
+    Ok(())
+}
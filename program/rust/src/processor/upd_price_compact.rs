@@ -0,0 +1,95 @@
+use {
+    super::upd_price::{
+        find_publisher_index,
+        get_publisher,
+        update_price,
+        UpdPriceAccounts,
+    },
+    crate::{
+        accounts::PriceAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            compact::CompactUpdPrice,
+            CommandHeader,
+        },
+        utils::{
+            check_valid_funding_account,
+            check_valid_writable_account,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    std::mem::size_of,
+};
+
+/// Publish component price like `UpdPrice`, with the price, confidence and publishing slot
+/// encoded as a `CompactUpdPrice` relative to the publisher's latest component price and to the
+/// clock slot. Once decoded, the update goes through exactly the same checks and state transition
+/// as `UpdPrice`, and accepts the same account layouts.
+///
+/// account[0] the publisher's account (funds the tx) [signer writable]
+/// account[1] the price account [writable]
+/// account[2] sysvar clock account []
+pub fn upd_price_compact(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+    let update = CompactUpdPrice::from_bytes(&instruction_data[size_of::<CommandHeader>()..])?;
+
+    let UpdPriceAccounts {
+        funding_account,
+        price_account,
+        clock_account,
+        delegation_account,
+        schedule_account,
+        message_buffer_accounts,
+    } = UpdPriceAccounts::from_accounts(program_id, accounts)?;
+
+    check_valid_funding_account(funding_account)?;
+    let clock = Clock::from_account_info(clock_account)?;
+    let publisher = get_publisher(
+        program_id,
+        funding_account,
+        delegation_account,
+        &clock,
+        hdr.version,
+    )?;
+
+    let latest_publisher_price = {
+        check_valid_writable_account(program_id, price_account)?;
+        let price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+        let publisher_index = find_publisher_index(
+            &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+            &publisher,
+        )
+        .ok_or(OracleError::PermissionViolation)?;
+        price_data.comp_[publisher_index].latest_
+    };
+    let cmd_args = update.to_upd_price_args(hdr.version, &latest_publisher_price, clock.slot)?;
+
+    let return_data = update_price(
+        program_id,
+        accounts,
+        price_account,
+        &publisher,
+        &clock,
+        &cmd_args,
+        schedule_account,
+        message_buffer_accounts,
+    )?;
+    set_return_data(&return_data.to_bytes());
+    Ok(())
+}
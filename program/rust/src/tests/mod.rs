@@ -1,30 +1,62 @@
 mod pyth_simulator;
+mod test_account_graph;
 mod test_add_price;
 mod test_add_product;
 mod test_add_publisher;
 mod test_aggregate_v2;
 mod test_aggregation;
+mod test_aggregation_mode;
+mod test_builders;
+#[cfg(not(feature = "rust-aggregation"))]
 mod test_c_code;
 mod test_check_valid_signable_account_or_permissioned_funding_account;
+mod test_checked_price;
+mod test_corp_act;
+mod test_decoder;
 mod test_del_price;
 mod test_del_product;
 mod test_del_publisher;
+#[cfg(feature = "delegation")]
+mod test_delegation;
 mod test_ema;
+mod test_error;
+mod test_events;
 mod test_full_publisher_set;
 mod test_init_mapping;
 mod test_init_price;
 mod test_message;
+mod test_outlier_filter;
+mod test_pause_feed;
 mod test_permission_migration;
+mod test_permissions;
+#[cfg(not(feature = "rust-aggregation"))]
+mod test_price_math;
+mod test_product_metadata;
 mod test_publish;
 mod test_publish_batch;
 mod test_resize_mapping;
+mod test_rotate_publisher;
+#[cfg(not(feature = "rust-aggregation"))]
+mod test_rust_aggregation;
+mod test_schedule;
+#[cfg(feature = "serde")]
+mod test_serde;
 mod test_set_max_latency;
 mod test_set_min_pub;
+mod test_set_price_flags;
+mod test_set_publishers;
 mod test_sizes;
+mod test_stake_weights;
+mod test_stale_conf;
 mod test_upd_aggregate;
 mod test_upd_permissions;
 mod test_upd_price;
+#[cfg(feature = "batch-updates")]
+mod test_upd_price_batch;
+#[cfg(feature = "batch-updates")]
+mod test_upd_price_compact;
 mod test_upd_price_no_fail_on_error;
+mod test_upd_price_return_data;
 mod test_upd_price_with_validator;
 mod test_upd_product;
 mod test_utils;
@@ -1,8 +1,9 @@
 use {
     crate::{
         accounts::{
+            Holiday,
             MappingAccount,
-            PERMISSIONS_SEED,
+            TradingSession,
         },
         c_oracle_header::{
             PC_PROD_ACC_SIZE,
@@ -10,18 +11,12 @@ use {
         },
         deserialize::load,
         instruction::{
-            AddPriceArgs,
-            AddPublisherArgs,
-            CommandHeader,
+            builders,
             OracleCommand,
             UpdPermissionsArgs,
-            UpdPriceArgs,
         },
     },
-    bytemuck::{
-        bytes_of,
-        Pod,
-    },
+    bytemuck::Pod,
     serde::{
         Deserialize,
         Serialize,
@@ -33,17 +28,12 @@ use {
         },
         clock::Clock,
         hash::Hash,
-        instruction::{
-            AccountMeta,
-            Instruction,
-        },
+        instruction::Instruction,
         native_token::LAMPORTS_PER_SOL,
         pubkey::Pubkey,
         rent::Rent,
         stake_history::Epoch,
         system_instruction,
-        system_program,
-        sysvar::SysvarId,
     },
     solana_program_test::{
         read_file,
@@ -59,7 +49,10 @@ use {
             Keypair,
             Signer,
         },
-        transaction::Transaction,
+        transaction::{
+            Transaction,
+            TransactionError,
+        },
     },
     std::{
         collections::HashMap,
@@ -79,11 +72,18 @@ pub struct PythSimulator {
     /// transaction; otherwise, replayed transactions in different states can return stale
     /// results.
     last_blockhash:        Hash,
-    programdata_id:        Pubkey,
     pub upgrade_authority: Keypair,
     pub genesis_keypair:   Keypair,
 }
 
+/// A transaction processed by `PythSimulator::process_ixs_with_metadata`.
+pub struct ProcessedTransaction {
+    pub result:       Result<(), TransactionError>,
+    pub log_messages: Vec<String>,
+    /// The last return data set by the transaction and the program that set it
+    pub return_data:  Option<(Pubkey, Vec<u8>)>,
+}
+
 pub struct Quote {
     pub price:      i64,
     pub confidence: u64,
@@ -156,7 +156,6 @@ impl PythSimulator {
             program_id: program_key,
             context,
             last_blockhash,
-            programdata_id: programdata_key,
             upgrade_authority: upgrade_authority_keypair,
             genesis_keypair: copy_keypair(&genesis_keypair),
         };
@@ -183,14 +182,14 @@ impl PythSimulator {
         result
     }
 
-    /// Process a transaction containing `instructions` signed by `signers`.
+    /// Sign a transaction containing `instructions` with `signers`, with a new blockhash.
     /// `payer` is used to pay for and sign the transaction.
-    async fn process_ixs(
+    async fn sign_ixs(
         &mut self,
         instructions: &[Instruction],
         signers: &Vec<&Keypair>,
         payer: &Keypair,
-    ) -> Result<(), BanksClientError> {
+    ) -> Transaction {
         let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
 
         let blockhash = self
@@ -203,13 +202,48 @@ impl PythSimulator {
 
         transaction.partial_sign(&[payer], self.last_blockhash);
         transaction.partial_sign(signers, self.last_blockhash);
+        transaction
+    }
 
+    /// Process a transaction containing `instructions` signed by `signers`.
+    /// `payer` is used to pay for and sign the transaction.
+    async fn process_ixs(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+        payer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.sign_ixs(instructions, signers, payer).await;
         self.context
             .banks_client
             .process_transaction(transaction)
             .await
     }
 
+    /// Process a transaction like `process_ixs`, returning what clients see of it: its result,
+    /// its logs and its return data.
+    pub async fn process_ixs_with_metadata(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+        payer: &Keypair,
+    ) -> Result<ProcessedTransaction, BanksClientError> {
+        let transaction = self.sign_ixs(instructions, signers, payer).await;
+        let processed = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        let metadata = processed.metadata.unwrap();
+        Ok(ProcessedTransaction {
+            result:       processed.result,
+            log_messages: metadata.log_messages,
+            return_data:  metadata
+                .return_data
+                .map(|return_data| (return_data.program_id, return_data.data)),
+        })
+    }
+
     /// Create an account owned by the pyth program containing `size` bytes.
     /// The account will be created with enough lamports to be rent-exempt.
     pub async fn create_pyth_account(&mut self, size: usize) -> Keypair {
@@ -239,20 +273,15 @@ impl PythSimulator {
     pub async fn init_mapping(&mut self) -> Result<Keypair, BanksClientError> {
         let mapping_keypair = self.create_pyth_account(size_of::<MappingAccount>()).await;
 
-        let cmd: CommandHeader = OracleCommand::InitMapping.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::init_mapping(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![&mapping_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -267,21 +296,17 @@ impl PythSimulator {
     ) -> Result<Keypair, BanksClientError> {
         let product_keypair = self.create_pyth_account(PC_PROD_ACC_SIZE as usize).await;
 
-        let cmd: CommandHeader = OracleCommand::AddProduct.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_product(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
+            &[],
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![mapping_keypair, &product_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -294,21 +319,16 @@ impl PythSimulator {
         mapping_keypair: &Keypair,
         product_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::DelProduct.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::del_product(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![mapping_keypair, product_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -325,25 +345,18 @@ impl PythSimulator {
             .create_pyth_account(size_of::<crate::accounts::PriceAccount>())
             .await;
 
-        let cmd = AddPriceArgs {
-            header:     OracleCommand::AddPrice.into(),
-            exponent:   expo,
-            price_type: PC_PTYPE_PRICE,
-        };
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_price(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
+            expo,
+            PC_PTYPE_PRICE,
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![product_keypair, &price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -356,23 +369,16 @@ impl PythSimulator {
         price_keypair: &Keypair,
         publisher: Pubkey,
     ) -> Result<(), BanksClientError> {
-        let cmd = AddPublisherArgs {
-            header: OracleCommand::AddPublisher.into(),
-            publisher,
-        };
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_publisher(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &price_keypair.pubkey(),
+            &publisher,
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -404,22 +410,15 @@ impl PythSimulator {
         let mut instructions: Vec<Instruction> = vec![];
 
         for (key, price_account) in price_accounts {
-            let cmd = UpdPriceArgs {
-                header:          OracleCommand::UpdPrice.into(),
-                status:          quotes[key].status,
-                unused_:         0,
-                price:           quotes[key].price,
-                confidence:      quotes[key].confidence,
-                publishing_slot: slot,
-            };
-            instructions.push(Instruction::new_with_bytes(
-                self.program_id,
-                bytes_of(&cmd),
-                vec![
-                    AccountMeta::new(publisher.pubkey(), true),
-                    AccountMeta::new(*price_account, false),
-                    AccountMeta::new(Clock::id(), false),
-                ],
+            instructions.push(builders::upd_price(
+                &self.program_id,
+                &publisher.pubkey(),
+                price_account,
+                quotes[key].status,
+                quotes[key].price,
+                quotes[key].confidence,
+                slot,
+                None,
             ));
         }
 
@@ -427,27 +426,91 @@ impl PythSimulator {
             .await
     }
 
+    /// Update price of a component of a scheduled price account, providing its schedule account
+    /// (using the upd_price instruction).
+    pub async fn upd_price_scheduled(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let instruction = builders::upd_price_scheduled(
+            &self.program_id,
+            &publisher.pubkey(),
+            &price_account,
+            quote.status,
+            quote.price,
+            quote.confidence,
+            slot,
+            None,
+        );
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Set the trading schedule of a price account (using the set_schedule instruction).
+    pub async fn set_schedule(
+        &mut self,
+        price_account: Pubkey,
+        utc_offset: i32,
+        sessions: &[TradingSession],
+        holidays: &[Holiday],
+    ) -> Result<(), BanksClientError> {
+        let instruction = builders::set_schedule(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &price_account,
+            utc_offset,
+            sessions,
+            holidays,
+        );
+
+        self.process_ixs(
+            &[instruction],
+            &vec![],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Allow `delegate` to publish on behalf of `publisher` until `expiry_slot` (using the
+    /// add_delegate instruction).
+    #[cfg(feature = "delegation")]
+    pub async fn add_delegate(
+        &mut self,
+        publisher: &Keypair,
+        delegate: Pubkey,
+        expiry_slot: u64,
+    ) -> Result<(), BanksClientError> {
+        let instruction = builders::add_delegate(
+            &self.program_id,
+            &publisher.pubkey(),
+            &delegate,
+            expiry_slot,
+        );
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
     // /// Delete a price account from an existing product account (using the del_price instruction).
     pub async fn del_price(
         &mut self,
         product_keypair: &Keypair,
         price_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::DelPrice.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::del_price(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![product_keypair, price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -462,15 +525,12 @@ impl PythSimulator {
     ) -> Result<Pubkey, BanksClientError> {
         let permissions_pubkey = self.get_permissions_pubkey();
 
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd_args),
-            vec![
-                AccountMeta::new(payer.pubkey(), true),
-                AccountMeta::new_readonly(self.programdata_id, false),
-                AccountMeta::new(permissions_pubkey, false),
-                AccountMeta::new_readonly(system_program::id(), false),
-            ],
+        let instruction = builders::upd_permissions(
+            &self.program_id,
+            &payer.pubkey(),
+            &cmd_args.master_authority,
+            &cmd_args.data_curation_authority,
+            &cmd_args.security_authority,
         );
 
         self.process_ixs(&[instruction], &vec![], payer)
@@ -488,12 +548,7 @@ impl PythSimulator {
         &mut self,
         mapping_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::ResizeMapping.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![AccountMeta::new(mapping_keypair.pubkey(), false)],
-        );
+        let instruction = builders::resize_mapping(&self.program_id, &mapping_keypair.pubkey());
 
         self.process_ixs(
             &[instruction],
@@ -534,10 +589,21 @@ impl PythSimulator {
         .await
     }
 
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
     pub fn get_permissions_pubkey(&self) -> Pubkey {
-        let (permissions_pubkey, __bump) =
-            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.program_id);
-        permissions_pubkey
+        builders::find_permissions_account(&self.program_id)
+    }
+
+    #[cfg(feature = "delegation")]
+    pub fn get_delegation_pubkey(&self, publisher: Pubkey) -> Pubkey {
+        builders::find_delegation_account(&self.program_id, &publisher)
+    }
+
+    pub fn get_schedule_pubkey(&self, price_account: Pubkey) -> Pubkey {
+        builders::find_schedule_account(&self.program_id, &price_account)
     }
 
     /// Setup 3 product accounts with 1 price account each and add a publisher to all of them.
@@ -583,6 +649,18 @@ impl PythSimulator {
     pub async fn warp_to_slot(&mut self, slot: u64) -> Result<(), ProgramTestError> {
         self.context.warp_to_slot(slot)
     }
+
+    /// Set the unix timestamp of the clock, keeping its slot.
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
 }
 
 pub fn copy_keypair(keypair: &Keypair) -> Keypair {
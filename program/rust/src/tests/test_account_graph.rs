@@ -0,0 +1,220 @@
+use {
+    crate::{
+        account_graph::{
+            walk_account_graph,
+            Diagnostic,
+        },
+        accounts::{
+            AccountHeader,
+            MappingAccount,
+            PriceAccount,
+            ProductAccount,
+            ProductMetadata,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_ACCTYPE_PRODUCT,
+            PC_MAGIC,
+            PC_PROD_ACC_SIZE,
+            PC_VERSION,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        mem::size_of,
+    },
+};
+
+fn header<T: PythAccount>() -> AccountHeader {
+    AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: T::ACCOUNT_TYPE,
+        size:         T::INITIAL_SIZE,
+    }
+}
+
+fn mapping_data(next_mapping_account: Pubkey, products: &[Pubkey]) -> Vec<u8> {
+    let mut mapping_account = MappingAccount::zeroed();
+    mapping_account.header = header::<MappingAccount>();
+    mapping_account.number_of_products = products.len() as u32;
+    mapping_account.next_mapping_account = next_mapping_account;
+    mapping_account.products_list[..products.len()].copy_from_slice(products);
+    bytes_of(&mapping_account).to_vec()
+}
+
+fn product_data(first_price_account: Pubkey, symbol: &str) -> Vec<u8> {
+    let mut metadata = ProductMetadata::new();
+    metadata.insert("symbol", symbol);
+    let metadata = metadata.encode().unwrap();
+
+    let mut product_account = ProductAccount {
+        header: header::<ProductAccount>(),
+        first_price_account,
+    };
+    product_account.header.size = (size_of::<ProductAccount>() + metadata.len()) as u32;
+
+    let mut data = bytes_of(&product_account).to_vec();
+    data.extend_from_slice(&metadata);
+    data.resize(PC_PROD_ACC_SIZE as usize, 0);
+    data
+}
+
+fn price_data(product_account: Pubkey, next_price_account: Pubkey) -> Vec<u8> {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.header = header::<PriceAccount>();
+    price_account.product_account = product_account;
+    price_account.next_price_account = next_price_account;
+    bytes_of(&price_account).to_vec()
+}
+
+#[test]
+fn test_walk_account_graph() {
+    let [mapping_1, mapping_2, product_1, product_2, price_1, price_2, price_3] =
+        [(); 7].map(|_| Pubkey::new_unique());
+
+    let mut accounts = HashMap::new();
+    accounts.insert(mapping_1, mapping_data(mapping_2, &[product_1]));
+    accounts.insert(mapping_2, mapping_data(Pubkey::default(), &[product_2]));
+    accounts.insert(product_1, product_data(price_1, "BTC/USD"));
+    accounts.insert(product_2, product_data(price_3, "ETH/USD"));
+    accounts.insert(price_1, price_data(product_1, price_2));
+    accounts.insert(price_2, price_data(product_1, Pubkey::default()));
+    accounts.insert(price_3, price_data(product_2, Pubkey::default()));
+
+    let graph = walk_account_graph(&mapping_1, &accounts);
+    assert_eq!(graph.diagnostics, vec![]);
+    assert_eq!(
+        graph.mappings.iter().map(|x| x.key).collect::<Vec<_>>(),
+        vec![mapping_1, mapping_2]
+    );
+    assert_eq!(
+        graph
+            .products()
+            .map(|x| (x.key, x.metadata.get("symbol").unwrap().into_owned()))
+            .collect::<Vec<_>>(),
+        vec![
+            (product_1, "BTC/USD".to_string()),
+            (product_2, "ETH/USD".to_string())
+        ]
+    );
+    assert_eq!(
+        graph
+            .prices()
+            .map(|(product, price)| (product.key, price.key))
+            .collect::<Vec<_>>(),
+        vec![
+            (product_1, price_1),
+            (product_1, price_2),
+            (product_2, price_3)
+        ]
+    );
+    assert_eq!(
+        graph
+            .prices()
+            .next()
+            .unwrap()
+            .1
+            .price_account
+            .next_price_account,
+        price_2
+    );
+
+    // The same works with a closure
+    let graph = walk_account_graph(&mapping_1, |key: &Pubkey| accounts.get(key).cloned());
+    assert_eq!(graph.prices().count(), 3);
+}
+
+#[test]
+fn test_walk_account_graph_diagnostics() {
+    let [mapping, product_1, product_2, product_3, product_4, price_1, price_2, price_3] =
+        [(); 8].map(|_| Pubkey::new_unique());
+    let missing = Pubkey::new_unique();
+
+    let mut accounts = HashMap::new();
+    accounts.insert(
+        mapping,
+        mapping_data(
+            mapping,
+            &[
+                product_1, product_2, missing, product_3, product_4, product_1,
+            ],
+        ),
+    );
+    // price_2 links back to price_1
+    accounts.insert(product_1, product_data(price_1, "BTC/USD"));
+    accounts.insert(price_1, price_data(product_1, price_2));
+    accounts.insert(price_2, price_data(product_1, price_1));
+    // price_3 thinks it belongs to product_1
+    accounts.insert(product_2, product_data(price_3, "ETH/USD"));
+    accounts.insert(price_3, price_data(product_1, Pubkey::default()));
+    // product_3 is a price account
+    accounts.insert(product_3, price_data(product_1, Pubkey::default()));
+    // product_4 has invalid metadata
+    let mut data = product_data(Pubkey::default(), "SOL/USD");
+    data[size_of::<ProductAccount>()] = 100;
+    accounts.insert(product_4, data);
+
+    let graph = walk_account_graph(&mapping, &accounts);
+    assert_eq!(graph.mappings.len(), 1);
+    assert_eq!(
+        graph.products().map(|x| x.key).collect::<Vec<_>>(),
+        vec![product_1, product_2, product_4]
+    );
+    assert_eq!(graph.prices().count(), 3);
+    assert!(graph.products().nth(2).unwrap().metadata.is_empty());
+
+    assert_eq!(graph.diagnostics.len(), 7);
+    assert_eq!(
+        graph.diagnostics[0],
+        Diagnostic::Cycle {
+            key:           price_1,
+            referenced_by: price_2,
+        }
+    );
+    assert_eq!(
+        graph.diagnostics[1],
+        Diagnostic::ProductMismatch {
+            key:                  price_3,
+            product_account:      product_2,
+            back_pointer_product: product_1,
+        }
+    );
+    assert_eq!(
+        graph.diagnostics[2],
+        Diagnostic::MissingAccount {
+            key:           missing,
+            referenced_by: mapping,
+        }
+    );
+    assert_eq!(
+        graph.diagnostics[3],
+        Diagnostic::InvalidAccountHeader {
+            key:                   product_3,
+            expected_account_type: PC_ACCTYPE_PRODUCT,
+        }
+    );
+    assert!(matches!(
+        graph.diagnostics[4],
+        Diagnostic::InvalidProductMetadata { key, .. } if key == product_4
+    ));
+    assert_eq!(
+        graph.diagnostics[5],
+        Diagnostic::Cycle {
+            key:           product_1,
+            referenced_by: mapping,
+        }
+    );
+    assert_eq!(
+        graph.diagnostics[6],
+        Diagnostic::Cycle {
+            key:           mapping,
+            referenced_by: mapping,
+        }
+    );
+}
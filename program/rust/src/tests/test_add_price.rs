@@ -23,10 +23,7 @@ use {
         tests::test_utils::AccountSetup,
     },
     bytemuck::bytes_of,
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
 };
 
 #[test]
@@ -182,7 +179,7 @@ fn test_add_price() {
             ],
             instruction_data_add_price
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidPriceType.into())
     );
 
     // Fresh product account
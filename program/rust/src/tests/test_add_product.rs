@@ -31,7 +31,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         clock::Epoch,
-        program_error::ProgramError,
         pubkey::Pubkey,
         rent::Rent,
     },
@@ -194,7 +193,7 @@ fn test_add_product() {
             ],
             &instruction_data[..size]
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::MappingAccountFull.into())
     );
 
     let mapping_data = load_checked::<MappingAccount>(&mapping_account, PC_VERSION).unwrap();
@@ -21,7 +21,6 @@ use {
     },
     bytemuck::bytes_of,
     solana_program::{
-        program_error::ProgramError,
         pubkey::Pubkey,
         rent::Rent,
     },
@@ -91,7 +90,7 @@ fn test_add_publisher() {
             ],
             instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::DuplicatePublisher.into())
     );
 
     clear_account(&price_account).unwrap();
@@ -147,7 +146,7 @@ fn test_add_publisher() {
             ],
             instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::TooManyPublishers.into())
     );
 
     // Make sure that publishers are sorted
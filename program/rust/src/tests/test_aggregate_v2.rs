@@ -101,7 +101,7 @@ fn update_price(accounts: &mut Accounts, price: i64, conf: u64, slot: u64) {
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 
     let mut clock = accounts.clock_account.as_account_info();
     clock.is_signer = false;
@@ -20,31 +20,9 @@ fn test_quote_set(input_path_raw: &str) {
     let input_path = input_path_raw.replace("program/rust/", "");
     let result_path = input_path.replace(".json", ".result");
 
-    let file = File::open(input_path).expect("Test file not found");
-    let quote_set: QuoteSet = serde_json::from_reader(&file).expect("Unable to parse JSON");
-
-    let result_file = File::open(result_path).expect("Test file not found");
-    let expected_result: QuoteSetResult =
-        serde_json::from_reader(&result_file).expect("Unable to parse JSON");
-
     let current_slot = 1000; // arbitrary
     let current_timestamp = 1234; // also arbitrary
-    let mut price_account: PriceAccount = PriceAccount::zeroed();
-
-    price_account.last_slot_ = current_slot;
-    price_account.agg_.pub_slot_ = current_slot;
-    price_account.exponent = quote_set.exponent;
-    price_account.num_ = quote_set.quotes.len() as u32;
-    for quote_idx in 0..quote_set.quotes.len() {
-        let mut current_component = &mut price_account.comp_[quote_idx];
-        let quote = &quote_set.quotes[quote_idx];
-        current_component.latest_.status_ = quote.status;
-        current_component.latest_.price_ = quote.price;
-        current_component.latest_.conf_ = quote.conf;
-        let slot_diff = quote.slot_diff.unwrap_or(0);
-        assert!(slot_diff > -(current_slot as i64));
-        current_component.latest_.pub_slot_ = ((current_slot as i64) + slot_diff) as u64;
-    }
+    let mut price_account = load_quote_set(&input_path, current_slot);
 
     unsafe {
         c_upd_aggregate(
@@ -54,6 +32,15 @@ fn test_quote_set(input_path_raw: &str) {
         );
     }
 
+    assert_quote_set_result(&price_account, &result_path);
+}
+
+/// Check the aggregate of `price_account` against the expected result at `result_path`.
+pub(super) fn assert_quote_set_result(price_account: &PriceAccount, result_path: &str) {
+    let result_file = File::open(result_path).expect("Test file not found");
+    let expected_result: QuoteSetResult =
+        serde_json::from_reader(&result_file).expect("Unable to parse JSON");
+
     // For some idiotic reason the status in the input is a number and the output is a string.
     let result_status: String = match price_account.agg_.status_ {
         0 => "unknown",
@@ -75,6 +62,30 @@ fn test_quote_set(input_path_raw: &str) {
     assert_eq!(expected_result, actual_result);
 }
 
+/// Price account with the quotes of the test file at `input_path`, published around
+/// `current_slot`.
+pub(super) fn load_quote_set(input_path: &str, current_slot: u64) -> PriceAccount {
+    let file = File::open(input_path).expect("Test file not found");
+    let quote_set: QuoteSet = serde_json::from_reader(&file).expect("Unable to parse JSON");
+
+    let mut price_account: PriceAccount = PriceAccount::zeroed();
+    price_account.last_slot_ = current_slot;
+    price_account.agg_.pub_slot_ = current_slot;
+    price_account.exponent = quote_set.exponent;
+    price_account.num_ = quote_set.quotes.len() as u32;
+    for quote_idx in 0..quote_set.quotes.len() {
+        let mut current_component = &mut price_account.comp_[quote_idx];
+        let quote = &quote_set.quotes[quote_idx];
+        current_component.latest_.status_ = quote.status;
+        current_component.latest_.price_ = quote.price;
+        current_component.latest_.conf_ = quote.conf;
+        let slot_diff = quote.slot_diff.unwrap_or(0);
+        assert!(slot_diff > -(current_slot as i64));
+        current_component.latest_.pub_slot_ = ((current_slot as i64) + slot_diff) as u64;
+    }
+    price_account
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct Quote {
     price:     i64,
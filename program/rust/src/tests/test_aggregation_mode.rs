@@ -0,0 +1,339 @@
+use {
+    crate::{
+        accounts::{
+            AggregationConfig,
+            AggregationMode,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            PythAccount,
+        },
+        aggregation::weighted_median,
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        events::Event,
+        instruction::{
+            builders,
+            OracleCommand,
+            SetAggregationConfigArgs,
+        },
+        processor::{
+            process_instruction,
+            upd_aggregate,
+        },
+        tests::{
+            test_aggregation::{
+                assert_quote_set_result,
+                load_quote_set,
+            },
+            test_utils::{
+                take_events,
+                update_clock_slot,
+                AccountSetup,
+            },
+        },
+        validator::{
+            self,
+            checked_load_price_account_mut,
+        },
+        OracleError,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    test_generator::test_resources,
+};
+
+/// Aggregate the quote set at `input_path_raw` with `mode` and compare the aggregate with the
+/// result of the same name in `result_dir`.
+fn check_quote_set(input_path_raw: &str, mode: AggregationMode, result_dir: &str) {
+    // For some reason these tests have a different working directory than the macro.
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let result_path = input_path.replace("aggregation/", &format!("aggregation/{result_dir}/"));
+    let result_path = result_path.replace(".json", ".result");
+
+    let current_slot = 1000;
+    let mut price_account = load_quote_set(&input_path, current_slot);
+    price_account.price_cumulative.aggregation.mode = mode as u8;
+    unsafe {
+        upd_aggregate(
+            &mut price_account as *mut PriceAccount as *mut u8,
+            current_slot + 1,
+            1234,
+        );
+    }
+
+    assert_quote_set_result(&price_account, &result_path);
+}
+
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_median(input_path_raw: &str) {
+    check_quote_set(input_path_raw, AggregationMode::Median, "median");
+}
+
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_weighted_median(input_path_raw: &str) {
+    check_quote_set(
+        input_path_raw,
+        AggregationMode::WeightedMedian,
+        "weighted_median",
+    );
+}
+
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_trimmed_mean(input_path_raw: &str) {
+    check_quote_set(input_path_raw, AggregationMode::TrimmedMean, "trimmed_mean");
+}
+
+#[test]
+fn test_weighted_median() {
+    assert_eq!(weighted_median(&mut [(7, 1)]), 7);
+    // Equal weights give the median, with the floor of the average for even counts
+    assert_eq!(weighted_median(&mut [(9, 1), (1, 1), (5, 1)]), 5);
+    assert_eq!(weighted_median(&mut [(4, 3), (1, 3), (2, 3), (-3, 3)]), 1);
+    // A heavy value wins
+    assert_eq!(weighted_median(&mut [(1, 1), (2, 1), (100, 3)]), 100);
+    // Exactly half of the weight on each side: average with the next weighted value
+    assert_eq!(
+        weighted_median(&mut [(10, 2), (12, 0), (20, 1), (30, 1)]),
+        15
+    );
+    assert_eq!(weighted_median(&mut [(10, 1), (11, 1), (20, 0)]), 10);
+    assert_eq!(
+        weighted_median(&mut [(i64::MAX, u64::MAX), (i64::MAX - 2, u64::MAX)]),
+        i64::MAX - 1
+    );
+}
+
+/// Three publishers, of which the first is `funding_account`, and the two others published
+/// 100 +- 10 and 130 +- 1 at slot 1.
+fn init_price_account(price_account: &AccountInfo, funding_account: &AccountInfo) {
+    let mut price_data = PriceAccount::initialize(price_account, PC_VERSION).unwrap();
+    price_data.num_ = 3;
+    price_data.comp_[0].pub_ = *funding_account.key;
+    let quotes = [(100, 10), (130, 1)];
+    for (component, (price, conf)) in price_data.comp_[1..3].iter_mut().zip(quotes) {
+        component.pub_ = Pubkey::new_unique();
+        component.latest_ = PriceInfo {
+            price_:           price,
+            conf_:            conf,
+            status_:          PC_STATUS_TRADING,
+            corp_act_status_: 0,
+            pub_slot_:        1,
+        };
+    }
+}
+
+#[test]
+fn test_set_aggregation_config() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    init_price_account(&price_account, &funding_account);
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .security_authority = *funding_account.key;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let admin_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let config = AggregationConfig::new(AggregationMode::TrimmedMean, 0, false);
+    let instruction = builders::set_aggregation_config(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        config,
+    );
+    assert!(process_instruction(&program_id, &admin_accounts, &instruction.data).is_ok());
+    assert_eq!(
+        take_events(),
+        vec![Event::AggregationConfigSet {
+            price_account:    *price_account.key,
+            mode:             AggregationMode::TrimmedMean as u8,
+            outlier_mads:     0,
+            widen_stale_conf: 0,
+        }]
+    );
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .price_cumulative
+            .aggregation,
+        config
+    );
+
+    // The next aggregation of UpdPrice uses the mode
+    let publish_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    for slot in [1, 2] {
+        update_clock_slot(&mut clock_account, slot);
+        let instruction = builders::upd_price(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            PC_STATUS_TRADING,
+            110,
+            1,
+            slot,
+            None,
+        );
+        process_instruction(&program_id, &publish_accounts, &instruction.data).unwrap();
+    }
+    {
+        // The mean of 110, 100 and 130, and of their distances from it to the far end of their
+        // confidence intervals: 4, 23 and 18
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.num_qt_, 3);
+        assert_eq!(price_data.agg_.price_, 113);
+        assert_eq!(price_data.agg_.conf_, 15);
+    }
+    take_events();
+
+    // Unknown modes, widen_stale_conf values other than 0 and 1 and non-zero unused bytes are
+    // rejected
+    let cmd = SetAggregationConfigArgs {
+        header: OracleCommand::SetAggregationConfig.into(),
+        config: AggregationConfig::new(AggregationMode::Model, 3, true),
+    };
+    for invalid in [
+        AggregationConfig {
+            mode: 4,
+            ..cmd.config
+        },
+        AggregationConfig {
+            widen_stale_conf: 2,
+            ..cmd.config
+        },
+        AggregationConfig {
+            unused_: [0, 0, 0, 0, 1],
+            ..cmd.config
+        },
+    ] {
+        let invalid_cmd = SetAggregationConfigArgs {
+            config: invalid,
+            ..cmd
+        };
+        assert_eq!(
+            process_instruction(&program_id, &admin_accounts, bytes_of(&invalid_cmd)),
+            Err(OracleError::InvalidAggregationConfig.into())
+        );
+    }
+
+    // Only the security authority can set the config
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            bytes_of(&cmd)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    assert!(take_events().is_empty());
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .price_cumulative
+            .aggregation,
+        config
+    );
+
+    // The config is replaced as a whole
+    assert!(process_instruction(&program_id, &admin_accounts, bytes_of(&cmd)).is_ok());
+    assert_eq!(
+        take_events(),
+        vec![Event::AggregationConfigSet {
+            price_account:    *price_account.key,
+            mode:             AggregationMode::Model as u8,
+            outlier_mads:     3,
+            widen_stale_conf: 1,
+        }]
+    );
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .price_cumulative
+            .aggregation,
+        cmd.config
+    );
+}
+
+#[test]
+fn test_aggregation_mode_with_validator() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    init_price_account(&price_account, &funding_account);
+
+    let aggregate = |mode: AggregationMode| {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.flags =
+            PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+        price_data.agg_ = PriceInfo::zeroed();
+        price_data.comp_[0].latest_ = PriceInfo {
+            price_:           110,
+            conf_:            1,
+            status_:          PC_STATUS_TRADING,
+            corp_act_status_: 0,
+            pub_slot_:        1,
+        };
+        price_data.price_cumulative.aggregation.mode = mode as u8;
+        drop(price_data);
+
+        validator::aggregate_price(
+            2,
+            102,
+            price_account.key,
+            checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
+        )
+        .unwrap();
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        (price_data.agg_.price_, price_data.agg_.conf_)
+    };
+
+    // Samples 109, 110, 111, 90, 100, 110, 129, 130, 131
+    assert_eq!(aggregate(AggregationMode::Model), (110, 19));
+    // Distances 1, 20 and 21
+    assert_eq!(aggregate(AggregationMode::Median), (110, 20));
+    // The precise quotes at 110 and 130 outweigh the one at 100
+    assert_eq!(aggregate(AggregationMode::WeightedMedian), (110, 20));
+    assert_eq!(aggregate(AggregationMode::TrimmedMean), (113, 15));
+}
@@ -0,0 +1,153 @@
+use {
+    crate::{
+        accounts::{
+            PERMISSIONS_SEED,
+            UPD_PRICE_WRITE_SEED,
+        },
+        c_oracle_header::{
+            PC_PTYPE_PRICE,
+            PC_STATUS_TRADING,
+        },
+        deserialize::load,
+        instruction::{
+            builders::{
+                self,
+                MessageBufferAccounts,
+            },
+            load_command_header_checked,
+            AddPriceArgs,
+            CommandHeader,
+            OracleCommand,
+            UpdPriceArgs,
+        },
+    },
+    solana_program::{
+        instruction::AccountMeta,
+        pubkey::Pubkey,
+        sysvar::clock,
+    },
+};
+
+#[test]
+fn test_permissioned_builders() {
+    let program_id = Pubkey::new_unique();
+    let funding_account = Pubkey::new_unique();
+    let product_account = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+    let (permissions_account, _) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &program_id);
+
+    let instruction = builders::add_price(
+        &program_id,
+        &funding_account,
+        &product_account,
+        &price_account,
+        -8,
+        PC_PTYPE_PRICE,
+    );
+    assert_eq!(instruction.program_id, program_id);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(funding_account, true),
+            AccountMeta::new(product_account, false),
+            AccountMeta::new(price_account, false),
+            AccountMeta::new(permissions_account, false),
+        ]
+    );
+    let cmd = load::<AddPriceArgs>(&instruction.data).unwrap();
+    assert!(load_command_header_checked(&instruction.data).unwrap() == OracleCommand::AddPrice);
+    assert_eq!(cmd.exponent, -8);
+    assert_eq!(cmd.price_type, PC_PTYPE_PRICE);
+
+    let instruction = builders::upd_product(
+        &program_id,
+        &funding_account,
+        &product_account,
+        &[3, b'k', b'e', b'y', 1, b'v'],
+    );
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(funding_account, true),
+            AccountMeta::new(product_account, false),
+            AccountMeta::new_readonly(permissions_account, false),
+        ]
+    );
+    assert_eq!(
+        instruction.data.len(),
+        std::mem::size_of::<CommandHeader>() + 6
+    );
+    assert!(load_command_header_checked(&instruction.data).unwrap() == OracleCommand::UpdProduct);
+}
+
+#[test]
+fn test_upd_price_builder() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+
+    let instruction = builders::upd_price(
+        &program_id,
+        &publisher,
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        100,
+        None,
+    );
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(publisher, true),
+            AccountMeta::new(price_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+        ]
+    );
+    let cmd = load::<UpdPriceArgs>(&instruction.data).unwrap();
+    assert!(load_command_header_checked(&instruction.data).unwrap() == OracleCommand::UpdPrice);
+    assert_eq!(cmd.status, PC_STATUS_TRADING);
+    assert_eq!(cmd.price, 42);
+    assert_eq!(cmd.confidence, 2);
+    assert_eq!(cmd.publishing_slot, 100);
+
+    let message_buffer_accounts = MessageBufferAccounts {
+        program_id:          Pubkey::new_unique(),
+        whitelist:           Pubkey::new_unique(),
+        message_buffer_data: Pubkey::new_unique(),
+    };
+    let (oracle_auth_pda, _) = Pubkey::find_program_address(
+        &[
+            UPD_PRICE_WRITE_SEED.as_bytes(),
+            &message_buffer_accounts.program_id.to_bytes(),
+        ],
+        &program_id,
+    );
+    let instruction = builders::upd_price_no_fail_on_error(
+        &program_id,
+        &publisher,
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        100,
+        Some(&message_buffer_accounts),
+    );
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(publisher, true),
+            AccountMeta::new(price_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(message_buffer_accounts.program_id, false),
+            AccountMeta::new_readonly(message_buffer_accounts.whitelist, false),
+            AccountMeta::new_readonly(oracle_auth_pda, false),
+            AccountMeta::new(message_buffer_accounts.message_buffer_data, false),
+        ]
+    );
+    assert!(
+        load_command_header_checked(&instruction.data).unwrap()
+            == OracleCommand::UpdPriceNoFailOnError
+    );
+}
@@ -70,3 +70,47 @@ fn test_sar() {
         assert_eq!(c::test_sar(), 0);
     }
 }
+
+#[test]
+fn test_checked_in_constants() {
+    macro_rules! assert_checked_in {
+        ($($name:ident),* $(,)?) => {
+            $(assert_eq!(
+                crate::c_oracle_header::$name as u64,
+                crate::c_oracle_header::checked_in::$name as u64,
+                stringify!($name)
+            );)*
+        };
+    }
+    assert_checked_in!(
+        PC_MAGIC,
+        PC_VERSION,
+        PC_MAX_SEND_LATENCY,
+        PC_MAP_TABLE_SIZE,
+        PC_NUM_COMP_PYTHNET,
+        PC_NUM_COMP,
+        PC_PROD_ACC_SIZE,
+        PC_PTYPE_UNKNOWN,
+        PC_PTYPE_PRICE,
+        PC_STATUS_UNKNOWN,
+        PC_STATUS_TRADING,
+        PC_STATUS_HALTED,
+        PC_STATUS_AUCTION,
+        PC_STATUS_IGNORED,
+        PC_CORP_ACT_NONE,
+        PC_CORP_ACT_SPLIT,
+        PC_CORP_ACT_DIVIDEND,
+        PC_CORP_ACT_SYMBOL_CHANGE,
+        PC_CORP_ACT_ALL,
+        PC_CORP_ACT_NUM_FLAGS,
+        PC_ACCTYPE_MAPPING,
+        PC_ACCTYPE_PRODUCT,
+        PC_ACCTYPE_PRICE,
+        PC_ACCTYPE_PERMISSIONS,
+        PC_ACCTYPE_DELEGATION,
+        PC_ACCTYPE_SCHEDULE,
+        ZSTD_UPPER_BOUND,
+        PC_PRICE_T_COMP_OFFSET,
+        PC_MAP_TABLE_T_PROD_OFFSET,
+    );
+}
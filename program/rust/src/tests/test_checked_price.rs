@@ -0,0 +1,147 @@
+use {
+    crate::{
+        accounts::{
+            CheckedPrice,
+            PriceAccount,
+            PriceGuardError,
+            BPS_DENOMINATOR,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::clock::Clock,
+};
+
+fn clock(unix_timestamp: i64) -> Clock {
+    Clock {
+        unix_timestamp,
+        ..Clock::default()
+    }
+}
+
+fn price_account() -> PriceAccount {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.exponent = -2;
+    price_account.min_pub_ = 3;
+    price_account.num_qt_ = 3;
+    price_account.timestamp_ = 1000;
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.agg_.price_ = 10_000;
+    price_account.agg_.conf_ = 10;
+    price_account.prev_timestamp_ = 990;
+    price_account.prev_price_ = 9_000;
+    price_account.prev_conf_ = 900;
+    price_account.twap_.val_ = 9_500;
+    price_account.twac_.val_ = 50;
+    price_account
+}
+
+#[test]
+fn test_get_price_no_older_than() {
+    let mut price_account = price_account();
+
+    assert_eq!(
+        price_account.get_price_no_older_than(&clock(1005), 5),
+        Ok(CheckedPrice {
+            price:        10_000,
+            conf:         10,
+            exponent:     -2,
+            publish_time: 1000,
+        })
+    );
+    assert_eq!(
+        price_account.get_price_no_older_than(&clock(1006), 5),
+        Err(PriceGuardError::Stale {
+            publish_time: 1000,
+            current_time: 1006,
+        })
+    );
+    assert_eq!(
+        price_account.get_ema_price_no_older_than(&clock(1000), 0),
+        Ok(CheckedPrice {
+            price:        9_500,
+            conf:         50,
+            exponent:     -2,
+            publish_time: 1000,
+        })
+    );
+
+    // Fall back to the previous price while the aggregate isn't trading
+    price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+    assert_eq!(
+        price_account.get_price_no_older_than(&clock(1000), 10),
+        Ok(CheckedPrice {
+            price:        9_000,
+            conf:         900,
+            exponent:     -2,
+            publish_time: 990,
+        })
+    );
+    assert_eq!(
+        price_account
+            .get_ema_price_no_older_than(&clock(1000), 10)
+            .map(|x| x.publish_time),
+        Ok(990)
+    );
+    assert_eq!(
+        price_account.get_price_no_older_than(&clock(1000), 9),
+        Err(PriceGuardError::NotTrading {
+            status: PC_STATUS_UNKNOWN,
+        })
+    );
+
+    price_account.num_qt_ = 2;
+    assert_eq!(
+        price_account.get_ema_price_no_older_than(&clock(1000), 9),
+        Err(PriceGuardError::TooFewPublishers {
+            num_qt:  2,
+            min_pub: 3,
+        })
+    );
+}
+
+#[test]
+fn test_max_conf_ratio() {
+    let mut price_account = price_account();
+
+    // conf / price = 0.1%
+    assert!(price_account
+        .get_price_no_older_than_with_max_conf_ratio(&clock(1000), 0, 10)
+        .is_ok());
+    assert_eq!(
+        price_account.get_price_no_older_than_with_max_conf_ratio(&clock(1000), 0, 9),
+        Err(PriceGuardError::TooUncertain {
+            price: 10_000,
+            conf:  10,
+        })
+    );
+
+    // Staleness is checked first
+    assert_eq!(
+        price_account.get_price_no_older_than_with_max_conf_ratio(&clock(2000), 0, 9),
+        Err(PriceGuardError::Stale {
+            publish_time: 1000,
+            current_time: 2000,
+        })
+    );
+
+    // Negative prices use the absolute value and extreme values don't overflow
+    price_account.agg_.price_ = i64::MIN;
+    price_account.agg_.conf_ = u64::MAX;
+    assert!(price_account
+        .get_price_no_older_than_with_max_conf_ratio(&clock(1000), 0, 20_000)
+        .is_ok());
+    assert!(price_account
+        .get_price_no_older_than_with_max_conf_ratio(&clock(1000), 0, 19_999)
+        .is_err());
+
+    let ema_price = price_account
+        .get_ema_price_no_older_than(&clock(1000), 0)
+        .unwrap();
+    assert!(ema_price.check_conf_ratio(BPS_DENOMINATOR).is_ok());
+    assert!(ema_price.check_conf_ratio(53).is_ok());
+    assert!(ema_price.check_conf_ratio(52).is_err());
+}
@@ -0,0 +1,261 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceInfo,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_CORP_ACT_DIVIDEND,
+            PC_CORP_ACT_NONE,
+            PC_CORP_ACT_SPLIT,
+            PC_CORP_ACT_SYMBOL_CHANGE,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        instruction::builders,
+        processor::{
+            c_upd_aggregate,
+            process_instruction,
+        },
+        tests::test_utils::{
+            take_logged_data,
+            update_clock_slot,
+            AccountSetup,
+        },
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+fn quote(price: i64, corp_act_status: u32) -> PriceInfo {
+    PriceInfo {
+        price_:           price,
+        conf_:            1,
+        status_:          PC_STATUS_TRADING,
+        corp_act_status_: corp_act_status,
+        pub_slot_:        1000,
+    }
+}
+
+/// Aggregate `quotes` at slot 1001 and return the corporate-action status of the aggregate, or
+/// `None` if the aggregation failed.
+fn aggregate(price_account: &AccountInfo, quotes: &[PriceInfo]) -> Option<u32> {
+    {
+        let mut price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+        price_data.num_ = quotes.len() as u32;
+        price_data.agg_.pub_slot_ = 1000;
+        for (component, quote) in price_data.comp_.iter_mut().zip(quotes) {
+            component.latest_ = *quote;
+        }
+    }
+    let aggregated = unsafe {
+        c_upd_aggregate(
+            price_account.try_borrow_mut_data().unwrap().as_mut_ptr(),
+            1001,
+            1,
+        )
+    };
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    for (component, quote) in price_data.comp_.iter().zip(quotes) {
+        assert_eq!(component.agg_.corp_act_status_, quote.corp_act_status_);
+    }
+    aggregated.then_some(price_data.agg_.corp_act_status_)
+}
+
+#[test]
+fn test_aggregate_corp_act() {
+    let program_id = Pubkey::new_unique();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    assert_eq!(
+        aggregate(
+            &price_account,
+            &[quote(100, PC_CORP_ACT_NONE), quote(101, PC_CORP_ACT_NONE)]
+        ),
+        Some(PC_CORP_ACT_NONE)
+    );
+
+    // Each corporate action needs a strict majority of the quotes
+    assert_eq!(
+        aggregate(
+            &price_account,
+            &[
+                quote(100, PC_CORP_ACT_SPLIT),
+                quote(101, PC_CORP_ACT_SPLIT | PC_CORP_ACT_DIVIDEND),
+                quote(102, PC_CORP_ACT_SYMBOL_CHANGE),
+            ]
+        ),
+        Some(PC_CORP_ACT_SPLIT)
+    );
+    assert_eq!(
+        aggregate(
+            &price_account,
+            &[
+                quote(100, PC_CORP_ACT_SPLIT | PC_CORP_ACT_DIVIDEND),
+                quote(101, PC_CORP_ACT_SPLIT | PC_CORP_ACT_DIVIDEND),
+                quote(102, PC_CORP_ACT_DIVIDEND),
+                quote(103, PC_CORP_ACT_NONE),
+            ]
+        ),
+        Some(PC_CORP_ACT_DIVIDEND)
+    );
+
+    // Only the quotes that make up the aggregate count
+    let mut halted = quote(102, PC_CORP_ACT_NONE);
+    halted.status_ = PC_STATUS_UNKNOWN;
+    let mut stale = quote(103, PC_CORP_ACT_NONE);
+    stale.pub_slot_ = 900;
+    assert_eq!(
+        aggregate(
+            &price_account,
+            &[
+                quote(100, PC_CORP_ACT_SYMBOL_CHANGE),
+                quote(101, PC_CORP_ACT_SYMBOL_CHANGE),
+                halted,
+                stale,
+            ]
+        ),
+        Some(PC_CORP_ACT_SYMBOL_CHANGE)
+    );
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .num_qt_,
+        2
+    );
+
+    // Unlike the price, the corporate actions are cleared when the aggregation fails
+    load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .min_pub_ = 3;
+    assert_eq!(
+        aggregate(
+            &price_account,
+            &[quote(100, PC_CORP_ACT_NONE), quote(101, PC_CORP_ACT_NONE)]
+        ),
+        None
+    );
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.agg_.corp_act_status_, PC_CORP_ACT_NONE);
+}
+
+#[test]
+fn test_upd_price_corp_act() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    let publish = |corp_act_status: u32, price: i64, slot: u64| {
+        let instruction = builders::upd_price_with_corp_act(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            PC_STATUS_TRADING,
+            corp_act_status,
+            price,
+            1,
+            slot,
+            None,
+        );
+        process_instruction(&program_id, &accounts, &instruction.data)
+    };
+    let latest = || {
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .comp_[0]
+            .latest_
+    };
+
+    update_clock_slot(&mut clock_account, 1);
+    assert!(publish(PC_CORP_ACT_SPLIT, 42, 1).is_ok());
+    assert_eq!(latest().corp_act_status_, PC_CORP_ACT_SPLIT);
+
+    // The next aggregate reports the split
+    update_clock_slot(&mut clock_account, 2);
+    assert!(publish(PC_CORP_ACT_SPLIT, 21, 2).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.price_, 42);
+        assert_eq!(price_data.agg_.corp_act_status_, PC_CORP_ACT_SPLIT);
+    }
+
+    // Unknown flags are ignored, as publishers used to send anything in this field
+    update_clock_slot(&mut clock_account, 3);
+    assert!(publish(PC_CORP_ACT_SPLIT | PC_CORP_ACT_SYMBOL_CHANGE << 1, 22, 3).is_ok());
+    assert_eq!(latest().price_, 22);
+    assert_eq!(latest().corp_act_status_, PC_CORP_ACT_SPLIT);
+
+    // Compact updates report no corporate action, so the split doesn't stick to them
+    #[cfg(feature = "batch-updates")]
+    {
+        use crate::instruction::compact::CompactUpdPrice;
+
+        update_clock_slot(&mut clock_account, 4);
+        let update = CompactUpdPrice::new(&latest(), 4, PC_STATUS_TRADING, 23, 1, 4).unwrap();
+        let instruction = builders::upd_price_compact(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            &update,
+            None,
+        );
+        assert!(process_instruction(&program_id, &accounts, &instruction.data).is_ok());
+        assert_eq!(latest().price_, 23);
+        assert_eq!(latest().corp_act_status_, PC_CORP_ACT_NONE);
+    }
+
+    // Plain updates report no corporate action
+    update_clock_slot(&mut clock_account, 5);
+    let instruction = builders::upd_price(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        PC_STATUS_TRADING,
+        24,
+        1,
+        5,
+        None,
+    );
+    assert!(process_instruction(&program_id, &accounts, &instruction.data).is_ok());
+    assert_eq!(latest().corp_act_status_, PC_CORP_ACT_NONE);
+
+    update_clock_slot(&mut clock_account, 6);
+    assert!(publish(PC_CORP_ACT_NONE, 25, 6).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.price_, 24);
+        assert_eq!(price_data.agg_.corp_act_status_, PC_CORP_ACT_NONE);
+    }
+    take_logged_data();
+}
@@ -0,0 +1,785 @@
+use {
+    crate::{
+        accounts::{
+            AggregationConfig,
+            AggregationMode,
+            Holiday,
+            TradingSession,
+        },
+        c_oracle_header::{
+            PC_CORP_ACT_DIVIDEND,
+            PC_CORP_ACT_NONE,
+            PC_CORP_ACT_SPLIT,
+            PC_PTYPE_PRICE,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        instruction::{
+            builders::{
+                self,
+                MessageBufferAccounts,
+            },
+            compact::CompactUpdPrice,
+            decoder::{
+                decode_instruction,
+                DecodeError,
+                MessageBufferAccountKeys,
+                OracleInstruction,
+                UpdPriceAccountKeys,
+            },
+            AddPriceArgs,
+            CommandHeader,
+            OracleCommand,
+            SetMinPubArgs,
+            SetPublishersMode,
+            UpdPriceBatchEntry,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        sysvar::clock,
+    },
+};
+
+fn decode(instruction: &Instruction) -> Result<OracleInstruction, DecodeError> {
+    let account_keys: Vec<Pubkey> = instruction.accounts.iter().map(|x| x.pubkey).collect();
+    decode_instruction(&instruction.data, &account_keys)
+}
+
+#[test]
+fn test_decode_builders() {
+    let program_id = Pubkey::new_unique();
+    let funding_account = Pubkey::new_unique();
+    let mapping_account = Pubkey::new_unique();
+    let product_account = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let permissions_account = builders::find_permissions_account(&program_id);
+
+    assert_eq!(
+        decode(&builders::add_product(
+            &program_id,
+            &funding_account,
+            &mapping_account,
+            &product_account,
+            &[1, b'a', 1, b'b'],
+        )),
+        Ok(OracleInstruction::AddProduct {
+            funding_account,
+            mapping_account,
+            product_account,
+            permissions_account,
+            metadata: vec![1, b'a', 1, b'b'],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::add_price(
+            &program_id,
+            &funding_account,
+            &product_account,
+            &price_account,
+            -5,
+            PC_PTYPE_PRICE,
+        )),
+        Ok(OracleInstruction::AddPrice {
+            funding_account,
+            product_account,
+            price_account,
+            permissions_account,
+            exponent: -5,
+            price_type: PC_PTYPE_PRICE,
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::del_publisher(
+            &program_id,
+            &funding_account,
+            &price_account,
+            &publisher,
+        )),
+        Ok(OracleInstruction::DelPublisher {
+            funding_account,
+            price_account,
+            permissions_account,
+            publisher,
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_max_latency(
+            &program_id,
+            &funding_account,
+            &price_account,
+            12,
+        )),
+        Ok(OracleInstruction::SetMaxLatency {
+            funding_account,
+            price_account,
+            permissions_account,
+            max_latency: 12,
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_price_flags(
+            &program_id,
+            &funding_account,
+            &price_account,
+            1,
+            2,
+        )),
+        Ok(OracleInstruction::SetPriceFlags {
+            funding_account,
+            price_account,
+            permissions_account,
+            set_flags: 1,
+            clear_flags: 2,
+        })
+    );
+    assert_eq!(
+        decode(&builders::pause_feed(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::PauseFeed {
+            funding_account,
+            price_account,
+            permissions_account,
+        })
+    );
+    assert_eq!(
+        decode(&builders::resume_feed(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::ResumeFeed {
+            funding_account,
+            price_account,
+            permissions_account,
+        })
+    );
+
+    let mut instruction = builders::set_aggregation_config(
+        &program_id,
+        &funding_account,
+        &price_account,
+        AggregationConfig::new(AggregationMode::TrimmedMean, 3, true),
+    );
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::SetAggregationConfig {
+            funding_account,
+            price_account,
+            permissions_account,
+            mode: AggregationMode::TrimmedMean,
+            outlier_mads: 3,
+            widen_stale_conf: true,
+        })
+    );
+    let valid_data = instruction.data.clone();
+    // An unknown mode, a widen_stale_conf of 2 and a non-zero unused byte
+    for (offset, value) in [(8, 4), (10, 2), (15, 1)] {
+        instruction.data = valid_data.clone();
+        instruction.data[offset] = value;
+        assert_eq!(
+            decode(&instruction),
+            Err(DecodeError::InvalidAggregationConfig)
+        );
+    }
+
+    let new_publisher = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::rotate_publisher(
+            &program_id,
+            &publisher,
+            &price_account,
+            &publisher,
+            &new_publisher,
+        )),
+        Ok(OracleInstruction::RotatePublisher {
+            funding_account: publisher,
+            price_account,
+            permissions_account,
+            old_publisher: publisher,
+            new_publisher,
+        })
+    );
+
+    let delegate = Pubkey::new_unique();
+    let delegation_account = builders::find_delegation_account(&program_id, &publisher);
+    let instruction = builders::add_delegate(&program_id, &publisher, &delegate, 100);
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::AddDelegate {
+            publisher,
+            delegation_account,
+            system_program: instruction.accounts[2].pubkey,
+            delegate,
+            expiry_slot: 100,
+        })
+    );
+    assert_eq!(
+        decode(&builders::del_delegate(&program_id, &publisher, &delegate)),
+        Ok(OracleInstruction::DelDelegate {
+            publisher,
+            delegation_account,
+            delegate,
+        })
+    );
+
+    let new_publisher = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::set_publishers(
+            &program_id,
+            &funding_account,
+            &price_account,
+            &[new_publisher],
+            &[publisher],
+        )),
+        Ok(OracleInstruction::SetPublishers {
+            funding_account,
+            price_account,
+            permissions_account,
+            mode: SetPublishersMode::Update,
+            add: vec![new_publisher],
+            remove: vec![publisher],
+        })
+    );
+    let mut instruction = builders::set_exact_publishers(
+        &program_id,
+        &funding_account,
+        &price_account,
+        &[publisher, new_publisher],
+    );
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::SetPublishers {
+            funding_account,
+            price_account,
+            permissions_account,
+            mode: SetPublishersMode::Exact,
+            add: vec![publisher, new_publisher],
+            remove: vec![],
+        })
+    );
+    instruction.data[8] = 2;
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidSetPublishersMode(2))
+    );
+    instruction.data.pop();
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidInstructionDataLength {
+            command:  OracleCommand::SetPublishers,
+            expected: 76,
+            actual:   75,
+        })
+    );
+
+    let sessions = [TradingSession {
+        start_minute: 570,
+        end_minute:   960,
+    }];
+    let holidays = [Holiday {
+        day:          19723,
+        start_minute: 0,
+        end_minute:   0,
+    }];
+    let mut instruction = builders::set_schedule(
+        &program_id,
+        &funding_account,
+        &price_account,
+        -18000,
+        &sessions,
+        &holidays,
+    );
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::SetSchedule {
+            funding_account,
+            price_account,
+            schedule_account: builders::find_schedule_account(&program_id, &price_account),
+            system_program: instruction.accounts[3].pubkey,
+            permissions_account,
+            utc_offset: -18000,
+            sessions: sessions.to_vec(),
+            holidays: holidays.to_vec(),
+        })
+    );
+    instruction.data.pop();
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidInstructionDataLength {
+            command:  OracleCommand::SetSchedule,
+            expected: 28,
+            actual:   27,
+        })
+    );
+
+    let upgrade_authority = Pubkey::new_unique();
+    let instruction = builders::upd_permissions(
+        &program_id,
+        &upgrade_authority,
+        &funding_account,
+        &mapping_account,
+        &publisher,
+    );
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::UpdPermissions {
+            upgrade_authority,
+            programdata_account: builders::find_programdata_account(&program_id),
+            permissions_account,
+            system_program: instruction.accounts[3].pubkey,
+            master_authority: funding_account,
+            data_curation_authority: mapping_account,
+            security_authority: publisher,
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::resize_mapping(&program_id, &mapping_account)),
+        Ok(OracleInstruction::ResizeMapping { mapping_account })
+    );
+}
+
+#[test]
+fn test_decode_upd_price() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+
+    let mut instruction = builders::upd_price(
+        &program_id,
+        &publisher,
+        &price_account,
+        PC_STATUS_TRADING,
+        100,
+        5,
+        42,
+        None,
+    );
+    let accounts = UpdPriceAccountKeys {
+        publisher,
+        price_account,
+        clock_account: clock::id(),
+        delegation_account: None,
+        schedule_account: None,
+        message_buffer_accounts: None,
+    };
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::UpdPrice {
+            accounts,
+            status: PC_STATUS_TRADING,
+            corp_act_status: PC_CORP_ACT_NONE,
+            price: 100,
+            confidence: 5,
+            publishing_slot: 42,
+        })
+    );
+    assert_eq!(
+        decode(&builders::upd_price_with_corp_act(
+            &program_id,
+            &publisher,
+            &price_account,
+            PC_STATUS_TRADING,
+            PC_CORP_ACT_SPLIT | PC_CORP_ACT_DIVIDEND,
+            100,
+            5,
+            42,
+            None,
+        )),
+        Ok(OracleInstruction::UpdPrice {
+            accounts,
+            status: PC_STATUS_TRADING,
+            corp_act_status: PC_CORP_ACT_SPLIT | PC_CORP_ACT_DIVIDEND,
+            price: 100,
+            confidence: 5,
+            publishing_slot: 42,
+        })
+    );
+
+    // Legacy layout where the account before the clock is ignored
+    let account_keys = [publisher, price_account, Pubkey::new_unique(), clock::id()];
+    assert_eq!(
+        decode_instruction(&instruction.data, &account_keys),
+        decode(&instruction)
+    );
+
+    // The data doesn't have to be aligned
+    let mut unaligned_data = vec![0u8];
+    unaligned_data.extend_from_slice(&instruction.data);
+    assert_eq!(
+        decode_instruction(&unaligned_data[1..], &account_keys),
+        decode(&instruction)
+    );
+
+    let message_buffer_accounts = MessageBufferAccounts {
+        program_id:          Pubkey::new_unique(),
+        whitelist:           Pubkey::new_unique(),
+        message_buffer_data: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        decode(&builders::upd_price_no_fail_on_error(
+            &program_id,
+            &publisher,
+            &price_account,
+            PC_STATUS_TRADING,
+            100,
+            5,
+            42,
+            Some(&message_buffer_accounts),
+        )),
+        Ok(OracleInstruction::UpdPriceNoFailOnError {
+            accounts:        UpdPriceAccountKeys {
+                message_buffer_accounts: Some(MessageBufferAccountKeys {
+                    program_id:          message_buffer_accounts.program_id,
+                    whitelist:           message_buffer_accounts.whitelist,
+                    oracle_auth_pda:     builders::find_upd_price_write_authority(
+                        &program_id,
+                        &message_buffer_accounts.program_id,
+                    ),
+                    message_buffer_data: message_buffer_accounts.message_buffer_data,
+                }),
+                ..accounts
+            },
+            status:          PC_STATUS_TRADING,
+            corp_act_status: PC_CORP_ACT_NONE,
+            price:           100,
+            confidence:      5,
+            publishing_slot: 42,
+        })
+    );
+
+    // Delegated updates add the publisher's delegation account after the clock
+    let delegate = Pubkey::new_unique();
+    let delegation_account = builders::find_delegation_account(&program_id, &publisher);
+    let delegated_accounts = UpdPriceAccountKeys {
+        publisher: delegate,
+        delegation_account: Some(delegation_account),
+        ..accounts
+    };
+    let delegated_instruction = builders::upd_price_as_delegate(
+        &program_id,
+        &delegate,
+        &publisher,
+        &price_account,
+        PC_STATUS_TRADING,
+        100,
+        5,
+        42,
+        None,
+    );
+    assert_eq!(
+        decode(&delegated_instruction),
+        Ok(OracleInstruction::UpdPrice {
+            accounts:        delegated_accounts,
+            status:          PC_STATUS_TRADING,
+            corp_act_status: PC_CORP_ACT_NONE,
+            price:           100,
+            confidence:      5,
+            publishing_slot: 42,
+        })
+    );
+    assert_eq!(
+        decode(&builders::upd_price_as_delegate(
+            &program_id,
+            &delegate,
+            &publisher,
+            &price_account,
+            PC_STATUS_TRADING,
+            100,
+            5,
+            42,
+            Some(&message_buffer_accounts),
+        ))
+        .map(|instruction| match instruction {
+            OracleInstruction::UpdPrice { accounts, .. } => accounts.delegation_account,
+            _ => None,
+        }),
+        Ok(Some(delegation_account))
+    );
+
+    // Scheduled updates add the schedule account after the delegation slot, which the price
+    // account fills when the publisher signs
+    let schedule_account = builders::find_schedule_account(&program_id, &price_account);
+    let scheduled_instruction = builders::upd_price_scheduled(
+        &program_id,
+        &publisher,
+        &price_account,
+        PC_STATUS_TRADING,
+        100,
+        5,
+        42,
+        None,
+    );
+    assert_eq!(
+        decode(&scheduled_instruction),
+        Ok(OracleInstruction::UpdPrice {
+            accounts:        UpdPriceAccountKeys {
+                schedule_account: Some(schedule_account),
+                ..accounts
+            },
+            status:          PC_STATUS_TRADING,
+            corp_act_status: PC_CORP_ACT_NONE,
+            price:           100,
+            confidence:      5,
+            publishing_slot: 42,
+        })
+    );
+    let delegated_keys = [
+        delegate,
+        price_account,
+        clock::id(),
+        delegation_account,
+        schedule_account,
+    ];
+    assert_eq!(
+        decode_instruction(&scheduled_instruction.data, &delegated_keys).map(|instruction| {
+            match instruction {
+                OracleInstruction::UpdPrice { accounts, .. } => accounts,
+                _ => panic!("unexpected instruction {instruction:?}"),
+            }
+        }),
+        Ok(UpdPriceAccountKeys {
+            schedule_account: Some(schedule_account),
+            ..delegated_accounts
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::agg_price(
+            &program_id,
+            &publisher,
+            &price_account
+        )),
+        Ok(OracleInstruction::AggPrice { accounts })
+    );
+
+    instruction.accounts.pop();
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidNumberOfAccounts {
+            command: OracleCommand::UpdPrice,
+            actual:  2,
+        })
+    );
+
+    instruction.data.pop();
+    assert_eq!(
+        decode_instruction(&instruction.data, &account_keys),
+        Err(DecodeError::InstructionDataTooShort {
+            command:  OracleCommand::UpdPrice,
+            expected: 40,
+            actual:   39,
+        })
+    );
+}
+
+#[test]
+fn test_decode_upd_price_batch() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let delegate = Pubkey::new_unique();
+    let price_accounts = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let entries = vec![
+        UpdPriceBatchEntry {
+            status:          PC_STATUS_TRADING,
+            corp_act_status: 0,
+            price:           42,
+            confidence:      1,
+            publishing_slot: 10,
+        },
+        UpdPriceBatchEntry {
+            status:          PC_STATUS_TRADING,
+            corp_act_status: 0,
+            price:           -7,
+            confidence:      3,
+            publishing_slot: 11,
+        },
+    ];
+    let prices: Vec<(Pubkey, UpdPriceBatchEntry)> = price_accounts
+        .iter()
+        .copied()
+        .zip(entries.iter().copied())
+        .collect();
+
+    assert_eq!(
+        decode(&builders::upd_price_batch(&program_id, &publisher, &prices)),
+        Ok(OracleInstruction::UpdPriceBatch {
+            funding_account:    publisher,
+            clock_account:      clock::id(),
+            delegation_account: None,
+            price_accounts:     price_accounts.clone(),
+            entries:            entries.clone(),
+        })
+    );
+
+    let mut instruction =
+        builders::upd_price_batch_as_delegate(&program_id, &delegate, &publisher, &prices);
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::UpdPriceBatch {
+            funding_account: delegate,
+            clock_account: clock::id(),
+            delegation_account: Some(builders::find_delegation_account(&program_id, &publisher)),
+            price_accounts,
+            entries,
+        })
+    );
+
+    instruction.accounts.truncate(3);
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidNumberOfAccounts {
+            command: OracleCommand::UpdPriceBatch,
+            actual:  3,
+        })
+    );
+
+    instruction.data.pop();
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidInstructionDataLength {
+            command:  OracleCommand::UpdPriceBatch,
+            expected: 40,
+            actual:   71,
+        })
+    );
+    instruction.data.truncate(8);
+    assert_eq!(
+        decode(&instruction),
+        Err(DecodeError::InvalidInstructionDataLength {
+            command:  OracleCommand::UpdPriceBatch,
+            expected: 40,
+            actual:   8,
+        })
+    );
+}
+
+#[test]
+fn test_decode_upd_price_compact() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+    let update = CompactUpdPrice {
+        status:      PC_STATUS_TRADING,
+        price_delta: -3,
+        conf_delta:  1,
+        slot_lag:    2,
+    };
+
+    let mut instruction =
+        builders::upd_price_compact(&program_id, &publisher, &price_account, &update, None);
+    assert_eq!(
+        decode(&instruction),
+        Ok(OracleInstruction::UpdPriceCompact {
+            accounts: UpdPriceAccountKeys {
+                publisher,
+                price_account,
+                clock_account: clock::id(),
+                delegation_account: None,
+                schedule_account: None,
+                message_buffer_accounts: None,
+            },
+            update,
+        })
+    );
+
+    instruction.data.push(0);
+    assert_eq!(decode(&instruction), Err(DecodeError::InvalidCompactUpdate));
+}
+
+#[test]
+fn test_decode_errors() {
+    let keys = [Pubkey::new_unique(); 3];
+
+    assert_eq!(
+        decode_instruction(&[2, 0, 0], &keys),
+        Err(DecodeError::CommandHeaderTooShort {
+            expected: 8,
+            actual:   3,
+        })
+    );
+
+    let mut cmd: CommandHeader = OracleCommand::InitMapping.into();
+    cmd.version = PC_VERSION + 1;
+    assert_eq!(
+        decode_instruction(bytes_of(&cmd), &keys),
+        Err(DecodeError::InvalidInstructionVersion(PC_VERSION + 1))
+    );
+
+    cmd.version = PC_VERSION;
+    cmd.command = 1000;
+    assert_eq!(
+        decode_instruction(bytes_of(&cmd), &keys),
+        Err(DecodeError::UnrecognizedInstruction(1000))
+    );
+
+    for command in [
+        OracleCommand::AddMapping,
+        OracleCommand::InitTest,
+        OracleCommand::UpdTest,
+        OracleCommand::ResizePriceAccount,
+        OracleCommand::InitPriceFeedIndex,
+    ] {
+        let cmd: CommandHeader = command.into();
+        assert_eq!(
+            decode_instruction(bytes_of(&cmd), &keys),
+            Err(DecodeError::RemovedInstruction(command))
+        );
+    }
+
+    // set_min_pub rejects trailing bytes
+    let cmd = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 3,
+        unused_:            [0; 3],
+    };
+    let mut data = bytes_of(&cmd).to_vec();
+    data.push(0);
+    assert_eq!(
+        decode_instruction(&data, &keys),
+        Err(DecodeError::InvalidInstructionDataLength {
+            command:  OracleCommand::SetMinPub,
+            expected: 12,
+            actual:   13,
+        })
+    );
+    assert_eq!(
+        decode_instruction(&data, &keys).unwrap_err().to_string(),
+        "13 bytes of instruction data for SetMinPub, expected 12"
+    );
+
+    // add_price and init_price ignore trailing bytes, like their processors
+    let cmd = AddPriceArgs {
+        header:     OracleCommand::InitPrice.into(),
+        exponent:   -8,
+        price_type: PC_PTYPE_PRICE,
+    };
+    let mut data = bytes_of(&cmd).to_vec();
+    data.push(0);
+    assert_eq!(
+        decode_instruction(&data, &keys),
+        Ok(OracleInstruction::InitPrice {
+            funding_account:     keys[0],
+            price_account:       keys[1],
+            permissions_account: keys[2],
+            exponent:            -8,
+            price_type:          PC_PTYPE_PRICE,
+        })
+    );
+    data.truncate(12);
+    assert_eq!(
+        decode_instruction(&data, &keys).unwrap_err().to_string(),
+        "12 bytes of instruction data for InitPrice, expected at least 16"
+    );
+}
@@ -14,6 +14,7 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             DelPublisherArgs,
             OracleCommand,
@@ -88,7 +89,7 @@ fn test_del_publisher() {
     .is_ok());
 
     {
-        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
         assert_eq!(price_data.num_, 0);
         assert_eq!(price_data.comp_[0].latest_.price_, 0);
         assert_eq!(price_data.comp_[0].latest_.conf_, 0);
@@ -97,7 +98,24 @@ fn test_del_publisher() {
         assert_eq!(price_data.comp_[0].latest_.corp_act_status_, 0);
         assert_eq!(price_data.header.size, PriceAccount::INITIAL_SIZE);
         assert!(price_data.comp_[0].pub_ == Pubkey::default());
+    }
 
+    // Can't delete a publisher that isn't in the list
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                permissions_account.clone(),
+            ],
+            &instruction_data
+        ),
+        Err(OracleError::PublisherNotFound.into())
+    );
+
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
         price_data.num_ = 2;
         price_data.comp_[0].latest_ = p1;
         price_data.comp_[1].latest_ = p2;
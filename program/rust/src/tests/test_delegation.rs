@@ -0,0 +1,384 @@
+use {
+    crate::{
+        accounts::{
+            DelegationAccount,
+            PriceAccount,
+            PythAccount,
+            MAX_DELEGATES,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        events::Event,
+        instruction::{
+            AddDelegateArgs,
+            DelDelegateArgs,
+            OracleCommand,
+            UpdPriceArgs,
+        },
+        processor::process_instruction,
+        tests::{
+            pyth_simulator::PythSimulator,
+            test_utils::{
+                take_logged_data,
+                update_clock_slot,
+                AccountSetup,
+            },
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    solana_sdk::signature::{
+        Keypair,
+        Signer,
+    },
+    std::mem::size_of,
+};
+
+fn add_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    delegate: Pubkey,
+    expiry_slot: u64,
+) -> ProgramResult {
+    let cmd = AddDelegateArgs {
+        header: OracleCommand::AddDelegate.into(),
+        delegate,
+        expiry_slot,
+    };
+    process_instruction(program_id, accounts, bytes_of(&cmd))
+}
+
+fn del_delegate(program_id: &Pubkey, accounts: &[AccountInfo], delegate: Pubkey) -> ProgramResult {
+    let cmd = DelDelegateArgs {
+        header: OracleCommand::DelDelegate.into(),
+        delegate,
+    };
+    process_instruction(program_id, accounts, bytes_of(&cmd))
+}
+
+#[test]
+fn test_add_del_delegate() {
+    let program_id = Pubkey::new_unique();
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+
+    let mut other_publisher_setup = AccountSetup::new_funding();
+    let other_publisher_account = other_publisher_setup.as_account_info();
+
+    let mut delegation_setup = AccountSetup::new_delegation(&program_id, publisher_account.key);
+    let mut delegation_account = delegation_setup.as_account_info();
+    delegation_account.is_signer = false;
+    // The account is created by the first `AddDelegate`, which tests can't invoke
+    DelegationAccount::initialize(&delegation_account, PC_VERSION).unwrap();
+
+    let system_program_key = system_program::id();
+    let system_program_owner = Pubkey::default();
+    let mut system_program_lamports = 0;
+    let mut system_program_data = [];
+    let system_program_account = AccountInfo::new(
+        &system_program_key,
+        false,
+        false,
+        &mut system_program_lamports,
+        &mut system_program_data,
+        &system_program_owner,
+        true,
+        0,
+    );
+
+    let delegates = || {
+        load_checked::<DelegationAccount>(&delegation_account, PC_VERSION)
+            .unwrap()
+            .delegates()
+            .iter()
+            .map(|delegate| (delegate.key, delegate.expiry_slot))
+            .collect::<Vec<_>>()
+    };
+
+    let delegate_1 = Pubkey::new_unique();
+    assert!(add_delegate(
+        &program_id,
+        &[
+            publisher_account.clone(),
+            delegation_account.clone(),
+            system_program_account.clone()
+        ],
+        delegate_1,
+        0
+    )
+    .is_ok());
+    assert_eq!(delegates(), vec![(delegate_1, 0)]);
+    assert_eq!(
+        load_checked::<DelegationAccount>(&delegation_account, PC_VERSION)
+            .unwrap()
+            .publisher,
+        *publisher_account.key
+    );
+    assert_eq!(
+        Event::from_bytes(&take_logged_data()[0][0]),
+        Ok(Event::DelegateAdded {
+            publisher:   *publisher_account.key,
+            delegate:    delegate_1,
+            expiry_slot: 0,
+        })
+    );
+
+    // Adding an existing delegate updates its expiry slot
+    assert!(add_delegate(
+        &program_id,
+        &[
+            publisher_account.clone(),
+            delegation_account.clone(),
+            system_program_account.clone()
+        ],
+        delegate_1,
+        50
+    )
+    .is_ok());
+    assert_eq!(delegates(), vec![(delegate_1, 50)]);
+
+    // Only the publisher's own delegation account can be used
+    assert_eq!(
+        add_delegate(
+            &program_id,
+            &[
+                other_publisher_account.clone(),
+                delegation_account.clone(),
+                system_program_account.clone()
+            ],
+            Pubkey::new_unique(),
+            0
+        ),
+        Err(OracleError::InvalidPda.into())
+    );
+    assert_eq!(
+        del_delegate(
+            &program_id,
+            &[other_publisher_account.clone(), delegation_account.clone()],
+            delegate_1
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+
+    let mut all_delegates = vec![(delegate_1, 50)];
+    for i in 1..MAX_DELEGATES {
+        let delegate = Pubkey::new_unique();
+        assert!(add_delegate(
+            &program_id,
+            &[
+                publisher_account.clone(),
+                delegation_account.clone(),
+                system_program_account.clone()
+            ],
+            delegate,
+            i as u64
+        )
+        .is_ok());
+        all_delegates.push((delegate, i as u64));
+    }
+    assert_eq!(delegates(), all_delegates);
+    assert_eq!(
+        add_delegate(
+            &program_id,
+            &[
+                publisher_account.clone(),
+                delegation_account.clone(),
+                system_program_account.clone()
+            ],
+            Pubkey::new_unique(),
+            0
+        ),
+        Err(OracleError::TooManyDelegates.into())
+    );
+
+    take_logged_data();
+    assert!(del_delegate(
+        &program_id,
+        &[publisher_account.clone(), delegation_account.clone()],
+        all_delegates[2].0
+    )
+    .is_ok());
+    assert_eq!(
+        Event::from_bytes(&take_logged_data()[0][0]),
+        Ok(Event::DelegateRemoved {
+            publisher: *publisher_account.key,
+            delegate:  all_delegates[2].0,
+        })
+    );
+    all_delegates.remove(2);
+    assert_eq!(delegates(), all_delegates);
+    assert_eq!(
+        del_delegate(
+            &program_id,
+            &[publisher_account.clone(), delegation_account.clone()],
+            Pubkey::new_unique()
+        ),
+        Err(OracleError::DelegateNotFound.into())
+    );
+}
+
+#[test]
+fn test_upd_price_as_delegate() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+
+    let mut delegate_setup = AccountSetup::new_funding();
+    let delegate_account = delegate_setup.as_account_info();
+
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = publisher;
+    }
+
+    let mut delegation_setup = AccountSetup::new_delegation(&program_id, &publisher);
+    let mut delegation_account = delegation_setup.as_account_info();
+    delegation_account.is_signer = false;
+    delegation_account.is_writable = false;
+    {
+        let mut delegation_data =
+            DelegationAccount::initialize(&delegation_account, PC_VERSION).unwrap();
+        delegation_data.publisher = publisher;
+        delegation_data.num_delegates = 1;
+        delegation_data.delegates[0].key = *delegate_account.key;
+        delegation_data.delegates[0].expiry_slot = 10;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let mut upd_price = UpdPriceArgs {
+        header:          OracleCommand::UpdPrice.into(),
+        status:          PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price:           42,
+        confidence:      1,
+        publishing_slot: 1,
+    };
+
+    update_clock_slot(&mut clock_account, 1);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            delegate_account.clone(),
+            price_account.clone(),
+            clock_account.clone(),
+            delegation_account.clone(),
+        ],
+        bytes_of(&upd_price)
+    )
+    .is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 42);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 1);
+    }
+
+    // Without the delegation account, the delegate isn't a publisher
+    upd_price.publishing_slot = 2;
+    update_clock_slot(&mut clock_account, 2);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                delegate_account.clone(),
+                price_account.clone(),
+                clock_account.clone(),
+            ],
+            bytes_of(&upd_price)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+
+    // Only listed delegates can sign
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                clock_account.clone(),
+                delegation_account.clone(),
+            ],
+            bytes_of(&upd_price)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+
+    // The delegation expires after its expiry slot
+    upd_price.publishing_slot = 11;
+    update_clock_slot(&mut clock_account, 11);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                delegate_account.clone(),
+                price_account.clone(),
+                clock_account.clone(),
+                delegation_account.clone(),
+            ],
+            bytes_of(&upd_price)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 1);
+}
+
+#[tokio::test]
+async fn test_add_delegate_funded_delegation_account() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Anyone can transfer lamports to the address of the delegation account before it exists
+    let delegation_pubkey = sim.get_delegation_pubkey(publisher.pubkey());
+    let prefunded_lamports = Rent::default().minimum_balance(0);
+    sim.airdrop(&delegation_pubkey, prefunded_lamports)
+        .await
+        .unwrap();
+
+    let delegate = Pubkey::new_unique();
+    sim.add_delegate(&publisher, delegate, 0).await.unwrap();
+
+    let delegation_account = sim.get_account(delegation_pubkey).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&delegation_account));
+    assert_eq!(
+        delegation_account.data.len(),
+        size_of::<DelegationAccount>()
+    );
+    assert_eq!(
+        delegation_account.lamports,
+        Rent::default().minimum_balance(size_of::<DelegationAccount>())
+    );
+    let delegation_data = sim
+        .get_account_data_as::<DelegationAccount>(delegation_pubkey)
+        .await
+        .unwrap();
+    assert_eq!(delegation_data.publisher, publisher.pubkey());
+    assert_eq!(delegation_data.num_delegates, 1);
+    assert_eq!(delegation_data.delegates[0].key, delegate);
+}
@@ -0,0 +1,58 @@
+use {
+    crate::error::OracleError,
+    solana_program::program_error::ProgramError,
+    std::collections::HashSet,
+};
+
+#[test]
+fn test_from_custom_code() {
+    for (i, error) in OracleError::ALL.iter().enumerate() {
+        let code = 600 + i as u32;
+        assert_eq!(*error as u32, code);
+        assert_eq!(ProgramError::from(*error), ProgramError::Custom(code));
+        assert_eq!(OracleError::from_custom_code(code), Some(*error));
+    }
+    assert_eq!(OracleError::from_custom_code(0), None);
+    assert_eq!(OracleError::from_custom_code(599), None);
+    assert_eq!(
+        OracleError::from_custom_code(600 + OracleError::ALL.len() as u32),
+        None
+    );
+}
+
+#[test]
+fn test_from_log_message() {
+    assert_eq!(
+        OracleError::from_log_message(
+            "Program FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH failed: custom program error: \
+             0x270"
+        ),
+        Some(OracleError::DuplicatePublisher)
+    );
+    assert_eq!(
+        OracleError::from_log_message("Program failed: custom program error: 0x25b trailing"),
+        Some(OracleError::UnrecognizedInstruction)
+    );
+    assert_eq!(
+        OracleError::from_log_message("Program failed: custom program error: 0x1"),
+        None
+    );
+    assert_eq!(
+        OracleError::from_log_message("Program failed: invalid program argument"),
+        None
+    );
+    assert_eq!(
+        OracleError::from_log_message("Program failed: custom program error: 0xzz"),
+        None
+    );
+}
+
+#[test]
+fn test_descriptions() {
+    let mut descriptions = HashSet::new();
+    for error in OracleError::ALL {
+        assert!(!error.description().is_empty());
+        assert!(!error.remediation().is_empty());
+        assert!(descriptions.insert(error.description()));
+    }
+}
@@ -0,0 +1,433 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        events::{
+            parse_events,
+            Event,
+            EVENT_VERSION,
+        },
+        instruction::{
+            builders,
+            AddPublisherArgs,
+            OracleCommand,
+            SetMinPubArgs,
+            UpdPriceArgs,
+        },
+        processor::process_instruction,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+            },
+            test_utils::{
+                take_logged_data,
+                update_clock_slot,
+                AccountSetup,
+            },
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::{
+        Keypair,
+        Signer,
+    },
+};
+
+fn events() -> Vec<Event> {
+    take_logged_data()
+        .into_iter()
+        .map(|fields| {
+            assert_eq!(fields.len(), 1);
+            Event::from_bytes(&fields[0]).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_emit_events() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+        permissions_account_data.data_curation_authority = *funding_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    let add_publisher = AddPublisherArgs {
+        header: OracleCommand::AddPublisher.into(),
+        publisher,
+    };
+    assert!(process_instruction(&program_id, &accounts, bytes_of(&add_publisher)).is_ok());
+    assert_eq!(
+        events(),
+        vec![Event::PublisherAdded {
+            price_account: *price_account.key,
+            publisher,
+        }]
+    );
+
+    // Failed instructions don't emit events
+    assert_eq!(
+        process_instruction(&program_id, &accounts, bytes_of(&add_publisher)),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(events(), vec![]);
+
+    let set_min_pub = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 3,
+        unused_:            [0; 3],
+    };
+    assert!(process_instruction(&program_id, &accounts, bytes_of(&set_min_pub)).is_ok());
+    assert_eq!(
+        events(),
+        vec![Event::MinPubSet {
+            price_account: *price_account.key,
+            min_pub:       3,
+        }]
+    );
+
+    let del_publisher = AddPublisherArgs {
+        header: OracleCommand::DelPublisher.into(),
+        publisher,
+    };
+    assert!(process_instruction(&program_id, &accounts, bytes_of(&del_publisher)).is_ok());
+    assert_eq!(
+        events(),
+        vec![Event::PublisherRemoved {
+            price_account: *price_account.key,
+            publisher,
+        }]
+    );
+}
+
+#[test]
+fn test_emit_aggregate_updated() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.min_pub_ = 1;
+        price_data.exponent = -2;
+        price_data.comp_[0].pub_ = *funding_account.key;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+
+    let mut upd_price = UpdPriceArgs {
+        header:          OracleCommand::UpdPrice.into(),
+        status:          PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price:           42,
+        confidence:      2,
+        publishing_slot: 1,
+    };
+    update_clock_slot(&mut clock_account, 1);
+    assert!(process_instruction(&program_id, &accounts, bytes_of(&upd_price)).is_ok());
+    assert_eq!(
+        events(),
+        vec![Event::AggregateUpdated {
+            price_account: *price_account.key,
+            price:         0,
+            conf:          0,
+            exponent:      -2,
+            status:        PC_STATUS_UNKNOWN,
+            num_qt:        0,
+            pub_slot:      1,
+            timestamp:     0,
+            ema_price:     0,
+            ema_conf:      0,
+        }]
+    );
+
+    upd_price.publishing_slot = 2;
+    update_clock_slot(&mut clock_account, 2);
+    assert!(process_instruction(&program_id, &accounts, bytes_of(&upd_price)).is_ok());
+    let price_data = *load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(
+        events(),
+        vec![Event::AggregateUpdated {
+            price_account: *price_account.key,
+            price:         42,
+            conf:          2,
+            exponent:      -2,
+            status:        PC_STATUS_TRADING,
+            num_qt:        1,
+            pub_slot:      2,
+            timestamp:     price_data.timestamp_,
+            ema_price:     price_data.twap_.val_,
+            ema_conf:      price_data.twac_.val_,
+        }]
+    );
+
+    // Failed updates don't aggregate
+    update_clock_slot(&mut clock_account, 3);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, bytes_of(&upd_price)),
+        Err(OracleError::StalePublishingSlot.into())
+    );
+    assert_eq!(events(), vec![]);
+}
+
+#[test]
+fn test_event_encoding() {
+    let [a, b, c] = [(); 3].map(|_| Pubkey::new_unique());
+    let all_events = [
+        Event::AggregateUpdated {
+            price_account: a,
+            price:         -1,
+            conf:          2,
+            exponent:      -3,
+            status:        4,
+            num_qt:        5,
+            pub_slot:      6,
+            timestamp:     7,
+            ema_price:     8,
+            ema_conf:      9,
+        },
+        Event::PublisherAdded {
+            price_account: a,
+            publisher:     b,
+        },
+        Event::PublisherRemoved {
+            price_account: b,
+            publisher:     a,
+        },
+        Event::PermissionsUpdated {
+            master_authority:        a,
+            data_curation_authority: b,
+            security_authority:      c,
+        },
+        Event::MinPubSet {
+            price_account: a,
+            min_pub:       1,
+        },
+        Event::MaxLatencySet {
+            price_account: a,
+            max_latency:   2,
+        },
+        Event::ProductDeleted {
+            mapping_account: a,
+            product_account: b,
+        },
+        Event::PriceDeleted {
+            product_account: b,
+            price_account:   c,
+        },
+        Event::PriceFlagsSet {
+            price_account: c,
+            flags:         1,
+        },
+        Event::PublisherRotated {
+            price_account: a,
+            old_publisher: b,
+            new_publisher: c,
+        },
+        Event::DelegateAdded {
+            publisher:   a,
+            delegate:    b,
+            expiry_slot: 10,
+        },
+        Event::DelegateRemoved {
+            publisher: a,
+            delegate:  c,
+        },
+        Event::FeedPaused { price_account: a },
+        Event::FeedResumed { price_account: a },
+        Event::ScheduleSet {
+            price_account: a,
+            num_sessions:  5,
+            num_holidays:  2,
+        },
+        Event::AggregationConfigSet {
+            price_account:    a,
+            mode:             2,
+            outlier_mads:     3,
+            widen_stale_conf: 1,
+        },
+    ];
+
+    for (discriminator, event) in all_events.iter().enumerate() {
+        let bytes = event.to_bytes();
+        assert_eq!(bytes[..2], [EVENT_VERSION, discriminator as u8]);
+        assert_eq!(Event::from_bytes(&bytes), Ok(*event));
+
+        assert_eq!(
+            Event::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(OracleError::DeserializationError)
+        );
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(
+            Event::from_bytes(&longer),
+            Err(OracleError::DeserializationError)
+        );
+        let mut other_version = bytes.clone();
+        other_version[0] = EVENT_VERSION + 1;
+        assert_eq!(
+            Event::from_bytes(&other_version),
+            Err(OracleError::DeserializationError)
+        );
+    }
+    assert_eq!(
+        Event::from_bytes(&[EVENT_VERSION, all_events.len() as u8]),
+        Err(OracleError::DeserializationError)
+    );
+}
+
+#[test]
+fn test_parse_events() {
+    let program_id = Pubkey::new_unique();
+    let other_program_id = Pubkey::new_unique();
+    let event_1 = Event::MinPubSet {
+        price_account: Pubkey::new_unique(),
+        min_pub:       1,
+    };
+    let event_2 = Event::MaxLatencySet {
+        price_account: Pubkey::new_unique(),
+        max_latency:   2,
+    };
+    let data = |event: &Event| format!("Program data: {}", base64::encode(event.to_bytes()));
+
+    let logs = vec![
+        format!("Program {program_id} invoke [1]"),
+        "Program log: Instruction: SetMinPub".to_string(),
+        data(&event_1),
+        format!("Program {other_program_id} invoke [2]"),
+        data(&event_2),
+        format!("Program {other_program_id} consumed 100 of 200000 compute units"),
+        format!("Program {other_program_id} success"),
+        "Program data: bm90IGFuIGV2ZW50 !!!".to_string(),
+        format!("Program {program_id} consumed 1000 of 200000 compute units"),
+        format!("Program {program_id} success"),
+        format!("Program {other_program_id} invoke [1]"),
+        data(&event_1),
+        format!("Program {other_program_id} failed: custom program error: 0x0"),
+        format!("Program {program_id} invoke [1]"),
+        data(&event_2),
+        format!("Program {program_id} success"),
+    ];
+
+    assert_eq!(parse_events(&program_id, &logs), vec![event_1, event_2]);
+    assert_eq!(
+        parse_events(&other_program_id, &logs),
+        vec![event_2, event_1]
+    );
+}
+
+#[tokio::test]
+async fn test_events_in_transaction_logs() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price_account = *price_accounts.values().next().unwrap();
+    let program_id = sim.program_id();
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+
+    let new_publisher = Pubkey::new_unique();
+    let transaction = sim
+        .process_ixs_with_metadata(
+            &[builders::add_publisher(
+                &program_id,
+                &genesis_keypair.pubkey(),
+                &price_account,
+                &new_publisher,
+            )],
+            &vec![],
+            &genesis_keypair,
+        )
+        .await
+        .unwrap();
+    assert_eq!(transaction.result, Ok(()));
+    assert_eq!(
+        parse_events(&program_id, &transaction.log_messages),
+        vec![Event::PublisherAdded {
+            price_account,
+            publisher: new_publisher,
+        }]
+    );
+
+    sim.warp_to_slot(10).await.unwrap();
+    let upd_price = builders::upd_price(
+        &program_id,
+        &publisher.pubkey(),
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        10,
+        None,
+    );
+    let transaction = sim
+        .process_ixs_with_metadata(&[upd_price.clone()], &vec![&publisher], &publisher)
+        .await
+        .unwrap();
+    assert_eq!(transaction.result, Ok(()));
+    let events = parse_events(&program_id, &transaction.log_messages);
+    assert!(matches!(
+        events[..],
+        [Event::AggregateUpdated {
+            price_account: event_price_account,
+            pub_slot: 10,
+            ..
+        }] if event_price_account == price_account
+    ));
+
+    // Failed transactions don't emit events
+    let transaction = sim
+        .process_ixs_with_metadata(&[upd_price], &vec![&publisher], &publisher)
+        .await
+        .unwrap();
+    assert!(transaction.result.is_err());
+    assert_eq!(parse_events(&program_id, &transaction.log_messages), vec![]);
+}
@@ -19,10 +19,7 @@ use {
         OracleError,
     },
     bytemuck::bytes_of,
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
 };
 
 #[test]
@@ -171,6 +168,25 @@ fn test_init_price() {
             ],
             instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidExponent.into())
+    );
+
+    let cmd: InitPriceArgs = InitPriceArgs {
+        header:     OracleCommand::InitPrice.into(),
+        exponent:   -2,
+        price_type: ptype + 1,
+    };
+    let instruction_data = bytes_of::<InitPriceArgs>(&cmd);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            instruction_data
+        ),
+        Err(OracleError::PriceTypeMismatch.into())
     );
 }
@@ -0,0 +1,320 @@
+use {
+    crate::{
+        accounts::{
+            AggregationConfig,
+            AggregationMode,
+            PermissionAccount,
+            PriceAccount,
+            PriceInfo,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_CORP_ACT_NONE,
+            PC_CORP_ACT_SPLIT,
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        events::Event,
+        instruction::builders,
+        processor::{
+            process_instruction,
+            upd_aggregate,
+        },
+        tests::test_utils::{
+            take_events,
+            update_clock_slot,
+            AccountSetup,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+};
+
+fn quote(price: i64, conf: u64) -> PriceInfo {
+    PriceInfo {
+        price_:           price,
+        conf_:            conf,
+        status_:          PC_STATUS_TRADING,
+        corp_act_status_: PC_CORP_ACT_NONE,
+        pub_slot_:        1,
+    }
+}
+
+/// Aggregate `quotes` at slot 2 with `mode` and the outlier filter set to `outlier_mads`.
+/// Returns whether the aggregation succeeded and the price account.
+fn aggregate(
+    quotes: &[PriceInfo],
+    outlier_mads: u8,
+    mode: AggregationMode,
+) -> (bool, Box<PriceAccount>) {
+    let mut price_account = Box::new(PriceAccount::zeroed());
+    price_account.num_ = quotes.len() as u32;
+    price_account.price_cumulative.aggregation.mode = mode as u8;
+    price_account.price_cumulative.aggregation.outlier_mads = outlier_mads;
+    for (component, quote) in price_account.comp_.iter_mut().zip(quotes) {
+        component.latest_ = *quote;
+    }
+    let aggregated =
+        unsafe { upd_aggregate(&mut *price_account as *mut PriceAccount as *mut u8, 2, 0) };
+    (aggregated, price_account)
+}
+
+/// The indices of the components excluded by the outlier filter
+fn excluded(price_account: &PriceAccount) -> Vec<usize> {
+    (0..PC_NUM_COMP as usize)
+        .filter(|&i| price_account.excluded_components & (1 << i) != 0)
+        .collect()
+}
+
+#[test]
+fn test_outlier_filter() {
+    // A tight quote far from the others
+    let quotes = [quote(100, 1), quote(101, 1), quote(1000, 1)];
+    let (aggregated, price_account) = aggregate(&quotes, 0, AggregationMode::Model);
+    assert!(aggregated);
+    assert_eq!(price_account.num_qt_, 3);
+    assert_eq!(excluded(&price_account), Vec::<usize>::new());
+    assert_eq!(price_account.agg_.price_, 101);
+    assert_eq!(price_account.agg_.conf_, 898);
+
+    // The median is 101 and the median absolute deviation 1
+    let (aggregated, price_account) = aggregate(&quotes, 3, AggregationMode::Model);
+    assert!(aggregated);
+    assert_eq!(price_account.num_qt_, 2);
+    assert_eq!(excluded(&price_account), vec![2]);
+    assert_eq!(price_account.comp_[2].agg_, quotes[2]);
+    assert_eq!(price_account.comp_[2].latest_, quotes[2]);
+    assert_eq!(price_account.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_account.agg_.price_, 100);
+    assert_eq!(price_account.agg_.conf_, 1);
+
+    // The filter applies to every mode
+    for mode in [
+        AggregationMode::Median,
+        AggregationMode::WeightedMedian,
+        AggregationMode::TrimmedMean,
+    ] {
+        let (aggregated, price_account) = aggregate(&quotes, 3, mode);
+        assert!(aggregated);
+        assert_eq!(price_account.num_qt_, 2);
+        assert_eq!(excluded(&price_account), vec![2]);
+    }
+
+    // Disabling the filter clears the excluded components at the next aggregation
+    let (_, mut price_account) = aggregate(&quotes, 3, AggregationMode::Model);
+    price_account.price_cumulative.aggregation.outlier_mads = 0;
+    assert!(unsafe { upd_aggregate(&mut *price_account as *mut PriceAccount as *mut u8, 3, 0) });
+    assert_eq!(excluded(&price_account), Vec::<usize>::new());
+}
+
+#[test]
+fn test_outlier_filter_threshold() {
+    // The median is 101, the deviations 11, 1, 9, 29 and 0, and their median 9
+    let quotes = [
+        quote(90, 1),
+        quote(100, 1),
+        quote(110, 1),
+        quote(130, 1),
+        quote(101, 1),
+    ];
+    let (_, price_account) = aggregate(&quotes, 1, AggregationMode::Model);
+    assert_eq!(excluded(&price_account), vec![0, 3]);
+    assert_eq!(price_account.num_qt_, 3);
+    let (_, price_account) = aggregate(&quotes, 3, AggregationMode::Model);
+    assert_eq!(excluded(&price_account), vec![3]);
+    assert_eq!(price_account.num_qt_, 4);
+    let (_, price_account) = aggregate(&quotes, 4, AggregationMode::Model);
+    assert_eq!(excluded(&price_account), Vec::<usize>::new());
+
+    // The median of an even number of prices isn't rounded: it is 100.5, and every deviation 0.5
+    let quotes = [quote(100, 1), quote(100, 1), quote(101, 1), quote(101, 1)];
+    let (_, price_account) = aggregate(&quotes, 1, AggregationMode::Model);
+    assert_eq!(excluded(&price_account), Vec::<usize>::new());
+
+    // When most prices are the same, the median absolute deviation is 0 and the filter keeps
+    // every quote
+    let quotes = [quote(100, 1), quote(100, 2), quote(100, 1), quote(103, 1)];
+    for outlier_mads in [1, u8::MAX] {
+        let (_, price_account) = aggregate(&quotes, outlier_mads, AggregationMode::Model);
+        assert_eq!(excluded(&price_account), Vec::<usize>::new());
+        assert_eq!(price_account.num_qt_, 4);
+    }
+
+    // Extreme prices don't overflow
+    let quotes = [
+        quote(i64::MIN + 1, 1),
+        quote(i64::MAX - 2, 1),
+        quote(i64::MAX - 1, 1),
+    ];
+    let (_, price_account) = aggregate(&quotes, 1, AggregationMode::Model);
+    assert_eq!(excluded(&price_account), vec![0]);
+}
+
+#[test]
+fn test_outlier_filter_valid_quotes() {
+    // Invalid quotes are neither part of the median nor excluded
+    let mut invalid = quote(5000, 1);
+    invalid.status_ = PC_STATUS_UNKNOWN;
+    let quotes = [invalid, quote(100, 1), quote(101, 1), quote(1000, 1)];
+    let (aggregated, price_account) = aggregate(&quotes, 3, AggregationMode::Model);
+    assert!(aggregated);
+    assert_eq!(excluded(&price_account), vec![3]);
+    assert_eq!(price_account.comp_[0].agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_account.num_qt_, 2);
+
+    // The corporate actions of excluded quotes don't count
+    let mut quotes = [quote(100, 1), quote(101, 1), quote(1000, 1)];
+    quotes[0].corp_act_status_ = PC_CORP_ACT_SPLIT;
+    quotes[2].corp_act_status_ = PC_CORP_ACT_SPLIT;
+    let (_, price_account) = aggregate(&quotes, 0, AggregationMode::Model);
+    assert_eq!(price_account.agg_.corp_act_status_, PC_CORP_ACT_SPLIT);
+    let (_, price_account) = aggregate(&quotes, 3, AggregationMode::Model);
+    assert_eq!(price_account.agg_.corp_act_status_, PC_CORP_ACT_NONE);
+
+    // Neither do excluded quotes for the minimum number of publishers
+    let mut price_account = Box::new(PriceAccount::zeroed());
+    price_account.num_ = 3;
+    price_account.min_pub_ = 3;
+    price_account.price_cumulative.aggregation.outlier_mads = 3;
+    for (component, quote) in price_account.comp_.iter_mut().zip(&quotes) {
+        component.latest_ = *quote;
+    }
+    assert!(!unsafe { upd_aggregate(&mut *price_account as *mut PriceAccount as *mut u8, 2, 0) });
+    assert_eq!(price_account.num_qt_, 2);
+    assert_eq!(price_account.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(excluded(&price_account), vec![2]);
+}
+
+#[test]
+fn test_set_outlier_filter() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 3;
+        price_data.comp_[0].pub_ = *funding_account.key;
+        for (component, price) in price_data.comp_[1..3].iter_mut().zip([100, 101]) {
+            component.pub_ = Pubkey::new_unique();
+            component.latest_ = quote(price, 1);
+        }
+    }
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .security_authority = *funding_account.key;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let admin_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let instruction = builders::set_aggregation_config(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        AggregationConfig::new(AggregationMode::Model, 3, false),
+    );
+    assert!(process_instruction(&program_id, &admin_accounts, &instruction.data).is_ok());
+    assert_eq!(
+        take_events(),
+        vec![Event::AggregationConfigSet {
+            price_account:    *price_account.key,
+            mode:             AggregationMode::Model as u8,
+            outlier_mads:     3,
+            widen_stale_conf: 0,
+        }]
+    );
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .price_cumulative
+            .aggregation
+            .outlier_mads,
+        3
+    );
+
+    // The next aggregation of UpdPrice excludes the quote of the first publisher
+    let publish_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    for slot in [1, 2] {
+        update_clock_slot(&mut clock_account, slot);
+        let instruction = builders::upd_price(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            PC_STATUS_TRADING,
+            1000,
+            1,
+            slot,
+            None,
+        );
+        process_instruction(&program_id, &publish_accounts, &instruction.data).unwrap();
+    }
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.num_qt_, 2);
+        assert_eq!(excluded(&price_data), vec![0]);
+        assert_eq!(price_data.agg_.price_, 100);
+        assert_eq!(price_data.agg_.conf_, 1);
+    }
+    take_events();
+
+    // Reordering the components clears the bitmap, it would point at the wrong publishers
+    let instruction = builders::add_publisher(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        &Pubkey::new_unique(),
+    );
+    process_instruction(&program_id, &admin_accounts, &instruction.data).unwrap();
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.num_, 4);
+        assert!(excluded(&price_data).is_empty());
+    }
+    take_events();
+
+    // Only the security authority can set the filter
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    let instruction = builders::set_aggregation_config(
+        &program_id,
+        attacker_account.key,
+        price_account.key,
+        AggregationConfig::zeroed(),
+    );
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction.data
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    assert!(take_events().is_empty());
+}
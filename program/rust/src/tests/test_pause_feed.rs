@@ -0,0 +1,316 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+            PythOracleSerialize,
+        },
+        c_oracle_header::{
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        events::Event,
+        instruction::{
+            builders,
+            UpdPriceReturnData,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            take_events,
+            take_return_data,
+            update_clock_slot,
+            AccountSetup,
+        },
+        validator::{
+            self,
+            checked_load_price_account_mut,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+fn publish(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: i64,
+    slot: u64,
+) -> Result<UpdPriceReturnData, ProgramError> {
+    let instruction = builders::upd_price(
+        program_id,
+        accounts[0].key,
+        accounts[1].key,
+        PC_STATUS_TRADING,
+        price,
+        1,
+        slot,
+        None,
+    );
+    process_instruction(program_id, accounts, &instruction.data)?;
+    Ok(UpdPriceReturnData::from_bytes(&take_return_data()).unwrap())
+}
+
+#[test]
+fn test_pause_feed() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+    }
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .security_authority = *funding_account.key;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let admin_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let pause = || {
+        let instruction = builders::pause_feed(&program_id, funding_account.key, price_account.key);
+        process_instruction(&program_id, &admin_accounts, &instruction.data)
+    };
+    let resume = || {
+        let instruction =
+            builders::resume_feed(&program_id, funding_account.key, price_account.key);
+        process_instruction(&program_id, &admin_accounts, &instruction.data)
+    };
+
+    update_clock_slot(&mut clock_account, 1);
+    let publish_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    publish(&program_id, &publish_accounts, 42, 1).unwrap();
+    update_clock_slot(&mut clock_account, 2);
+    publish(&program_id, &publish_accounts, 43, 2).unwrap();
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.price_, 42);
+    }
+    take_events();
+
+    // Pausing halts the aggregate right away, keeping the last trading price as the previous price
+    assert!(pause().is_ok());
+    assert_eq!(
+        take_events(),
+        vec![Event::FeedPaused {
+            price_account: *price_account.key,
+        }]
+    );
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert!(price_data.flags.contains(PriceAccountFlags::PAUSED));
+        assert_eq!(price_data.agg_.status_, PC_STATUS_HALTED);
+        assert_eq!(price_data.prev_price_, 42);
+        assert_eq!(price_data.prev_slot_, 2);
+        assert_eq!(price_data.message_sent_, 0);
+        let message = price_data.as_price_feed_message(price_account.key);
+        assert_eq!(message.price, 42);
+        assert_eq!(message.publish_time, price_data.prev_timestamp_);
+    }
+
+    // Pausing again does nothing
+    assert!(pause().is_ok());
+    assert!(take_events().is_empty());
+
+    // Components are still updated, but the aggregate is held
+    update_clock_slot(&mut clock_account, 3);
+    let return_data = publish(&program_id, &publish_accounts, 50, 3).unwrap();
+    assert!(!return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_HALTED);
+    assert_eq!(return_data.agg_price, 42);
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 50);
+        assert_eq!(price_data.agg_.pub_slot_, 3);
+        assert_eq!(price_data.valid_slot_, 2);
+        assert_eq!(price_data.last_slot_, 2);
+        assert_eq!(price_data.prev_price_, 42);
+    }
+    match take_events()[..] {
+        [Event::AggregateUpdated { status, price, .. }] => {
+            assert_eq!(status, PC_STATUS_HALTED);
+            assert_eq!(price, 42);
+        }
+        ref events => panic!("unexpected events {events:?}"),
+    }
+
+    // Once resumed, the next aggregation uses the components published during the pause
+    assert!(resume().is_ok());
+    assert_eq!(
+        take_events(),
+        vec![Event::FeedResumed {
+            price_account: *price_account.key,
+        }]
+    );
+    assert!(resume().is_ok());
+    assert!(take_events().is_empty());
+
+    update_clock_slot(&mut clock_account, 4);
+    let return_data = publish(&program_id, &publish_accounts, 51, 4).unwrap();
+    assert!(return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_TRADING);
+    assert_eq!(return_data.agg_price, 50);
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert!(!price_data.flags.contains(PriceAccountFlags::PAUSED));
+        assert_eq!(price_data.last_slot_, 4);
+        // The halted aggregate didn't replace the previous price
+        assert_eq!(price_data.prev_price_, 42);
+    }
+    take_events();
+
+    // The pause flag can't be cleared with SetPriceFlags
+    assert!(pause().is_ok());
+    let instruction = builders::set_price_flags(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        0,
+        PriceAccountFlags::PAUSED.bits(),
+    );
+    assert_eq!(
+        process_instruction(&program_id, &admin_accounts, &instruction.data),
+        Err(OracleError::InvalidPriceFlags.into())
+    );
+    assert!(resume().is_ok());
+    take_events();
+
+    // Only the security authority can pause and resume feeds
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    for instruction in [
+        builders::pause_feed(&program_id, attacker_account.key, price_account.key),
+        builders::resume_feed(&program_id, attacker_account.key, price_account.key),
+    ] {
+        assert_eq!(
+            process_instruction(
+                &program_id,
+                &[
+                    attacker_account.clone(),
+                    price_account.clone(),
+                    permissions_account.clone()
+                ],
+                &instruction.data
+            ),
+            Err(OracleError::PermissionViolation.into())
+        );
+    }
+    assert!(take_events().is_empty());
+}
+
+#[test]
+fn test_pause_feed_with_validator() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+        price_data.flags =
+            PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+    }
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .security_authority = *funding_account.key;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    let publish_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    publish(&program_id, &publish_accounts, 42, 1).unwrap();
+    validator::aggregate_price(
+        1,
+        101,
+        price_account.key,
+        checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
+    )
+    .unwrap();
+
+    let instruction = builders::pause_feed(&program_id, funding_account.key, price_account.key);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            price_account.clone(),
+            permissions_account.clone()
+        ],
+        &instruction.data
+    )
+    .is_ok());
+    take_events();
+
+    // Publishers keep publishing and messages keep being produced, showing the last trading price
+    update_clock_slot(&mut clock_account, 2);
+    publish(&program_id, &publish_accounts, 50, 2).unwrap();
+    let messages = validator::aggregate_price(
+        2,
+        102,
+        price_account.key,
+        checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
+    )
+    .unwrap();
+
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.comp_[0].latest_.price_, 50);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_HALTED);
+    assert_eq!(price_data.agg_.pub_slot_, 2);
+    assert_eq!(price_data.agg_.price_, 42);
+    assert_eq!(price_data.timestamp_, 102);
+    assert_eq!(price_data.prev_timestamp_, 101);
+    assert_eq!(
+        messages,
+        [
+            price_data
+                .as_price_feed_message(price_account.key)
+                .to_bytes(),
+            price_data.as_twap_message(price_account.key).to_bytes(),
+        ]
+    );
+    let message = price_data.as_price_feed_message(price_account.key);
+    assert_eq!(message.price, 42);
+    assert_eq!(message.publish_time, 101);
+}
@@ -0,0 +1,229 @@
+use {
+    crate::{
+        accounts::{
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        error::OracleError,
+        instruction::{
+            AddPublisherArgs,
+            CommandHeader,
+            OracleCommand,
+            SetMaxLatencyArgs,
+            SetMinPubArgs,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    num_traits::FromPrimitive,
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_is_authorized() {
+    use OracleCommand::*;
+
+    let permissions = PermissionAccount {
+        master_authority: Pubkey::new_unique(),
+        data_curation_authority: Pubkey::new_unique(),
+        security_authority: Pubkey::new_unique(),
+        ..PermissionAccount::zeroed()
+    };
+    let attacker = Pubkey::new_unique();
+
+    let data_curation_commands = [
+        InitMapping,
+        AddMapping,
+        AddProduct,
+        UpdProduct,
+        AddPrice,
+        InitPrice,
+        DelPrice,
+        DelProduct,
+        SetSchedule,
+    ];
+    let security_commands = [
+        AddPublisher,
+        DelPublisher,
+        SetMinPub,
+        SetPriceFlags,
+        RotatePublisher,
+        SetPublishers,
+        PauseFeed,
+        ResumeFeed,
+        SetAggregationConfig,
+    ];
+
+    let all_commands: Vec<OracleCommand> = (0..).map_while(OracleCommand::from_i32).collect();
+    assert_eq!(all_commands.last(), Some(&SetAggregationConfig));
+
+    for command in all_commands {
+        assert!(permissions.is_authorized(&permissions.master_authority, command));
+        assert_eq!(
+            permissions.is_authorized(&permissions.data_curation_authority, command),
+            data_curation_commands.contains(&command),
+        );
+        assert_eq!(
+            permissions.is_authorized(&permissions.security_authority, command),
+            security_commands.contains(&command),
+        );
+        assert!(!permissions.is_authorized(&attacker, command));
+    }
+
+    // A key holding several roles gets the permissions of all of them
+    let permissions = PermissionAccount {
+        data_curation_authority: permissions.security_authority,
+        ..permissions
+    };
+    for command in data_curation_commands
+        .iter()
+        .chain(security_commands.iter())
+    {
+        assert!(permissions.is_authorized(&permissions.security_authority, *command));
+    }
+    assert!(!permissions.is_authorized(&permissions.security_authority, SetMaxLatency));
+}
+
+#[test]
+fn test_authority_roles() {
+    let program_id = Pubkey::new_unique();
+
+    let mut master_setup = AccountSetup::new_funding();
+    let master_account = master_setup.as_account_info();
+
+    let mut data_curation_setup = AccountSetup::new_funding();
+    let data_curation_account = data_curation_setup.as_account_info();
+
+    let mut security_setup = AccountSetup::new_funding();
+    let security_account = security_setup.as_account_info();
+
+    let mut mapping_setup = AccountSetup::new::<MappingAccount>(&program_id);
+    let mut mapping_account = mapping_setup.as_account_info();
+    mapping_account.is_signer = false;
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *master_account.key;
+        permissions_account_data.data_curation_authority = *data_curation_account.key;
+        permissions_account_data.security_authority = *security_account.key;
+    }
+
+    let add_publisher = AddPublisherArgs {
+        header:    OracleCommand::AddPublisher.into(),
+        publisher: Pubkey::new_unique(),
+    };
+    let del_publisher = AddPublisherArgs {
+        header:    OracleCommand::DelPublisher.into(),
+        publisher: add_publisher.publisher,
+    };
+    let set_min_pub = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 2,
+        unused_:            [0; 3],
+    };
+    let set_max_latency = SetMaxLatencyArgs {
+        header:      OracleCommand::SetMaxLatency.into(),
+        max_latency: 5,
+        unused_:     [0; 3],
+    };
+    let init_mapping: CommandHeader = OracleCommand::InitMapping.into();
+
+    let price_instructions: [&[u8]; 4] = [
+        bytes_of(&add_publisher),
+        bytes_of(&set_min_pub),
+        bytes_of(&del_publisher),
+        bytes_of(&set_max_latency),
+    ];
+
+    // The data curation authority doesn't manage publishers
+    for instruction_data in price_instructions {
+        assert_eq!(
+            process_instruction(
+                &program_id,
+                &[
+                    data_curation_account.clone(),
+                    price_account.clone(),
+                    permissions_account.clone()
+                ],
+                instruction_data
+            ),
+            Err(OracleError::PermissionViolation.into())
+        );
+    }
+
+    // The security authority manages the publishers of price accounts
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                security_account.clone(),
+                mapping_account.clone(),
+                permissions_account.clone()
+            ],
+            bytes_of(&init_mapping)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    for instruction_data in &price_instructions[..3] {
+        assert!(process_instruction(
+            &program_id,
+            &[
+                security_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            instruction_data
+        )
+        .is_ok());
+    }
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                security_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            bytes_of(&set_max_latency)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+
+    assert!(process_instruction(
+        &program_id,
+        &[
+            data_curation_account.clone(),
+            mapping_account.clone(),
+            permissions_account.clone()
+        ],
+        bytes_of(&init_mapping)
+    )
+    .is_ok());
+
+    // The master authority can do everything
+    assert!(process_instruction(
+        &program_id,
+        &[
+            master_account.clone(),
+            price_account.clone(),
+            permissions_account.clone()
+        ],
+        bytes_of(&set_max_latency)
+    )
+    .is_ok());
+}
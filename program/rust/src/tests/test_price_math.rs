@@ -0,0 +1,231 @@
+use {
+    crate::{
+        accounts::{
+            Price,
+            PriceInfo,
+        },
+        error::OracleError,
+    },
+    quickcheck::Arbitrary,
+    quickcheck_macros::quickcheck,
+    std::cmp::Ordering,
+};
+
+// Decimal arithmetic from pd.h, see program/c/src/oracle/util/test_pd.c
+mod c {
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct PdT {
+        pub e_: i32,
+        pub v_: i64,
+    }
+
+    #[link(name = "cpyth-test")]
+    extern "C" {
+        pub fn test_pd_mul(r: *mut PdT, n1: *const PdT, n2: *const PdT);
+        pub fn test_pd_div(r: *mut PdT, n1: *mut PdT, n2: *mut PdT);
+        pub fn test_pd_add(r: *mut PdT, n1: *const PdT, n2: *const PdT);
+        pub fn test_pd_lt(n1: *const PdT, n2: *const PdT) -> i32;
+        pub fn test_pd_gt(n1: *const PdT, n2: *const PdT) -> i32;
+    }
+}
+
+/// A non-zero value in the range used by `pd_t` after `pd_scale`.
+#[derive(Clone, Copy, Debug)]
+struct PdValue {
+    price:    i64,
+    exponent: i32,
+}
+
+impl Arbitrary for PdValue {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let price = i64::arbitrary(g) % (1 << 28);
+        PdValue {
+            price:    if price == 0 { 1 } else { price },
+            exponent: i32::from(i8::arbitrary(g)) % 13,
+        }
+    }
+}
+
+impl PdValue {
+    fn to_pd(self) -> c::PdT {
+        c::PdT {
+            e_: self.exponent,
+            v_: self.price,
+        }
+    }
+
+    fn to_price(self) -> Price {
+        Price::new(self.price, 0, self.exponent)
+    }
+}
+
+fn value(price: i64, exponent: i32) -> f64 {
+    price as f64 * 10f64.powi(exponent)
+}
+
+/// pd_t keeps about 8 significant digits, check that the results agree up to that precision.
+fn assert_close(price: Price, pd: c::PdT) {
+    let expected = value(pd.v_, pd.e_);
+    let actual = value(price.price, price.exponent);
+    assert!(
+        (expected - actual).abs() <= 1e-7 * expected.abs().max(actual.abs()),
+        "{price:?} != {pd:?}"
+    );
+}
+
+#[quickcheck]
+fn test_mul_matches_pd(a: PdValue, b: PdValue) -> bool {
+    let mut r = c::PdT::default();
+    unsafe { c::test_pd_mul(&mut r, &a.to_pd(), &b.to_pd()) };
+    assert_close(a.to_price().mul(&b.to_price()).unwrap(), r);
+    true
+}
+
+#[quickcheck]
+fn test_div_matches_pd(a: PdValue, b: PdValue) -> bool {
+    let mut r = c::PdT::default();
+    unsafe { c::test_pd_div(&mut r, &mut a.to_pd(), &mut b.to_pd()) };
+    assert_close(a.to_price().div(&b.to_price()).unwrap(), r);
+    true
+}
+
+#[quickcheck]
+fn test_add_matches_pd(a: PdValue, b: PdValue) -> bool {
+    let mut r = c::PdT::default();
+    unsafe { c::test_pd_add(&mut r, &a.to_pd(), &b.to_pd()) };
+    assert_close(a.to_price().add(&b.to_price()).unwrap(), r);
+    true
+}
+
+#[quickcheck]
+fn test_cmp_matches_pd(a: PdValue, b: PdValue) -> bool {
+    let (lt, gt) = unsafe {
+        (
+            c::test_pd_lt(&a.to_pd(), &b.to_pd()) != 0,
+            c::test_pd_gt(&a.to_pd(), &b.to_pd()) != 0,
+        )
+    };
+    let expected = match (lt, gt) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => Ordering::Equal,
+    };
+    a.to_price().cmp(&b.to_price()) == expected
+}
+
+#[test]
+fn test_scale_to_exponent() {
+    let price = Price::new(-123_456, 1_001, -3);
+    assert_eq!(
+        price.scale_to_exponent(-5),
+        Ok(Price::new(-12_345_600, 100_100, -5))
+    );
+    // The price is truncated while the confidence is rounded up
+    assert_eq!(price.scale_to_exponent(-1), Ok(Price::new(-1_234, 11, -1)));
+    assert_eq!(price.scale_to_exponent(100), Ok(Price::new(0, 1, 100)));
+    assert_eq!(
+        Price::new(0, 0, 0).scale_to_exponent(100),
+        Ok(Price::new(0, 0, 100))
+    );
+    assert_eq!(
+        price.scale_to_exponent(-20),
+        Err(OracleError::IntegerCastingError)
+    );
+    assert_eq!(
+        Price::new(1, 0, 0).scale_to_exponent(i32::MIN),
+        Err(OracleError::IntegerCastingError)
+    );
+
+    let price_info = PriceInfo {
+        price_:           100,
+        conf_:            2,
+        status_:          0,
+        corp_act_status_: 0,
+        pub_slot_:        0,
+    };
+    assert_eq!(
+        Price::from_price_info(&price_info, -8),
+        Price::new(100, 2, -8)
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    // 1.5 +- 0.1 and 2 +- 0.05
+    let a = Price::new(150, 10, -2);
+    let b = Price::new(2_000, 50, -3);
+
+    assert_eq!(a.add(&b), Ok(Price::new(3_500, 150, -3)));
+    // 3 +- (1.5 * 0.05 + 2 * 0.1)
+    assert_eq!(a.mul(&b), Ok(Price::new(300_000, 27_500, -5)));
+    // 0.75 +- (0.1 + 0.75 * 0.05) / 2
+    let quotient = a.div(&b).unwrap();
+    assert_eq!(
+        quotient.scale_to_exponent(-6),
+        Ok(Price::new(750_000, 68_750, -6))
+    );
+    assert_eq!(
+        Price::new(0, 10, 0).div(&b),
+        Ok(Price::new(0, 5_000_000_000_000_000, -15))
+    );
+    assert_eq!(
+        a.div(&Price::new(0, 1, 0)),
+        Err(OracleError::IntegerCastingError)
+    );
+
+    // Results that don't fit in 64 bits lose digits instead of overflowing
+    let big = Price::new(i64::MAX, u64::MAX, 0);
+    assert_eq!(
+        big.mul(&big),
+        Ok(Price::new(
+            850_705_917_302_346_158,
+            3_402_823_669_209_384_635,
+            20
+        ))
+    );
+    assert_eq!(
+        big.add(&big),
+        Ok(Price::new(
+            1_844_674_407_370_955_161,
+            3_689_348_814_741_910_323,
+            1
+        ))
+    );
+    assert_eq!(
+        Price::new(-1, 0, 0).div(&Price::new(3, 0, 0)),
+        Ok(Price::new(-333_333_333_333_333_333, 0, -18))
+    );
+    assert_eq!(
+        Price::new(1, 0, i32::MAX).mul(&Price::new(1, 0, 1)),
+        Err(OracleError::IntegerCastingError)
+    );
+    assert_eq!(
+        Price::new(1, 0, i32::MAX).add(&Price::new(1, 0, i32::MIN)),
+        Err(OracleError::IntegerCastingError)
+    );
+}
+
+#[test]
+fn test_cmp() {
+    let a = Price::new(150, 10, -2);
+    assert_eq!(a.cmp(&Price::new(1_500, 0, -3)), Ordering::Equal);
+    assert_eq!(a.cmp(&Price::new(1_501, 0, -3)), Ordering::Less);
+    assert_eq!(a.cmp(&Price::new(-1_501, 0, -3)), Ordering::Greater);
+    assert_eq!(
+        Price::new(-150, 0, -2).cmp(&Price::new(-1_501, 0, -3)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        Price::new(0, 0, 5).cmp(&Price::new(0, 0, -5)),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Price::new(1, 0, i32::MAX).cmp(&Price::new(i64::MAX, 0, i32::MIN)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        Price::new(-1, 0, i32::MAX).cmp(&Price::new(i64::MIN, 0, i32::MIN)),
+        Ordering::Less
+    );
+}
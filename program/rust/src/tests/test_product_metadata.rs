@@ -0,0 +1,173 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            ProductAccount,
+            ProductMetadata,
+            ProductMetadataError,
+            PythAccount,
+            MAX_PC_STR_LEN,
+            MAX_PRODUCT_METADATA_LEN,
+        },
+        c_oracle_header::PC_VERSION,
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_product_metadata_round_trip() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut product_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let product_account = product_setup.as_account_info();
+    ProductAccount::initialize(&product_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let mut metadata = ProductMetadata::new();
+    assert_eq!(metadata.insert("symbol", "Crypto.BTC/USD"), None);
+    assert_eq!(metadata.insert("asset_type", "Crypto"), None);
+    assert_eq!(metadata.insert("base", "BTC"), None);
+    assert_eq!(
+        metadata.insert("symbol", "BTC/USD"),
+        Some(b"Crypto.BTC/USD".to_vec())
+    );
+    assert_eq!(metadata.remove(b"base"), Some(b"BTC".to_vec()));
+    assert_eq!(metadata.remove(b"base"), None);
+
+    let encoded = metadata.encode().unwrap();
+    assert_eq!(encoded.len(), metadata.encoded_len());
+    assert_eq!(
+        encoded,
+        b"\x06symbol\x07BTC/USD\x0aasset_type\x06Crypto".to_vec()
+    );
+
+    let header: CommandHeader = OracleCommand::UpdProduct.into();
+    let mut instruction_data = bytes_of(&header).to_vec();
+    instruction_data.extend_from_slice(&encoded);
+    process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            product_account.clone(),
+            permissions_account.clone(),
+        ],
+        &instruction_data,
+    )
+    .unwrap();
+
+    let parsed =
+        ProductMetadata::from_account_data(&product_account.try_borrow_data().unwrap()).unwrap();
+    assert_eq!(parsed, metadata);
+    assert_eq!(
+        parsed.iter().collect::<Vec<_>>(),
+        vec![
+            (&b"symbol"[..], &b"BTC/USD"[..]),
+            (&b"asset_type"[..], &b"Crypto"[..])
+        ]
+    );
+    assert_eq!(parsed.get("asset_type").as_deref(), Some("Crypto"));
+    assert_eq!(parsed.get("base"), None);
+
+    // Shrinking the metadata leaves stale bytes in the account that must be ignored
+    let header: CommandHeader = OracleCommand::UpdProduct.into();
+    process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            product_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of(&header),
+    )
+    .unwrap();
+    assert!(
+        ProductMetadata::from_account_data(&product_account.try_borrow_data().unwrap())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_product_metadata_errors() {
+    let long_string = "a".repeat(MAX_PC_STR_LEN + 1);
+    for (key, value) in [
+        (long_string.as_str(), "value"),
+        ("key", long_string.as_str()),
+    ] {
+        let mut metadata = ProductMetadata::new();
+        metadata.insert(key, value);
+        assert_eq!(
+            metadata.encode(),
+            Err(ProductMetadataError::StringTooLong {
+                len: MAX_PC_STR_LEN + 1,
+            })
+        );
+    }
+
+    let mut metadata = ProductMetadata::new();
+    metadata.insert("a", "a".repeat(MAX_PC_STR_LEN));
+    metadata.insert("b", "b".repeat(MAX_PC_STR_LEN));
+    assert_eq!(
+        metadata.encode(),
+        Err(ProductMetadataError::MetadataTooLarge {
+            len: 2 * (MAX_PC_STR_LEN + 3),
+            max: MAX_PRODUCT_METADATA_LEN,
+        })
+    );
+
+    assert_eq!(
+        ProductMetadata::parse(b"\x03key\x05val"),
+        Err(ProductMetadataError::TruncatedString { offset: 4 })
+    );
+    assert_eq!(
+        ProductMetadata::parse(b"\x03key"),
+        Err(ProductMetadataError::MissingValue {
+            key: "key".to_string(),
+        })
+    );
+    assert_eq!(
+        ProductMetadata::parse(&[0; MAX_PRODUCT_METADATA_LEN + 1]),
+        Err(ProductMetadataError::MetadataTooLarge {
+            len: MAX_PRODUCT_METADATA_LEN + 1,
+            max: MAX_PRODUCT_METADATA_LEN,
+        })
+    );
+    assert_eq!(
+        ProductMetadata::from_account_data(&[0; 10]),
+        Err(ProductMetadataError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn test_product_metadata_stored_by_the_program() {
+    // The program stores duplicate keys and strings that aren't UTF-8
+    let data = b"\x01a\x01b\x01k\x02\xff\xfe\x01a\x01c";
+    let mut metadata = ProductMetadata::parse(data).unwrap();
+    assert_eq!(metadata.len(), 3);
+    assert_eq!(metadata.encode().unwrap(), data.to_vec());
+    assert_eq!(metadata.get("a").as_deref(), Some("c"));
+    assert_eq!(metadata.get_bytes(b"k"), Some(&b"\xff\xfe"[..]));
+    assert_eq!(metadata.get("k").as_deref(), Some("\u{fffd}\u{fffd}"));
+
+    // Setting a duplicate key keeps its first position and drops the other entries
+    assert_eq!(metadata.insert("a", "d"), Some(b"c".to_vec()));
+    assert_eq!(
+        metadata.encode().unwrap(),
+        b"\x01a\x01d\x01k\x02\xff\xfe".to_vec()
+    );
+}
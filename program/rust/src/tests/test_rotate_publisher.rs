@@ -0,0 +1,219 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceComponent,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        events::Event,
+        instruction::{
+            OracleCommand,
+            RotatePublisherArgs,
+        },
+        processor::{
+            process_instruction,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+        },
+        tests::test_utils::{
+            take_logged_data,
+            AccountSetup,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_rotate_publisher() {
+    let program_id = Pubkey::new_unique();
+
+    let mut security_setup = AccountSetup::new_funding();
+    let security_account = security_setup.as_account_info();
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = Pubkey::new_unique();
+        permissions_account_data.security_authority = *security_account.key;
+    }
+
+    let other_publisher = Pubkey::new_unique();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        let mut publishers = [*publisher_account.key, other_publisher];
+        publishers.sort();
+        price_data.num_ = 2;
+        for (i, publisher) in publishers.iter().enumerate() {
+            price_data.comp_[i].pub_ = *publisher;
+            price_data.comp_[i].latest_.price_ = 100 + i as i64;
+            price_data.comp_[i].latest_.status_ = PC_STATUS_TRADING;
+            price_data.comp_[i].agg_.price_ = 200 + i as i64;
+            price_data.comp_[i].agg_.pub_slot_ = 300 + i as u64;
+        }
+    }
+    let publisher_component = find_component(&price_account, publisher_account.key).unwrap();
+
+    let as_publisher = [
+        publisher_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let as_security_authority = [
+        security_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    // The publisher can rotate its own key
+    let new_publisher = Pubkey::new_unique();
+    assert!(rotate(
+        &program_id,
+        &as_publisher,
+        *publisher_account.key,
+        new_publisher
+    )
+    .is_ok());
+    assert_eq!(
+        take_logged_data()
+            .into_iter()
+            .map(|fields| Event::from_bytes(&fields[0]).unwrap())
+            .collect::<Vec<_>>(),
+        vec![Event::PublisherRotated {
+            price_account: *price_account.key,
+            old_publisher: *publisher_account.key,
+            new_publisher,
+        }]
+    );
+    assert!(find_component(&price_account, publisher_account.key).is_none());
+    assert_eq!(
+        find_component(&price_account, &new_publisher).unwrap(),
+        PriceComponent {
+            pub_: new_publisher,
+            ..publisher_component
+        }
+    );
+    assert_sorted(&price_account);
+
+    // but not the key of other publishers
+    assert_eq!(
+        rotate(
+            &program_id,
+            &as_publisher,
+            other_publisher,
+            Pubkey::new_unique()
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+
+    // The security authority can rotate any key
+    let newer_publisher = Pubkey::new_unique();
+    assert!(rotate(
+        &program_id,
+        &as_security_authority,
+        new_publisher,
+        newer_publisher
+    )
+    .is_ok());
+    assert_eq!(
+        find_component(&price_account, &newer_publisher)
+            .unwrap()
+            .agg_
+            .price_,
+        publisher_component.agg_.price_
+    );
+    assert_sorted(&price_account);
+    take_logged_data();
+
+    assert_eq!(
+        rotate(
+            &program_id,
+            &as_security_authority,
+            new_publisher,
+            Pubkey::new_unique()
+        ),
+        Err(OracleError::PublisherNotFound.into())
+    );
+    assert_eq!(
+        rotate(
+            &program_id,
+            &as_security_authority,
+            newer_publisher,
+            other_publisher
+        ),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(
+        rotate(
+            &program_id,
+            &as_security_authority,
+            newer_publisher,
+            Pubkey::default()
+        ),
+        Err(OracleError::InvalidPublisherKey.into())
+    );
+    for sentinel in [ENABLE_ACCUMULATOR_V2, DISABLE_ACCUMULATOR_V2] {
+        assert_eq!(
+            rotate(
+                &program_id,
+                &as_security_authority,
+                newer_publisher,
+                Pubkey::from(sentinel)
+            ),
+            Err(OracleError::InvalidPublisherKey.into())
+        );
+    }
+    assert!(take_logged_data().is_empty());
+
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.num_, 2);
+}
+
+fn rotate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    old_publisher: Pubkey,
+    new_publisher: Pubkey,
+) -> ProgramResult {
+    let cmd = RotatePublisherArgs {
+        header: OracleCommand::RotatePublisher.into(),
+        old_publisher,
+        new_publisher,
+    };
+    process_instruction(program_id, accounts, bytes_of(&cmd))
+}
+
+fn find_component(price_account: &AccountInfo, publisher: &Pubkey) -> Option<PriceComponent> {
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    price_data.comp_[..price_data.num_ as usize]
+        .iter()
+        .find(|comp| comp.pub_ == *publisher)
+        .copied()
+}
+
+fn assert_sorted(price_account: &AccountInfo) {
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    let comps = &price_data.comp_[..price_data.num_ as usize];
+    assert!(comps.windows(2).all(|pair| pair[0].pub_ < pair[1].pub_));
+}
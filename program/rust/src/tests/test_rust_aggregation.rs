@@ -0,0 +1,316 @@
+extern crate test_generator;
+
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceInfo,
+        },
+        aggregation::{
+            self,
+            pd::{
+                Pd,
+                PD_EMA_MAX_DIFF,
+            },
+        },
+        c_oracle_header::{
+            MAX_NUM_DECIMALS,
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+        },
+        processor::{
+            c_upd_aggregate,
+            c_upd_twap,
+        },
+        tests::test_aggregation::load_quote_set,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    quickcheck::{
+        Arbitrary,
+        Gen,
+    },
+    quickcheck_macros::quickcheck,
+    test_generator::test_resources,
+};
+
+// Decimal arithmetic from pd.h, see program/c/src/oracle/util/test_pd.c
+mod c {
+    use crate::aggregation::pd::Pd;
+
+    #[link(name = "cpyth-test")]
+    extern "C" {
+        pub fn test_pd_mul(r: *mut Pd, n1: *const Pd, n2: *const Pd);
+        pub fn test_pd_div(r: *mut Pd, n1: *mut Pd, n2: *mut Pd);
+        pub fn test_pd_add(r: *mut Pd, n1: *const Pd, n2: *const Pd);
+        pub fn test_pd_sub(r: *mut Pd, n1: *const Pd, n2: *const Pd);
+    }
+}
+
+/// Run `upd_aggregate` then `upd_twap` with both engines on copies of `price_account`, and check
+/// that they return the same result and leave the same bytes in the account.
+fn assert_engines_agree(price_account: &PriceAccount, slot: u64, timestamp: i64, nslots: i64) {
+    let mut c_account = Box::new(*price_account);
+    let mut rust_account = Box::new(*price_account);
+
+    let c_result = unsafe {
+        c_upd_aggregate(
+            &mut *c_account as *mut PriceAccount as *mut u8,
+            slot,
+            timestamp,
+        )
+    };
+    let rust_result = aggregation::upd_aggregate(&mut rust_account, slot, timestamp);
+    assert_eq!(c_result, rust_result);
+    assert!(
+        bytes_of(&*c_account) == bytes_of(&*rust_account),
+        "upd_aggregate: {:?} != {:?}",
+        c_account.agg_,
+        rust_account.agg_
+    );
+
+    unsafe { c_upd_twap(&mut *c_account as *mut PriceAccount as *mut u8, nslots) };
+    aggregation::upd_twap(&mut rust_account, nslots);
+    assert!(
+        bytes_of(&*c_account) == bytes_of(&*rust_account),
+        "upd_twap: {:?} {:?} != {:?} {:?}",
+        c_account.twap_,
+        c_account.twac_,
+        rust_account.twap_,
+        rust_account.twac_
+    );
+}
+
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_engines_agree(input_path_raw: &str) {
+    // For some reason these tests have a different working directory than the macro.
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let price_account = load_quote_set(&input_path, 1000);
+    assert_engines_agree(&price_account, 1001, 1234, 1);
+}
+
+/// The inputs of an aggregation, in the states the program can reach: an exponent within
+/// `MAX_NUM_DECIMALS`, at most `PC_NUM_COMP` publishers and initialized EMAs. Outside of these
+/// states, the C code can index past its table of powers of 10 and divide by zero. Quotes are
+/// biased towards valid ones so that most aggregations succeed.
+#[derive(Clone, Debug)]
+struct AggregationInput {
+    exponent:    i32,
+    min_pub:     u8,
+    max_latency: u8,
+    agg:         PriceInfo,
+    ema_price:   i64,
+    ema_conf:    u64,
+    quotes:      Vec<PriceInfo>,
+    slot:        u64,
+    timestamp:   i64,
+    nslots:      i64,
+}
+
+fn arbitrary_quote(g: &mut Gen, slot: u64) -> PriceInfo {
+    let price = if bool::arbitrary(g) {
+        i64::arbitrary(g)
+    } else {
+        1_000_000 + i64::from(i16::arbitrary(g))
+    };
+    let conf = if bool::arbitrary(g) {
+        u64::arbitrary(g)
+    } else {
+        u64::from(u16::arbitrary(g))
+    };
+    let status = if u8::arbitrary(g) % 4 != 0 {
+        PC_STATUS_TRADING
+    } else {
+        u32::arbitrary(g) % 5
+    };
+    let pub_slot = if u8::arbitrary(g) % 8 != 0 {
+        slot - u64::from(u8::arbitrary(g) % 40)
+    } else {
+        u64::arbitrary(g)
+    };
+    PriceInfo {
+        price_:           price,
+        conf_:            conf,
+        status_:          status,
+        corp_act_status_: u32::arbitrary(g),
+        pub_slot_:        pub_slot,
+    }
+}
+
+impl Arbitrary for AggregationInput {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let slot = 1_000_000 + u64::from(u32::arbitrary(g));
+        let num_quotes = usize::from(u8::arbitrary(g)) % (PC_NUM_COMP as usize + 1);
+        let mut agg = PriceInfo::arbitrary(g);
+        // Aggregate confidences always fit in an i64
+        agg.conf_ &= i64::MAX as u64;
+        agg.pub_slot_ = slot - u64::from(u8::arbitrary(g));
+        AggregationInput {
+            exponent: i32::from(i8::arbitrary(g)) % (MAX_NUM_DECIMALS + 1),
+            min_pub: if bool::arbitrary(g) {
+                u8::arbitrary(g) % 4
+            } else {
+                u8::arbitrary(g)
+            },
+            max_latency: if bool::arbitrary(g) {
+                0
+            } else {
+                u8::arbitrary(g)
+            },
+            agg,
+            ema_price: i64::arbitrary(g),
+            ema_conf: 1 + u64::arbitrary(g) % (1 << 40),
+            quotes: (0..num_quotes).map(|_| arbitrary_quote(g, slot)).collect(),
+            slot,
+            timestamp: i64::arbitrary(g),
+            nslots: i64::from(u16::arbitrary(g)) % (2 * PD_EMA_MAX_DIFF),
+        }
+    }
+}
+
+impl AggregationInput {
+    fn to_price_account(&self) -> Box<PriceAccount> {
+        let mut price_account = Box::new(PriceAccount::zeroed());
+        price_account.exponent = self.exponent;
+        price_account.min_pub_ = self.min_pub;
+        price_account.max_latency_ = self.max_latency;
+        price_account.agg_ = self.agg;
+        // EMAs as left by a first `upd_twap`
+        let ema_price = Pd::new_scale(self.ema_price, self.exponent);
+        let ema_conf = Pd::new_scale(self.ema_conf as i64, self.exponent);
+        aggregation::upd_ema(
+            &mut price_account.twap_,
+            ema_price,
+            ema_conf,
+            PD_EMA_MAX_DIFF + 1,
+            self.exponent,
+        );
+        aggregation::upd_ema(
+            &mut price_account.twac_,
+            ema_conf,
+            ema_conf,
+            PD_EMA_MAX_DIFF + 1,
+            self.exponent,
+        );
+        price_account.num_ = self.quotes.len() as u32;
+        for (component, quote) in price_account.comp_.iter_mut().zip(&self.quotes) {
+            component.latest_ = *quote;
+        }
+        price_account
+    }
+}
+
+#[quickcheck]
+fn test_engines_agree(input: AggregationInput) -> bool {
+    assert_engines_agree(
+        &input.to_price_account(),
+        input.slot,
+        input.timestamp,
+        input.nslots,
+    );
+    true
+}
+
+/// Consecutive aggregations as done by `upd_price`, so that the EMAs and previous prices come
+/// from earlier runs.
+#[quickcheck]
+fn test_engines_agree_over_time(inputs: Vec<AggregationInput>) -> bool {
+    let (mut c_account, mut slot) = match inputs.first() {
+        Some(first) => (first.to_price_account(), first.slot),
+        None => return true,
+    };
+    let mut rust_account = c_account.clone();
+    for input in &inputs {
+        slot += (input.nslots % 4) as u64 + 1;
+        rust_account.num_ = input.quotes.len() as u32;
+        for (component, quote) in rust_account.comp_.iter_mut().zip(&input.quotes) {
+            component.latest_ = *quote;
+            // Publishing slots relative to the current slot
+            component.latest_.pub_slot_ =
+                quote.pub_slot_.wrapping_sub(input.slot).wrapping_add(slot);
+        }
+        *c_account = *rust_account;
+
+        let c_result = unsafe {
+            c_upd_aggregate(
+                &mut *c_account as *mut PriceAccount as *mut u8,
+                slot,
+                input.timestamp,
+            )
+        };
+        let rust_result = aggregation::upd_aggregate(&mut rust_account, slot, input.timestamp);
+        if c_result != rust_result {
+            return false;
+        }
+        if rust_result {
+            let agg_diff = (slot as i64) - rust_account.prev_slot_ as i64;
+            unsafe { c_upd_twap(&mut *c_account as *mut PriceAccount as *mut u8, agg_diff) };
+            aggregation::upd_twap(&mut rust_account, agg_diff);
+        }
+        if bytes_of(&*c_account) != bytes_of(&*rust_account) {
+            return false;
+        }
+    }
+    true
+}
+
+/// A `pd_t` with any value and an exponent in the range reached by the EMA computation.
+#[derive(Clone, Copy, Debug)]
+struct PdValue(Pd);
+
+impl Arbitrary for PdValue {
+    fn arbitrary(g: &mut Gen) -> Self {
+        PdValue(Pd::new(i64::arbitrary(g), i32::from(i8::arbitrary(g)) % 40))
+    }
+}
+
+#[quickcheck]
+fn test_pd_mul_matches_c(a: PdValue, b: PdValue) -> bool {
+    let mut r = Pd::default();
+    unsafe { c::test_pd_mul(&mut r, &a.0, &b.0) };
+    a.0.mul(b.0) == r
+}
+
+#[quickcheck]
+fn test_pd_div_matches_c(a: PdValue, b: PdValue) -> bool {
+    if b.0.v == 0 {
+        return true;
+    }
+    let (mut n1, mut n2) = (a.0, b.0);
+    let mut r = Pd::default();
+    unsafe { c::test_pd_div(&mut r, &mut n1, &mut n2) };
+    a.0.div(b.0) == r
+}
+
+#[quickcheck]
+fn test_pd_add_matches_c(a: PdValue, b: PdValue) -> bool {
+    let mut r = Pd::default();
+    unsafe { c::test_pd_add(&mut r, &a.0, &b.0) };
+    a.0.add(b.0) == r
+}
+
+#[quickcheck]
+fn test_pd_sub_matches_c(a: PdValue, b: PdValue) -> bool {
+    let mut r = Pd::default();
+    unsafe { c::test_pd_sub(&mut r, &a.0, &b.0) };
+    a.0.sub(b.0) == r
+}
+
+#[test]
+fn test_price_model_core() {
+    assert_eq!(aggregation::price_model_core(&mut [7]), (7, 7, 7));
+    assert_eq!(aggregation::price_model_core(&mut [9, 1, 5]), (1, 5, 9));
+    // Even counts take the floor of the average of the middle quotes
+    assert_eq!(aggregation::price_model_core(&mut [4, 1, 2, 3]), (2, 2, 3));
+    assert_eq!(
+        aggregation::price_model_core(&mut [-3, 0, -2, 1]),
+        (-2, -1, 0)
+    );
+    assert_eq!(
+        aggregation::price_model_core(&mut [i64::MAX, i64::MAX - 1]),
+        (i64::MAX - 1, i64::MAX - 1, i64::MAX)
+    );
+    assert_eq!(aggregation::avg_2_int64(i64::MIN, i64::MAX), -1);
+}
@@ -0,0 +1,783 @@
+use {
+    crate::{
+        accounts::{
+            Holiday,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+            ScheduleAccount,
+            TradingSession,
+            MAX_SESSIONS,
+            MAX_UTC_OFFSET,
+            MINUTES_PER_DAY,
+            MINUTES_PER_WEEK,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        events::Event,
+        instruction::{
+            builders,
+            UpdPriceReturnData,
+        },
+        processor::process_instruction,
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+            },
+            test_utils::{
+                take_logged_data,
+                take_return_data,
+                update_clock_slot,
+                update_clock_timestamp,
+                AccountSetup,
+            },
+        },
+        validator::{
+            self,
+            checked_load_price_account_mut,
+            AggregationError,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        instruction::InstructionError,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+/// 2024-01-01 00:00:00 UTC, a Monday
+const NEW_YEAR_2024: i64 = 1_704_067_200;
+/// New York during winter
+const UTC_OFFSET: i32 = -5 * 60 * 60;
+
+/// Unix timestamp of `minute` in New York, `day` days after 2024-01-01.
+fn new_york_time(day: i64, minute: i64) -> i64 {
+    NEW_YEAR_2024 + day * 24 * 60 * 60 + minute * 60 - i64::from(UTC_OFFSET)
+}
+
+/// Day of 2024-01-01 + `day`, counted from 1970-01-01
+fn holiday_day(day: i64) -> i32 {
+    (NEW_YEAR_2024 / (24 * 60 * 60) + day) as i32
+}
+
+/// Monday to Friday, from 09:30 to 16:00
+fn equity_sessions() -> Vec<TradingSession> {
+    (0..5)
+        .map(|weekday| TradingSession {
+            start_minute: weekday * MINUTES_PER_DAY + 9 * 60 + 30,
+            end_minute:   weekday * MINUTES_PER_DAY + 16 * 60,
+        })
+        .collect()
+}
+
+/// Closed on New Year's day, and only open until 13:00 on January 3rd
+fn equity_holidays() -> Vec<Holiday> {
+    vec![
+        Holiday {
+            day:          holiday_day(0),
+            start_minute: 0,
+            end_minute:   0,
+        },
+        Holiday {
+            day:          holiday_day(2),
+            start_minute: 9 * 60 + 30,
+            end_minute:   13 * 60,
+        },
+    ]
+}
+
+fn equity_schedule(price_account: &Pubkey) -> ScheduleAccount {
+    let sessions = equity_sessions();
+    let holidays = equity_holidays();
+    let mut schedule = ScheduleAccount {
+        price_account: *price_account,
+        utc_offset: UTC_OFFSET,
+        num_sessions: sessions.len() as u8,
+        num_holidays: holidays.len() as u8,
+        ..ScheduleAccount::zeroed()
+    };
+    schedule.sessions[..sessions.len()].copy_from_slice(&sessions);
+    schedule.holidays[..holidays.len()].copy_from_slice(&holidays);
+    schedule
+}
+
+#[test]
+fn test_is_trading() {
+    let schedule = equity_schedule(&Pubkey::new_unique());
+    assert!(schedule.is_valid());
+
+    // Tuesday
+    assert!(!schedule.is_trading(new_york_time(1, 9 * 60 + 29)));
+    assert!(schedule.is_trading(new_york_time(1, 9 * 60 + 30)));
+    assert!(schedule.is_trading(new_york_time(1, 15 * 60 + 59)));
+    assert!(!schedule.is_trading(new_york_time(1, 16 * 60)));
+    // The last seconds of a minute belong to that minute
+    assert!(schedule.is_trading(new_york_time(1, 16 * 60) - 1));
+    // 16:00 in London is 11:00 in New York
+    assert!(schedule.is_trading(NEW_YEAR_2024 + 24 * 60 * 60 + 16 * 60 * 60));
+
+    // Closed on weekends
+    assert!(!schedule.is_trading(new_york_time(5, 12 * 60)));
+    assert!(!schedule.is_trading(new_york_time(6, 12 * 60)));
+    // Open on Friday and the following Monday
+    assert!(schedule.is_trading(new_york_time(4, 12 * 60)));
+    assert!(schedule.is_trading(new_york_time(7, 12 * 60)));
+
+    // Holidays replace the weekly sessions
+    assert!(!schedule.is_trading(new_york_time(0, 12 * 60)));
+    assert!(schedule.is_trading(new_york_time(2, 12 * 60)));
+    assert!(!schedule.is_trading(new_york_time(2, 14 * 60)));
+
+    // Timestamps before 1970 are supported
+    assert!(!schedule.is_trading(-1));
+    assert!(!ScheduleAccount::zeroed().is_trading(new_york_time(1, 12 * 60)));
+}
+
+#[test]
+fn test_is_valid() {
+    let valid = equity_schedule(&Pubkey::new_unique());
+    assert!(valid.is_valid());
+    assert!(ScheduleAccount::zeroed().is_valid());
+
+    let mut schedule = valid;
+    schedule.sessions[0].end_minute = schedule.sessions[0].start_minute;
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.sessions[4].end_minute = MINUTES_PER_WEEK + 1;
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.holidays[1].end_minute = MINUTES_PER_DAY + 1;
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.holidays.swap(0, 1);
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.holidays[1].day = schedule.holidays[0].day;
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.utc_offset = MAX_UTC_OFFSET;
+    assert!(schedule.is_valid());
+    schedule.utc_offset = -MAX_UTC_OFFSET - 1;
+    assert!(!schedule.is_valid());
+
+    let mut schedule = valid;
+    schedule.num_sessions = MAX_SESSIONS as u8 + 1;
+    assert!(!schedule.is_valid());
+}
+
+#[test]
+fn test_set_schedule() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut schedule_setup = AccountSetup::new_schedule(&program_id, price_account.key);
+    let mut schedule_account = schedule_setup.as_account_info();
+    schedule_account.is_signer = false;
+    // The account is created by the first `SetSchedule`, which tests can't invoke
+    ScheduleAccount::initialize(&schedule_account, PC_VERSION).unwrap();
+
+    let system_program_key = system_program::id();
+    let system_program_owner = Pubkey::default();
+    let mut system_program_lamports = 0;
+    let mut system_program_data = [];
+    let system_program_account = AccountInfo::new(
+        &system_program_key,
+        false,
+        false,
+        &mut system_program_lamports,
+        &mut system_program_data,
+        &system_program_owner,
+        true,
+        0,
+    );
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .data_curation_authority = *funding_account.key;
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        schedule_account.clone(),
+        system_program_account.clone(),
+        permissions_account.clone(),
+    ];
+    let set_schedule = |utc_offset: i32, sessions: &[TradingSession], holidays: &[Holiday]| {
+        let instruction = builders::set_schedule(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            utc_offset,
+            sessions,
+            holidays,
+        );
+        process_instruction(&program_id, &accounts, &instruction.data)
+    };
+
+    assert!(set_schedule(UTC_OFFSET, &equity_sessions(), &equity_holidays()).is_ok());
+    {
+        let schedule_data = load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION).unwrap();
+        assert_eq!(
+            schedule_data.header.account_type,
+            ScheduleAccount::ACCOUNT_TYPE
+        );
+        assert_eq!(schedule_data.price_account, *price_account.key);
+        assert_eq!(schedule_data.utc_offset, UTC_OFFSET);
+        assert_eq!(schedule_data.sessions(), &equity_sessions()[..]);
+        assert_eq!(schedule_data.holidays(), &equity_holidays()[..]);
+    }
+    assert!(load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .flags
+        .contains(PriceAccountFlags::SCHEDULED));
+    assert_eq!(
+        Event::from_bytes(&take_logged_data()[0][0]),
+        Ok(Event::ScheduleSet {
+            price_account: *price_account.key,
+            num_sessions:  5,
+            num_holidays:  2,
+        })
+    );
+
+    // Setting a schedule replaces the previous one
+    let sessions = equity_sessions();
+    assert!(set_schedule(0, &sessions[..1], &[]).is_ok());
+    {
+        let schedule_data = load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION).unwrap();
+        assert_eq!(schedule_data.utc_offset, 0);
+        assert_eq!(schedule_data.sessions(), &sessions[..1]);
+        assert!(schedule_data.holidays().is_empty());
+        assert_eq!(schedule_data.holidays[0], Holiday::zeroed());
+    }
+    take_logged_data();
+
+    // Malformed schedules are rejected without changing the current one
+    let mut holidays = equity_holidays();
+    holidays.reverse();
+    assert_eq!(
+        set_schedule(UTC_OFFSET, &sessions, &holidays),
+        Err(OracleError::InvalidSchedule.into())
+    );
+    assert_eq!(
+        set_schedule(MAX_UTC_OFFSET + 1, &sessions, &[]),
+        Err(OracleError::InvalidSchedule.into())
+    );
+    assert_eq!(
+        set_schedule(
+            0,
+            &[TradingSession {
+                start_minute: 0,
+                end_minute:   MINUTES_PER_WEEK + 1,
+            }],
+            &[]
+        ),
+        Err(OracleError::InvalidSchedule.into())
+    );
+    let mut instruction = builders::set_schedule(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        0,
+        &sessions,
+        &[],
+    );
+    instruction.data.pop();
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction.data),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION)
+            .unwrap()
+            .sessions(),
+        &sessions[..1]
+    );
+    assert!(take_logged_data().is_empty());
+
+    // The schedule account must be the PDA of the price account
+    let mut other_schedule_setup = AccountSetup::new_schedule(&program_id, &Pubkey::new_unique());
+    let other_schedule_account = other_schedule_setup.as_account_info();
+    let instruction = builders::set_schedule(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        0,
+        &sessions,
+        &[],
+    );
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                other_schedule_account,
+                system_program_account.clone(),
+                permissions_account.clone(),
+            ],
+            &instruction.data
+        ),
+        Err(OracleError::InvalidPda.into())
+    );
+
+    // Only the data curation authority can set schedules
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    let instruction = builders::set_schedule(
+        &program_id,
+        attacker_account.key,
+        price_account.key,
+        0,
+        &sessions,
+        &[],
+    );
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                schedule_account.clone(),
+                system_program_account.clone(),
+                permissions_account.clone(),
+            ],
+            &instruction.data
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+}
+
+fn publish(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    price: i64,
+    slot: u64,
+) -> Result<UpdPriceReturnData, ProgramError> {
+    let instruction = builders::upd_price(
+        program_id,
+        accounts[0].key,
+        accounts[1].key,
+        PC_STATUS_TRADING,
+        price,
+        1,
+        slot,
+        None,
+    );
+    process_instruction(program_id, accounts, &instruction.data)?;
+    Ok(UpdPriceReturnData::from_bytes(&take_return_data()).unwrap())
+}
+
+#[test]
+fn test_upd_price_scheduled() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+        price_data.flags = PriceAccountFlags::SCHEDULED;
+    }
+
+    let mut schedule_setup = AccountSetup::new_schedule(&program_id, price_account.key);
+    let mut schedule_account = schedule_setup.as_account_info();
+    schedule_account.is_signer = false;
+    schedule_account.is_writable = false;
+    ScheduleAccount::initialize(&schedule_account, PC_VERSION).unwrap();
+    {
+        let mut schedule_data =
+            load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION).unwrap();
+        let header = schedule_data.header;
+        *schedule_data = equity_schedule(price_account.key);
+        schedule_data.header = header;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let scheduled_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+        price_account.clone(),
+        schedule_account.clone(),
+    ];
+    let unscheduled_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+
+    // During a session, prices are aggregated as usual
+    update_clock_slot(&mut clock_account, 1);
+    update_clock_timestamp(&mut clock_account, new_york_time(1, 10 * 60));
+    publish(&program_id, &scheduled_accounts, 42, 1).unwrap();
+    update_clock_slot(&mut clock_account, 2);
+    let return_data = publish(&program_id, &scheduled_accounts, 43, 2).unwrap();
+    assert!(return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_TRADING);
+    assert_eq!(return_data.agg_price, 42);
+    take_logged_data();
+
+    // After the close, the aggregate is held at the last trading price with an unknown status,
+    // while the components are still updated
+    update_clock_slot(&mut clock_account, 3);
+    update_clock_timestamp(&mut clock_account, new_york_time(1, 17 * 60));
+    let return_data = publish(&program_id, &scheduled_accounts, 44, 3).unwrap();
+    assert!(!return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_UNKNOWN);
+    assert_eq!(return_data.agg_price, 42);
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 44);
+        assert_eq!(price_data.agg_.pub_slot_, 3);
+        assert_eq!(price_data.last_slot_, 2);
+        assert_eq!(price_data.prev_price_, 42);
+        assert_eq!(price_data.prev_slot_, 2);
+        assert_eq!(price_data.message_sent_, 0);
+        let message = price_data.as_price_feed_message(price_account.key);
+        assert_eq!(message.price, 42);
+        assert_eq!(message.publish_time, price_data.prev_timestamp_);
+    }
+    match Event::from_bytes(&take_logged_data()[0][0]) {
+        Ok(Event::AggregateUpdated { status, price, .. }) => {
+            assert_eq!(status, PC_STATUS_UNKNOWN);
+            assert_eq!(price, 42);
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+
+    // The next session aggregates the latest components
+    update_clock_slot(&mut clock_account, 4);
+    update_clock_timestamp(&mut clock_account, new_york_time(2, 10 * 60));
+    let return_data = publish(&program_id, &scheduled_accounts, 45, 4).unwrap();
+    assert!(return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_TRADING);
+    assert_eq!(return_data.agg_price, 44);
+    take_logged_data();
+
+    // Without the schedule account, the update is rejected rather than aggregated or held
+    // without knowing the trading hours
+    update_clock_slot(&mut clock_account, 5);
+    assert_eq!(
+        publish(&program_id, &unscheduled_accounts, 46, 5),
+        Err(OracleError::ScheduleAccountRequired.into())
+    );
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 45);
+        assert_eq!(price_data.agg_.pub_slot_, 4);
+    }
+    assert!(take_logged_data().is_empty());
+
+    // The schedule must belong to the price account
+    load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION)
+        .unwrap()
+        .price_account = Pubkey::new_unique();
+    update_clock_slot(&mut clock_account, 6);
+    assert_eq!(
+        publish(&program_id, &scheduled_accounts, 47, 6),
+        Err(OracleError::InvalidPda.into())
+    );
+    load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION)
+        .unwrap()
+        .price_account = *price_account.key;
+
+    // Clearing the flag stops following the schedule
+    load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .flags = PriceAccountFlags::empty();
+    update_clock_timestamp(&mut clock_account, new_york_time(5, 12 * 60));
+    let return_data = publish(&program_id, &unscheduled_accounts, 47, 6).unwrap();
+    assert!(return_data.aggregate_triggered);
+    assert_eq!(return_data.agg_status, PC_STATUS_TRADING);
+    assert_eq!(return_data.agg_price, 45);
+    take_logged_data();
+}
+
+#[test]
+fn test_schedule_with_validator() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+        price_data.flags = PriceAccountFlags::ACCUMULATOR_V2
+            | PriceAccountFlags::MESSAGE_BUFFER_CLEARED
+            | PriceAccountFlags::SCHEDULED;
+    }
+
+    let mut schedule_setup = AccountSetup::new_schedule(&program_id, price_account.key);
+    let schedule_account = schedule_setup.as_account_info();
+    ScheduleAccount::initialize(&schedule_account, PC_VERSION).unwrap();
+    {
+        let mut schedule_data =
+            load_checked::<ScheduleAccount>(&schedule_account, PC_VERSION).unwrap();
+        let header = schedule_data.header;
+        *schedule_data = equity_schedule(price_account.key);
+        schedule_data.header = header;
+    }
+    let schedule_data = schedule_account.data.borrow();
+    let schedule = validator::checked_load_schedule_account(&schedule_data).unwrap();
+    assert!(validator::checked_load_schedule_account(&price_account.data.borrow()).is_none());
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    let publish_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+    publish(&program_id, &publish_accounts, 42, 1).unwrap();
+
+    // The schedule of another price account is rejected, leaving the price account unchanged
+    let other_schedule = equity_schedule(&Pubkey::new_unique());
+    {
+        let mut price_data =
+            Box::new(*checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap());
+        assert_eq!(
+            validator::aggregate_price_with_schedule(
+                2,
+                new_york_time(1, 10 * 60),
+                price_account.key,
+                &mut price_data,
+                Some(&other_schedule),
+            ),
+            Err(AggregationError::ScheduleMismatch)
+        );
+        assert_eq!(price_data.agg_.pub_slot_, 0);
+        assert_eq!(price_data.timestamp_, 0);
+    }
+
+    // Without its schedule, a scheduled price account is aggregated like an unscheduled one, even
+    // outside of its trading hours
+    {
+        let mut price_data =
+            Box::new(*checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap());
+        validator::aggregate_price(
+            2,
+            new_york_time(5, 10 * 60),
+            price_account.key,
+            &mut price_data,
+        )
+        .unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.pub_slot_, 2);
+        assert_eq!(price_data.agg_.price_, 42);
+    }
+
+    validator::aggregate_price_with_schedule(
+        2,
+        new_york_time(1, 10 * 60),
+        price_account.key,
+        checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
+        Some(schedule),
+    )
+    .unwrap();
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.price_, 42);
+    }
+
+    update_clock_slot(&mut clock_account, 3);
+    publish(&program_id, &publish_accounts, 50, 3).unwrap();
+    let messages = validator::aggregate_price_with_schedule(
+        3,
+        new_york_time(5, 10 * 60),
+        price_account.key,
+        checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
+        Some(schedule),
+    )
+    .unwrap();
+
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.comp_[0].latest_.price_, 50);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.agg_.pub_slot_, 3);
+    assert_eq!(price_data.agg_.price_, 42);
+    assert_eq!(price_data.timestamp_, new_york_time(5, 10 * 60));
+    assert_eq!(price_data.prev_timestamp_, new_york_time(1, 10 * 60));
+    assert_eq!(
+        messages,
+        [
+            price_data
+                .as_price_feed_message(price_account.key)
+                .to_bytes(),
+            price_data.as_twap_message(price_account.key).to_bytes(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_schedule_with_simulator() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price = price_accounts["LTC"];
+
+    sim.set_schedule(price, UTC_OFFSET, &equity_sessions(), &equity_holidays())
+        .await
+        .unwrap();
+    let schedule = sim
+        .get_account_data_as::<ScheduleAccount>(sim.get_schedule_pubkey(price))
+        .await
+        .unwrap();
+    assert_eq!(schedule.price_account, price);
+    assert_eq!(schedule.sessions(), &equity_sessions()[..]);
+    assert_eq!(schedule.holidays(), &equity_holidays()[..]);
+
+    let quote = |price| Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    };
+
+    sim.warp_to_timestamp(new_york_time(1, 10 * 60)).await;
+    sim.upd_price_scheduled(&publisher, price, quote(150))
+        .await
+        .unwrap();
+    sim.warp_to_slot(2).await.unwrap();
+    sim.warp_to_timestamp(new_york_time(1, 11 * 60)).await;
+    sim.upd_price_scheduled(&publisher, price, quote(151))
+        .await
+        .unwrap();
+    {
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.price_, 150);
+    }
+
+    // On New Year's day, the aggregate is held
+    sim.warp_to_slot(3).await.unwrap();
+    sim.warp_to_timestamp(new_york_time(0, 12 * 60)).await;
+    sim.upd_price_scheduled(&publisher, price, quote(152))
+        .await
+        .unwrap();
+    {
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 152);
+        assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+        assert_eq!(price_data.agg_.price_, 150);
+        assert_eq!(price_data.agg_.pub_slot_, 3);
+    }
+
+    // The next session resumes the aggregation
+    sim.warp_to_slot(4).await.unwrap();
+    sim.warp_to_timestamp(new_york_time(2, 10 * 60)).await;
+    sim.upd_price_scheduled(&publisher, price, quote(153))
+        .await
+        .unwrap();
+    {
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.price_, 152);
+    }
+
+    // Updates without the schedule account are rejected
+    sim.warp_to_slot(5).await.unwrap();
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote(154))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::ScheduleAccountRequired as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_set_schedule_funded_schedule_account() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price = price_accounts["LTC"];
+
+    // Anyone can transfer lamports to the address of the schedule account before it exists
+    let schedule_pubkey = sim.get_schedule_pubkey(price);
+    sim.airdrop(&schedule_pubkey, Rent::default().minimum_balance(0))
+        .await
+        .unwrap();
+
+    sim.set_schedule(price, UTC_OFFSET, &equity_sessions(), &equity_holidays())
+        .await
+        .unwrap();
+    let schedule_account = sim.get_account(schedule_pubkey).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&schedule_account));
+    assert_eq!(
+        schedule_account.lamports,
+        Rent::default().minimum_balance(size_of::<ScheduleAccount>())
+    );
+    let schedule = sim
+        .get_account_data_as::<ScheduleAccount>(schedule_pubkey)
+        .await
+        .unwrap();
+    assert_eq!(schedule.price_account, price);
+    assert_eq!(schedule.sessions(), &equity_sessions()[..]);
+}
@@ -0,0 +1,254 @@
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            DelegationAccount,
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            ProductAccount,
+            ProductAccountData,
+            ProductMetadata,
+            PythAccount,
+            ScheduleAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Pod,
+        Zeroable,
+    },
+    serde::{
+        de::DeserializeOwned,
+        Serialize,
+    },
+    serde_json::json,
+    solana_program::pubkey::Pubkey,
+};
+
+fn header<T: PythAccount>() -> AccountHeader {
+    AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: T::ACCOUNT_TYPE,
+        size:         T::INITIAL_SIZE,
+    }
+}
+
+/// Checks that the account survives a round trip through JSON and returns the JSON value
+fn round_trip<T: Pod + Serialize + DeserializeOwned>(account: &T) -> serde_json::Value {
+    let value = serde_json::to_value(account).unwrap();
+    let text = serde_json::to_string(&value).unwrap();
+    let loaded: T = serde_json::from_str(&text).unwrap();
+    assert_eq!(bytes_of(&loaded), bytes_of(account));
+    value
+}
+
+#[test]
+fn test_price_account_json() {
+    let publisher = Pubkey::new_unique();
+    let product = Pubkey::new_unique();
+
+    let mut price_account = PriceAccount::zeroed();
+    price_account.header = header::<PriceAccount>();
+    price_account.exponent = -5;
+    price_account.num_ = 2;
+    price_account.product_account = product;
+    price_account.flags =
+        PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+    price_account.agg_ = PriceInfo {
+        price_:           100,
+        conf_:            3,
+        status_:          PC_STATUS_TRADING,
+        corp_act_status_: 0,
+        pub_slot_:        42,
+    };
+    price_account.comp_[0].pub_ = publisher;
+    price_account.comp_[0].latest_ = price_account.agg_;
+    price_account.comp_[1].pub_ = Pubkey::new_unique();
+    price_account.comp_[1].latest_.status_ = 7;
+    price_account.excluded_components = 0b10;
+    price_account.price_cumulative.price = -(1 << 100);
+    price_account.price_cumulative.conf = 1 << 120;
+
+    let value = round_trip(&price_account);
+    assert_eq!(value["product_account"], json!(product.to_string()));
+    assert_eq!(value["agg_"]["status_"], json!("PC_STATUS_TRADING"));
+    assert_eq!(
+        value["price_cumulative"]["conf"],
+        json!("1329227995784915872903807060280344576")
+    );
+    assert_eq!(
+        value["flags"],
+        json!(["ACCUMULATOR_V2", "MESSAGE_BUFFER_CLEARED"])
+    );
+    assert_eq!(value["comp_"].as_array().unwrap().len(), 2);
+    assert_eq!(value["comp_"][0]["pub_"], json!(publisher.to_string()));
+    assert_eq!(value["comp_"][1]["latest_"]["status_"], json!(7));
+    assert_eq!(
+        value["comp_"][1]["agg_"]["status_"],
+        json!("PC_STATUS_UNKNOWN")
+    );
+    assert_eq!(value["excluded_components"], json!(2));
+
+    // Bits without a name are kept
+    price_account.flags = PriceAccountFlags::from_bits_retain(0x81);
+    let value = round_trip(&price_account);
+    assert_eq!(value["flags"], json!(["ACCUMULATOR_V2", "0x80"]));
+
+    // The number of components has to match num_
+    let mut value = value;
+    value["num_"] = json!(3);
+    assert!(serde_json::from_value::<PriceAccount>(value.clone()).is_err());
+
+    value["num_"] = json!(2);
+    value["flags"] = json!(["NOT_A_FLAG"]);
+    assert!(serde_json::from_value::<PriceAccount>(value.clone()).is_err());
+
+    value["flags"] = json!([]);
+    value["agg_"]["status_"] = json!("PC_STATUS_OPEN");
+    assert!(serde_json::from_value::<PriceAccount>(value).is_err());
+}
+
+#[test]
+fn test_mapping_account_json() {
+    let mut mapping_account = MappingAccount::zeroed();
+    mapping_account.header = header::<MappingAccount>();
+    mapping_account.number_of_products = 3;
+    mapping_account.next_mapping_account = Pubkey::new_unique();
+    for i in 0..3 {
+        mapping_account.products_list[i] = Pubkey::new_unique();
+    }
+
+    let mut value = round_trip(&mapping_account);
+    assert_eq!(
+        value["products_list"],
+        json!(mapping_account.products_list[..3]
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>())
+    );
+
+    value["products_list"][1] = json!("not base58!");
+    assert!(serde_json::from_value::<MappingAccount>(value.clone()).is_err());
+
+    value["products_list"] = json!([]);
+    assert!(serde_json::from_value::<MappingAccount>(value).is_err());
+}
+
+#[test]
+fn test_product_and_permission_account_json() {
+    let product_account = ProductAccount {
+        header:              header::<ProductAccount>(),
+        first_price_account: Pubkey::new_unique(),
+    };
+    let value = round_trip(&product_account);
+    assert_eq!(
+        value["first_price_account"],
+        json!(product_account.first_price_account.to_string())
+    );
+
+    let permission_account = PermissionAccount {
+        header:                  header::<PermissionAccount>(),
+        master_authority:        Pubkey::new_unique(),
+        data_curation_authority: Pubkey::new_unique(),
+        security_authority:      Pubkey::new_unique(),
+    };
+    let value = round_trip(&permission_account);
+    assert_eq!(
+        value["security_authority"],
+        json!(permission_account.security_authority.to_string())
+    );
+}
+
+#[test]
+fn test_product_account_data_json() {
+    let mut metadata = ProductMetadata::new();
+    metadata.push("symbol", "Crypto.BTC/USD");
+    metadata.push("base", vec![0xff, 0xfe]);
+    metadata.push("symbol", "BTC/USD");
+    let mut product_account = ProductAccount {
+        header:              header::<ProductAccount>(),
+        first_price_account: Pubkey::new_unique(),
+    };
+    product_account.header.size += metadata.encoded_len() as u32;
+    let data = ProductAccountData {
+        account: product_account,
+        metadata,
+    }
+    .to_account_data()
+    .unwrap();
+
+    let value =
+        serde_json::to_value(ProductAccountData::from_account_data(&data).unwrap()).unwrap();
+    assert_eq!(
+        value["first_price_account"],
+        json!(product_account.first_price_account.to_string())
+    );
+    assert_eq!(
+        value["metadata"],
+        json!([
+            ["symbol", "Crypto.BTC/USD"],
+            ["base", [0xff, 0xfe]],
+            ["symbol", "BTC/USD"]
+        ])
+    );
+    let text = serde_json::to_string(&value).unwrap();
+    let loaded: ProductAccountData = serde_json::from_str(&text).unwrap();
+    assert_eq!(loaded.to_account_data().unwrap(), data);
+
+    // The size in the header must match the metadata
+    let mut value = value;
+    value["metadata"] = json!([]);
+    assert!(serde_json::from_value::<ProductAccountData>(value).is_err());
+}
+
+#[test]
+fn test_delegation_account_json() {
+    let mut delegation_account = DelegationAccount::zeroed();
+    delegation_account.header = header::<DelegationAccount>();
+    delegation_account.publisher = Pubkey::new_unique();
+    delegation_account.num_delegates = 1;
+    delegation_account.delegates[0].key = Pubkey::new_unique();
+    delegation_account.delegates[0].expiry_slot = 100;
+
+    let value = round_trip(&delegation_account);
+    assert_eq!(
+        value["publisher"],
+        json!(delegation_account.publisher.to_string())
+    );
+    assert_eq!(
+        value["delegates"][0]["key"],
+        json!(delegation_account.delegates[0].key.to_string())
+    );
+    assert_eq!(value["delegates"][0]["expiry_slot"], json!(100));
+}
+
+#[test]
+fn test_schedule_account_json() {
+    let mut schedule_account = ScheduleAccount::zeroed();
+    schedule_account.header = header::<ScheduleAccount>();
+    schedule_account.price_account = Pubkey::new_unique();
+    schedule_account.utc_offset = -5 * 60 * 60;
+    schedule_account.num_sessions = 1;
+    schedule_account.sessions[0].start_minute = 570;
+    schedule_account.sessions[0].end_minute = 960;
+    schedule_account.num_holidays = 1;
+    schedule_account.holidays[0].day = 19_716;
+
+    let value = round_trip(&schedule_account);
+    assert_eq!(
+        value["price_account"],
+        json!(schedule_account.price_account.to_string())
+    );
+    assert_eq!(value["utc_offset"], json!(-18_000));
+    assert_eq!(value["sessions"][0]["start_minute"], json!(570));
+    assert_eq!(value["holidays"][0]["day"], json!(19_716));
+}
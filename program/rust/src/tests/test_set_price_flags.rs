@@ -0,0 +1,234 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_checked,
+        error::OracleError,
+        events::Event,
+        instruction::{
+            AddPublisherArgs,
+            OracleCommand,
+            SetPriceFlagsArgs,
+        },
+        processor::{
+            process_instruction,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+        },
+        tests::test_utils::{
+            take_events,
+            AccountSetup,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_set_price_flags() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+        permissions_account_data.data_curation_authority = *funding_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let set_price_flags = |set: PriceAccountFlags, clear: PriceAccountFlags| {
+        let cmd = SetPriceFlagsArgs {
+            header:      OracleCommand::SetPriceFlags.into(),
+            set_flags:   set.bits(),
+            clear_flags: clear.bits(),
+            unused_:     [0; 2],
+        };
+        process_instruction(&program_id, &accounts, bytes_of(&cmd))
+    };
+    let v2 = PriceAccountFlags::ACCUMULATOR_V2;
+    let cleared = PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+    let none = PriceAccountFlags::empty();
+
+    assert!(set_price_flags(v2, none).is_ok());
+    assert_eq!(get_flags(&price_account), v2.bits());
+    assert_eq!(
+        take_events(),
+        vec![Event::PriceFlagsSet {
+            price_account: *price_account.key,
+            flags:         v2.bits(),
+        }]
+    );
+
+    // Setting a flag that is already set is fine
+    assert!(set_price_flags(v2, none).is_ok());
+    assert_eq!(get_flags(&price_account), v2.bits());
+    take_events();
+
+    set_flags(&price_account, v2 | cleared);
+
+    // MESSAGE_BUFFER_CLEARED can't be set by instruction, nor outlive ACCUMULATOR_V2, PAUSED is
+    // only changed by PauseFeed and ResumeFeed and SCHEDULED is only set by SetSchedule
+    let paused = PriceAccountFlags::PAUSED;
+    let scheduled = PriceAccountFlags::SCHEDULED;
+    for (set, clear) in [
+        (cleared, none),
+        (none, v2),
+        (v2, v2),
+        (paused, none),
+        (none, paused),
+        (scheduled, none),
+    ] {
+        assert_eq!(
+            set_price_flags(set, clear),
+            Err(OracleError::InvalidPriceFlags.into())
+        );
+        assert_eq!(get_flags(&price_account), (v2 | cleared).bits());
+    }
+
+    // Clearing MESSAGE_BUFFER_CLEARED makes the next update clear the message buffer
+    assert!(set_price_flags(none, cleared).is_ok());
+    assert_eq!(get_flags(&price_account), v2.bits());
+    set_flags(&price_account, v2 | cleared);
+
+    assert!(set_price_flags(none, v2 | cleared).is_ok());
+    assert_eq!(get_flags(&price_account), 0);
+    take_events();
+
+    // Clearing SCHEDULED stops following the schedule
+    set_flags(&price_account, scheduled);
+    assert!(set_price_flags(none, scheduled).is_ok());
+    assert_eq!(get_flags(&price_account), 0);
+    take_events();
+
+    // Unknown flags are rejected
+    for (set, clear) in [(0b1_0000, 0), (0, 0b1000_0000)] {
+        let cmd = SetPriceFlagsArgs {
+            header:      OracleCommand::SetPriceFlags.into(),
+            set_flags:   set,
+            clear_flags: clear,
+            unused_:     [0; 2],
+        };
+        assert_eq!(
+            process_instruction(&program_id, &accounts, bytes_of(&cmd)),
+            Err(OracleError::InvalidPriceFlags.into())
+        );
+    }
+    assert!(take_events().is_empty());
+
+    let cmd = SetPriceFlagsArgs {
+        header:      OracleCommand::SetPriceFlags.into(),
+        set_flags:   v2.bits(),
+        clear_flags: 0,
+        unused_:     [0; 2],
+    };
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            bytes_of(&cmd)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    assert_eq!(get_flags(&price_account), 0);
+}
+
+#[test]
+fn test_deprecated_accumulator_v2_publishers() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let add_publisher = |publisher: [u8; 32]| {
+        let cmd = AddPublisherArgs {
+            header:    OracleCommand::AddPublisher.into(),
+            publisher: Pubkey::from(publisher),
+        };
+        process_instruction(&program_id, &accounts, bytes_of(&cmd))
+    };
+
+    assert!(add_publisher(ENABLE_ACCUMULATOR_V2).is_ok());
+    assert_eq!(
+        get_flags(&price_account),
+        PriceAccountFlags::ACCUMULATOR_V2.bits()
+    );
+    assert_eq!(
+        take_events(),
+        vec![Event::PriceFlagsSet {
+            price_account: *price_account.key,
+            flags:         PriceAccountFlags::ACCUMULATOR_V2.bits(),
+        }]
+    );
+
+    set_flags(
+        &price_account,
+        PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED,
+    );
+    assert!(add_publisher(DISABLE_ACCUMULATOR_V2).is_ok());
+    assert_eq!(get_flags(&price_account), 0);
+
+    // The sentinels are never stored as publishers
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.num_, 0);
+}
+
+fn get_flags(price_account: &AccountInfo) -> u8 {
+    load_checked::<PriceAccount>(price_account, PC_VERSION)
+        .unwrap()
+        .flags
+        .bits()
+}
+
+fn set_flags(price_account: &AccountInfo, flags: PriceAccountFlags) {
+    load_checked::<PriceAccount>(price_account, PC_VERSION)
+        .unwrap()
+        .flags = flags;
+}
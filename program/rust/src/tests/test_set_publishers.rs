@@ -0,0 +1,261 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceComponent,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        events::Event,
+        instruction::builders,
+        processor::{
+            process_instruction,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+        },
+        tests::test_utils::{
+            take_logged_data,
+            AccountSetup,
+        },
+        OracleError,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+fn publishers(price_account: &AccountInfo) -> Vec<Pubkey> {
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    price_data.comp_[..price_data.num_ as usize]
+        .iter()
+        .map(|comp| comp.pub_)
+        .collect()
+}
+
+fn sorted(mut publishers: Vec<Pubkey>) -> Vec<Pubkey> {
+    publishers.sort();
+    publishers
+}
+
+fn latest_price(price_account: &AccountInfo, publisher: &Pubkey) -> i64 {
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    price_data
+        .comp_
+        .iter()
+        .find(|comp| comp.pub_ == *publisher)
+        .unwrap()
+        .latest_
+        .price_
+}
+
+#[test]
+fn test_set_publishers() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .security_authority = *funding_account.key;
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let set_publishers = |add: &[Pubkey], remove: &[Pubkey]| {
+        let instruction = builders::set_publishers(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            add,
+            remove,
+        );
+        process_instruction(&program_id, &accounts, &instruction.data)
+    };
+    let set_exact_publishers = |publishers: &[Pubkey]| {
+        let instruction = builders::set_exact_publishers(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            publishers,
+        );
+        process_instruction(&program_id, &accounts, &instruction.data)
+    };
+
+    let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let (a, b, c, d, e) = (keys[0], keys[1], keys[2], keys[3], keys[4]);
+
+    assert!(set_publishers(&[c, a, b], &[]).is_ok());
+    assert_eq!(publishers(&price_account), sorted(vec![a, b, c]));
+    assert_eq!(take_logged_data().len(), 3);
+
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        for comp in price_data.comp_[..3].iter_mut() {
+            comp.latest_.price_ = 42;
+        }
+    }
+
+    // Adds and removes are applied together, and the remaining publishers keep their prices
+    assert!(set_publishers(&[d], &[a]).is_ok());
+    assert_eq!(publishers(&price_account), sorted(vec![b, c, d]));
+    assert_eq!(latest_price(&price_account, &b), 42);
+    assert_eq!(latest_price(&price_account, &c), 42);
+    assert_eq!(latest_price(&price_account, &d), 0);
+    assert_eq!(
+        take_logged_data()
+            .iter()
+            .map(|fields| Event::from_bytes(&fields[0]).unwrap())
+            .collect::<Vec<_>>(),
+        vec![
+            Event::PublisherRemoved {
+                price_account: *price_account.key,
+                publisher:     a,
+            },
+            Event::PublisherAdded {
+                price_account: *price_account.key,
+                publisher:     d,
+            },
+        ]
+    );
+
+    // Nothing is applied if any of the changes is invalid
+    assert_eq!(
+        set_publishers(&[e], &[a]),
+        Err(OracleError::PublisherNotFound.into())
+    );
+    assert_eq!(
+        set_publishers(&[e, b], &[]),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(
+        set_publishers(&[e, e], &[]),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(
+        set_publishers(&[b], &[b]),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(
+        set_publishers(&[], &[c, c]),
+        Err(OracleError::DuplicatePublisher.into())
+    );
+    assert_eq!(
+        set_publishers(&[e, Pubkey::default()], &[]),
+        Err(OracleError::InvalidPublisherKey.into())
+    );
+    for sentinel in [ENABLE_ACCUMULATOR_V2, DISABLE_ACCUMULATOR_V2] {
+        assert_eq!(
+            set_publishers(&[Pubkey::from(sentinel)], &[]),
+            Err(OracleError::InvalidPublisherKey.into())
+        );
+    }
+    assert_eq!(publishers(&price_account), sorted(vec![b, c, d]));
+
+    // The exact set of publishers can be set, keeping the prices of the remaining publishers
+    take_logged_data();
+    assert!(set_exact_publishers(&[c, e, b]).is_ok());
+    assert_eq!(publishers(&price_account), sorted(vec![b, c, e]));
+    assert_eq!(latest_price(&price_account, &b), 42);
+    assert_eq!(latest_price(&price_account, &c), 42);
+    assert_eq!(take_logged_data().len(), 2);
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(
+            bytes_of(&price_data.comp_[3]),
+            bytes_of(&PriceComponent::zeroed())
+        );
+    }
+
+    // Setting the same set again is a no-op
+    assert!(set_exact_publishers(&[b, c, e]).is_ok());
+    assert!(take_logged_data().is_empty());
+
+    let too_many: Vec<Pubkey> = (0..=PC_NUM_COMP).map(|_| Pubkey::new_unique()).collect();
+    assert_eq!(
+        set_exact_publishers(&too_many),
+        Err(OracleError::TooManyPublishers.into())
+    );
+    assert!(set_exact_publishers(&too_many[..PC_NUM_COMP as usize]).is_ok());
+    assert_eq!(
+        publishers(&price_account),
+        sorted(too_many[..PC_NUM_COMP as usize].to_vec())
+    );
+    assert!(set_exact_publishers(&[]).is_ok());
+    assert!(publishers(&price_account).is_empty());
+
+    // Malformed instructions
+    let mut instruction =
+        builders::set_exact_publishers(&program_id, funding_account.key, price_account.key, &[a]);
+    instruction.data.push(0);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction.data),
+        Err(OracleError::InvalidPublisherList.into())
+    );
+    instruction.data.pop();
+    instruction.data[9] += 1;
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction.data),
+        Err(OracleError::InvalidPublisherList.into())
+    );
+    instruction.data[9] -= 1;
+    instruction.data[8] = 2;
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction.data),
+        Err(OracleError::InvalidSetPublishersMode.into())
+    );
+    // The exact mode has no publishers to remove
+    let mut instruction = builders::set_publishers(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        &[a],
+        &[b],
+    );
+    instruction.data[8] = 1;
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction.data),
+        Err(OracleError::RemoveInExactMode.into())
+    );
+
+    // Only the security authority can set publishers
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    let instruction = builders::set_publishers(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+        &[a],
+        &[],
+    );
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account,
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction.data
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+}
@@ -2,6 +2,9 @@ use {
     crate::{
         accounts::{
             AccountHeader,
+            AggregationConfig,
+            DelegationAccount,
+            Holiday,
             MappingAccount,
             PermissionAccount,
             PriceAccount,
@@ -10,6 +13,8 @@ use {
             PriceInfo,
             ProductAccount,
             PythAccount,
+            ScheduleAccount,
+            TradingSession,
         },
         c_oracle_header::{
             PC_MAP_TABLE_SIZE,
@@ -22,14 +27,22 @@ use {
             load_checked,
         },
         instruction::{
+            AddDelegateArgs,
             AddPriceArgs,
             AddPublisherArgs,
             CommandHeader,
+            DelDelegateArgs,
             DelPublisherArgs,
             InitPriceArgs,
+            RotatePublisherArgs,
+            SetAggregationConfigArgs,
             SetMaxLatencyArgs,
             SetMinPubArgs,
+            SetPriceFlagsArgs,
+            SetPublishersArgs,
+            SetScheduleArgs,
             UpdPriceArgs,
+            UpdPriceBatchEntry,
         },
         tests::test_utils::AccountSetup,
         utils::try_convert,
@@ -67,13 +80,15 @@ fn test_sizes() {
             48 + u64::BITS as usize
                 + 3 * size_of::<Pubkey>()
                 + size_of::<PriceInfo>()
-                + (PC_NUM_COMP_PYTHNET as usize) * size_of::<PriceComponent>()
+                + (PC_NUM_COMP_PYTHNET as usize - 1) * size_of::<PriceComponent>()
+                + 12 * size_of::<u64>()
                 + size_of::<PriceCumulative>()
         );
         assert_eq!(size_of::<PriceAccount>(), 12576);
         assert!(size_of::<PriceAccount>() == try_convert::<_, usize>(ZSTD_UPPER_BOUND).unwrap());
 
         assert_eq!(size_of::<PriceCumulative>(), 48);
+        assert_eq!(size_of::<AggregationConfig>(), 8);
     }
 
     assert_eq!(size_of::<CommandHeader>(), 8);
@@ -81,9 +96,17 @@ fn test_sizes() {
     assert_eq!(size_of::<InitPriceArgs>(), 16);
     assert_eq!(size_of::<SetMinPubArgs>(), 12);
     assert_eq!(size_of::<SetMaxLatencyArgs>(), 12);
+    assert_eq!(size_of::<SetPriceFlagsArgs>(), 12);
+    assert_eq!(size_of::<SetPublishersArgs>(), 12);
+    assert_eq!(size_of::<SetScheduleArgs>(), 16);
+    assert_eq!(size_of::<SetAggregationConfigArgs>(), 16);
+    assert_eq!(size_of::<RotatePublisherArgs>(), 72);
+    assert_eq!(size_of::<AddDelegateArgs>(), 48);
+    assert_eq!(size_of::<DelDelegateArgs>(), 40);
     assert_eq!(size_of::<AddPublisherArgs>(), 40);
     assert_eq!(size_of::<DelPublisherArgs>(), 40);
     assert_eq!(size_of::<UpdPriceArgs>(), 40);
+    assert_eq!(size_of::<UpdPriceBatchEntry>(), 32);
     assert_eq!(size_of::<Pubkey>(), 32);
     assert_eq!(size_of::<AccountHeader>(), 16);
     assert_eq!(size_of::<MappingAccount>(), 160056);
@@ -91,6 +114,10 @@ fn test_sizes() {
     assert_eq!(size_of::<PriceComponent>(), 96);
     assert_eq!(size_of::<PriceEma>(), 24);
     assert_eq!(size_of::<PermissionAccount>(), 112);
+    assert_eq!(size_of::<DelegationAccount>(), 376);
+    assert_eq!(size_of::<TradingSession>(), 4);
+    assert_eq!(size_of::<Holiday>(), 8);
+    assert_eq!(size_of::<ScheduleAccount>(), 376);
 }
 
 #[test]
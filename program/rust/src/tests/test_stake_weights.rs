@@ -0,0 +1,263 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            PythAccount,
+            PythOracleSerialize,
+        },
+        aggregation::{
+            self,
+            price_model_core,
+            weighted_price_model_core,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        processor::upd_aggregate,
+        tests::test_utils::AccountSetup,
+        validator::{
+            self,
+            capped_stake_weights,
+            checked_load_price_account_mut,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    pythnet_sdk::messages::{
+        PublisherStakeCap,
+        PublisherStakeCapsMessage,
+    },
+    quickcheck_macros::quickcheck,
+    solana_program::pubkey::Pubkey,
+    std::collections::BTreeMap,
+};
+
+/// Price account with a component per quote, published at slot 1. A confidence of 0 makes the
+/// quote invalid.
+fn price_account_with_quotes(quotes: &[(i64, u64)]) -> Box<PriceAccount> {
+    let mut price_account = Box::new(PriceAccount::zeroed());
+    price_account.num_ = quotes.len() as u32;
+    for (component, &(price, conf)) in price_account.comp_.iter_mut().zip(quotes) {
+        component.pub_ = Pubkey::new_unique();
+        component.latest_ = PriceInfo {
+            price_:           price,
+            conf_:            conf,
+            status_:          PC_STATUS_TRADING,
+            corp_act_status_: 0,
+            pub_slot_:        1,
+        };
+    }
+    price_account
+}
+
+/// Aggregate copies of `price_account` with the current model and with `weights`, and return
+/// whether both aggregates are the same.
+fn same_as_model(price_account: &PriceAccount, weights: &[u64]) -> bool {
+    let mut model_account = Box::new(*price_account);
+    let mut weighted_account = Box::new(*price_account);
+    let model_result =
+        unsafe { upd_aggregate(&mut *model_account as *mut PriceAccount as *mut u8, 2, 0) };
+    let weighted_result =
+        aggregation::upd_aggregate_with_weights(&mut weighted_account, 2, 0, weights);
+    model_result == weighted_result && bytes_of(&*model_account) == bytes_of(&*weighted_account)
+}
+
+/// Aggregate `price_account` at slot 2 with `weights`, returning the aggregate price and
+/// confidence if it succeeded.
+fn aggregate(price_account: &PriceAccount, weights: &[u64]) -> Option<(i64, u64)> {
+    let mut price_account = Box::new(*price_account);
+    aggregation::upd_aggregate_with_weights(&mut price_account, 2, 0, weights)
+        .then_some((price_account.agg_.price_, price_account.agg_.conf_))
+}
+
+#[quickcheck]
+fn test_equal_weights_match_model(quotes: Vec<(i16, u8)>, weight: u64) -> bool {
+    let quotes: Vec<(i64, u64)> = quotes
+        .iter()
+        .take(PC_NUM_COMP as usize)
+        .map(|&(price, conf)| (1000 + i64::from(price), u64::from(conf)))
+        .collect();
+    let price_account = price_account_with_quotes(&quotes);
+    let weight = weight.max(1);
+    same_as_model(&price_account, &vec![weight; quotes.len()])
+        // Only the weights of the components count
+        && same_as_model(&price_account, &vec![weight; PC_NUM_COMP as usize + 1])
+}
+
+#[quickcheck]
+fn test_weighted_price_model_core_equal_weights(values: Vec<i64>, weight: u64) -> bool {
+    if values.is_empty() {
+        return true;
+    }
+    let weight = weight.max(1);
+    let mut samples: Vec<(i64, u64)> = values.iter().map(|&value| (value, weight)).collect();
+    let mut values = values;
+    weighted_price_model_core(&mut samples) == price_model_core(&mut values)
+}
+
+#[test]
+fn test_degenerate_weights() {
+    let price_account = price_account_with_quotes(&[(100, 1), (101, 1), (1000, 1)]);
+    assert_eq!(aggregate(&price_account, &[]), Some((101, 898)));
+
+    // Without positive weights, the model is unweighted
+    assert!(same_as_model(&price_account, &[]));
+    assert!(same_as_model(&price_account, &[0, 0, 0]));
+    assert!(same_as_model(&price_account, &[u64::MAX; 3]));
+
+    // A single weighted quote is the aggregate
+    assert_eq!(aggregate(&price_account, &[0, 0, 7]), Some((1000, 1)));
+    assert_eq!(aggregate(&price_account, &[0, u64::MAX]), Some((101, 1)));
+
+    // Quotes without weight don't count, like in the unweighted model without them
+    let without_outlier = price_account_with_quotes(&[(100, 1), (101, 1)]);
+    assert_eq!(aggregate(&price_account, &[1, 1, 0]), Some((100, 1)));
+    assert_eq!(aggregate(&without_outlier, &[]), Some((100, 1)));
+
+    // Weights of invalid quotes are ignored
+    let with_invalid = price_account_with_quotes(&[(100, 1), (101, 1), (1000, 0)]);
+    assert_eq!(aggregate(&with_invalid, &[0, 0, 5]), Some((100, 1)));
+
+    // No valid quote
+    let invalid = price_account_with_quotes(&[(100, 0)]);
+    assert_eq!(aggregate(&invalid, &[1]), None);
+}
+
+#[test]
+fn test_capped_stake_weights() {
+    let price_account = price_account_with_quotes(&[(1000, 1), (100, 1), (101, 1)]);
+    let publishers: Vec<Pubkey> = price_account.comp_[..3]
+        .iter()
+        .map(|component| component.pub_)
+        .collect();
+    let stakes = BTreeMap::from([
+        (publishers[0], 1000),
+        (publishers[1], 10),
+        (publishers[2], 10),
+        (Pubkey::new_unique(), 5000),
+    ]);
+
+    // The cap per publisher is m / max(num_publishers, z)
+    assert_eq!(
+        capped_stake_weights(&price_account, &stakes, 30, 0),
+        vec![10, 10, 10]
+    );
+    assert_eq!(
+        capped_stake_weights(&price_account, &stakes, 30, 6),
+        vec![5, 5, 5]
+    );
+    assert_eq!(
+        capped_stake_weights(&price_account, &stakes, 6000, 3),
+        vec![1000, 10, 10]
+    );
+    assert_eq!(
+        capped_stake_weights(&price_account, &BTreeMap::new(), 30, 3),
+        vec![0, 0, 0]
+    );
+
+    // Uncapped, the largest stake dominates the aggregate
+    assert_eq!(aggregate(&price_account, &[1000, 10, 10]), Some((1000, 1)));
+
+    // Capped, it doesn't
+    let program_id = Pubkey::new_unique();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_info = price_setup.as_account_info();
+    {
+        let mut price_data = PriceAccount::initialize(&price_account_info, PC_VERSION).unwrap();
+        price_data.num_ = price_account.num_;
+        price_data.comp_ = price_account.comp_;
+        price_data.flags =
+            PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+    }
+    let mut data = price_account_info.data.borrow_mut();
+    let price_data = checked_load_price_account_mut(*data).unwrap();
+    let weights = capped_stake_weights(price_data, &stakes, 30, 3);
+    validator::aggregate_price_with_weights(
+        2,
+        102,
+        price_account_info.key,
+        price_data,
+        None,
+        Some(&weights),
+    )
+    .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 101);
+    assert_eq!(price_data.agg_.conf_, 898);
+}
+
+#[test]
+fn test_capped_stake_weights_per_feed() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let other_publisher = Pubkey::new_unique();
+
+    let mut price_setup_1 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_1 = price_setup_1.as_account_info();
+    {
+        let mut price_data = PriceAccount::initialize(&price_account_1, PC_VERSION).unwrap();
+        price_data.num_ = 2;
+        price_data.comp_[0].pub_ = publisher;
+        price_data.comp_[1].pub_ = other_publisher;
+    }
+    let mut price_setup_2 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_2 = price_setup_2.as_account_info();
+    {
+        let mut price_data = PriceAccount::initialize(&price_account_2, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = publisher;
+    }
+
+    // Across price accounts, the cap of a publisher is the sum of its caps in each of them
+    let mut caps = vec![
+        PublisherStakeCap {
+            publisher: publisher.to_bytes(),
+            cap:       15 + 30,
+        },
+        PublisherStakeCap {
+            publisher: other_publisher.to_bytes(),
+            cap:       15,
+        },
+    ];
+    caps.sort_by_key(|cap| cap.publisher);
+    {
+        let data_1 = price_account_1.data.borrow();
+        let data_2 = price_account_2.data.borrow();
+        assert_eq!(
+            validator::compute_publisher_stake_caps([&data_1[..], &data_2[..]], 0, 30, 0),
+            PublisherStakeCapsMessage {
+                publish_time: 0,
+                caps:         caps.into(),
+            }
+            .to_bytes()
+        );
+    }
+
+    // Within a price account, the weights are capped at the cap in this price account only
+    let stakes = BTreeMap::from([(publisher, 100), (other_publisher, 100)]);
+    assert_eq!(
+        capped_stake_weights(
+            &load_checked::<PriceAccount>(&price_account_1, PC_VERSION).unwrap(),
+            &stakes,
+            30,
+            0
+        ),
+        vec![15, 15]
+    );
+    assert_eq!(
+        capped_stake_weights(
+            &load_checked::<PriceAccount>(&price_account_2, PC_VERSION).unwrap(),
+            &stakes,
+            30,
+            0
+        ),
+        vec![30]
+    );
+}
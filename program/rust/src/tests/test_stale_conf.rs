@@ -0,0 +1,107 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceInfo,
+        },
+        aggregation::widen_conf,
+        c_oracle_header::{
+            PC_CORP_ACT_NONE,
+            PC_STATUS_TRADING,
+        },
+        processor::upd_aggregate,
+    },
+    bytemuck::Zeroable,
+};
+
+/// A quote published at `pub_slot`
+fn quote(price: i64, conf: u64, pub_slot: u64) -> PriceInfo {
+    PriceInfo {
+        price_:           price,
+        conf_:            conf,
+        status_:          PC_STATUS_TRADING,
+        corp_act_status_: PC_CORP_ACT_NONE,
+        pub_slot_:        pub_slot,
+    }
+}
+
+/// Aggregate `quotes` at slot 100, widening the confidences of stale quotes if
+/// `widen_stale_conf` is 1. Returns whether the aggregation succeeded and the price account.
+fn aggregate(quotes: &[PriceInfo], widen_stale_conf: u8) -> (bool, Box<PriceAccount>) {
+    let mut price_account = Box::new(PriceAccount::zeroed());
+    price_account.num_ = quotes.len() as u32;
+    price_account.price_cumulative.aggregation.widen_stale_conf = widen_stale_conf;
+    for (component, quote) in price_account.comp_.iter_mut().zip(quotes) {
+        component.latest_ = *quote;
+    }
+    let aggregated =
+        unsafe { upd_aggregate(&mut *price_account as *mut PriceAccount as *mut u8, 100, 0) };
+    (aggregated, price_account)
+}
+
+#[test]
+fn test_widen_conf() {
+    assert_eq!(widen_conf(100, 0), 100);
+    assert_eq!(widen_conf(100, 1), 100);
+    assert_eq!(widen_conf(100, 2), 141);
+    assert_eq!(widen_conf(100, 4), 200);
+    assert_eq!(widen_conf(100, 25), 500);
+    assert_eq!(widen_conf(1, 3), 1);
+    assert_eq!(widen_conf(0, 9), 0);
+
+    // Quotes from the future aren't narrowed, and the widening stops at PC_MAX_SEND_LATENCY
+    assert_eq!(widen_conf(100, -5), 100);
+    assert_eq!(widen_conf(100, 26), 500);
+    assert_eq!(widen_conf(100, i64::MAX), 500);
+
+    assert_eq!(widen_conf(u64::MAX / 5, 25), u64::MAX / 5 * 5);
+    assert_eq!(widen_conf(u64::MAX / 5 + 1, 25), u64::MAX);
+}
+
+#[test]
+fn test_stale_conf_widening() {
+    // 1, 4 and 16 slots old
+    let quotes = [quote(100, 10, 99), quote(110, 10, 96), quote(120, 10, 84)];
+
+    // Samples 90, 100, 110, 100, 110, 120, 110, 120 and 130
+    let (aggregated, price_account) = aggregate(&quotes, 0);
+    assert!(aggregated);
+    assert_eq!(price_account.agg_.price_, 110);
+    assert_eq!(price_account.agg_.conf_, 10);
+    for (component, quote) in price_account.comp_.iter().zip(&quotes) {
+        assert_eq!(component.agg_, *quote);
+    }
+
+    // Samples 90, 100, 110, 90, 110, 130, 80, 120 and 160
+    let (aggregated, price_account) = aggregate(&quotes, 1);
+    assert!(aggregated);
+    assert_eq!(price_account.num_qt_, 3);
+    assert_eq!(price_account.agg_.price_, 110);
+    assert_eq!(price_account.agg_.conf_, 20);
+    for (component, (quote, conf)) in price_account
+        .comp_
+        .iter()
+        .zip(quotes.iter().zip([10, 20, 40]))
+    {
+        assert_eq!(component.agg_.conf_, conf);
+        assert_eq!(component.agg_.price_, quote.price_);
+        assert_eq!(component.latest_, *quote);
+    }
+
+    // A quote whose widened confidence overflows is invalid, and so are quotes older than the
+    // maximum latency
+    let quotes = [
+        quote(100, 10, 99),
+        quote(0, i64::MAX as u64, 96),
+        quote(110, 10, 74),
+    ];
+    let (aggregated, price_account) = aggregate(&quotes, 0);
+    assert!(aggregated);
+    assert_eq!(price_account.num_qt_, 2);
+    let (aggregated, price_account) = aggregate(&quotes, 1);
+    assert!(aggregated);
+    assert_eq!(price_account.num_qt_, 1);
+    assert_eq!(price_account.comp_[1].agg_.conf_, u64::MAX);
+    assert_eq!(price_account.agg_.price_, 100);
+    assert_eq!(price_account.agg_.conf_, 10);
+}
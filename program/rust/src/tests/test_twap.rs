@@ -2,6 +2,7 @@ use {
     super::test_utils::AccountSetup,
     crate::{
         accounts::{
+            AggregationConfig,
             PriceAccount,
             PriceCumulative,
             PriceInfo,
@@ -13,6 +14,7 @@ use {
         },
         deserialize::load_account_as_mut,
     },
+    bytemuck::Zeroable,
     quickcheck::Arbitrary,
     quickcheck_macros::quickcheck,
     solana_program::pubkey::Pubkey,
@@ -52,7 +54,7 @@ fn test_twap(input: Vec<DataEvent>) -> bool {
         price:          0,
         conf:           0,
         num_down_slots: 0,
-        unused:         0,
+        aggregation:    AggregationConfig::zeroed(),
     };
 
     let mut data = Vec::<DataEvent>::new();
@@ -68,7 +70,7 @@ fn test_twap(input: Vec<DataEvent>) -> bool {
         price_cumulative.check_price(data.as_slice());
         price_cumulative.check_conf(data.as_slice());
         price_cumulative.check_num_down_slots(data.as_slice());
-        price_cumulative.check_unused();
+        price_cumulative.check_aggregation();
     }
 
     true
@@ -108,8 +110,8 @@ impl PriceCumulative {
             self.num_down_slots
         );
     }
-    pub fn check_unused(&self) {
-        assert_eq!(self.unused, 0);
+    pub fn check_aggregation(&self) {
+        assert_eq!(self.aggregation, AggregationConfig::zeroed());
     }
 }
 
@@ -119,7 +121,7 @@ fn test_twap_unit() {
         price:          1,
         conf:           2,
         num_down_slots: 3,
-        unused:         0,
+        aggregation:    AggregationConfig::zeroed(),
     };
 
     let data = vec![
@@ -164,7 +166,7 @@ fn test_twap_unit() {
     assert_eq!(price_cumulative.price, 5);
     assert_eq!(price_cumulative.conf, 10);
     assert_eq!(price_cumulative.num_down_slots, 3);
-    assert_eq!(price_cumulative.unused, 0);
+    assert_eq!(price_cumulative.aggregation, AggregationConfig::zeroed());
 
     price_cumulative.update(
         data[1].price,
@@ -175,7 +177,7 @@ fn test_twap_unit() {
     assert_eq!(price_cumulative.price, 9_223_372_036_854_775_812i128);
     assert_eq!(price_cumulative.conf, 18_446_744_073_709_551_625u128);
     assert_eq!(price_cumulative.num_down_slots, 3);
-    assert_eq!(price_cumulative.unused, 0);
+    assert_eq!(price_cumulative.aggregation, AggregationConfig::zeroed());
 
     price_cumulative.update(
         data[2].price,
@@ -186,13 +188,13 @@ fn test_twap_unit() {
     assert_eq!(price_cumulative.price, 9_223_372_036_854_775_512i128);
     assert_eq!(price_cumulative.conf, 18_446_744_073_709_551_745u128);
     assert_eq!(price_cumulative.num_down_slots, 8);
-    assert_eq!(price_cumulative.unused, 0);
+    assert_eq!(price_cumulative.aggregation, AggregationConfig::zeroed());
 
     let mut price_cumulative_overflow = PriceCumulative {
         price:          0,
         conf:           0,
         num_down_slots: 0,
-        unused:         0,
+        aggregation:    AggregationConfig::zeroed(),
     };
     price_cumulative_overflow.update(i64::MIN, u64::MAX, u64::MAX, u8::MAX);
     assert_eq!(
@@ -207,13 +209,16 @@ fn test_twap_unit() {
         price_cumulative_overflow.num_down_slots,
         u64::MAX - u64::from(u8::MAX)
     );
-    assert_eq!(price_cumulative_overflow.unused, 0);
+    assert_eq!(
+        price_cumulative_overflow.aggregation,
+        AggregationConfig::zeroed()
+    );
 
     let mut price_cumulative_nonzero_max_latency = PriceCumulative {
         price:          1,
         conf:           2,
         num_down_slots: 3,
-        unused:         0,
+        aggregation:    AggregationConfig::zeroed(),
     };
 
     price_cumulative_nonzero_max_latency.update(
@@ -225,7 +230,10 @@ fn test_twap_unit() {
     assert_eq!(price_cumulative_nonzero_max_latency.price, 5);
     assert_eq!(price_cumulative_nonzero_max_latency.conf, 10);
     assert_eq!(price_cumulative_nonzero_max_latency.num_down_slots, 3);
-    assert_eq!(price_cumulative_nonzero_max_latency.unused, 0);
+    assert_eq!(
+        price_cumulative_nonzero_max_latency.aggregation,
+        AggregationConfig::zeroed()
+    );
 
     price_cumulative_nonzero_max_latency.update(
         data[4].price,
@@ -236,7 +244,10 @@ fn test_twap_unit() {
     assert_eq!(price_cumulative_nonzero_max_latency.price, 53);
     assert_eq!(price_cumulative_nonzero_max_latency.conf, 66);
     assert_eq!(price_cumulative_nonzero_max_latency.num_down_slots, 6);
-    assert_eq!(price_cumulative_nonzero_max_latency.unused, 0);
+    assert_eq!(
+        price_cumulative_nonzero_max_latency.aggregation,
+        AggregationConfig::zeroed()
+    );
 }
 
 #[test]
@@ -258,7 +269,7 @@ fn test_twap_with_price_account() {
         price:          1,
         conf:           2,
         num_down_slots: 3,
-        unused:         0,
+        aggregation:    AggregationConfig::zeroed(),
     };
     price_data.prev_slot_ = 3;
     price_data.update_price_cumulative();
@@ -371,5 +371,5 @@ fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = pub_slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 }
@@ -14,6 +14,7 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
@@ -24,10 +25,7 @@ use {
             AccountSetup,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -95,7 +93,7 @@ fn test_upd_price() {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     {
@@ -202,7 +200,22 @@ fn test_upd_price() {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
+    );
+
+    // try to publish in the future
+    populate_instruction(&mut instruction_data, 81, 2, 6);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                clock_account.clone()
+            ],
+            &instruction_data
+        ),
+        Err(OracleError::FuturePublishingSlot.into())
     );
 
     {
@@ -341,5 +354,5 @@ fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = pub_slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 }
@@ -0,0 +1,324 @@
+use {
+    crate::{
+        accounts::{
+            DelegationAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            CommandHeader,
+            ComponentUpdate,
+            OracleCommand,
+            UpdPriceBatchEntry,
+            UpdPriceReturnData,
+            MAX_UPD_PRICE_BATCH_SIZE,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            take_return_data,
+            update_clock_slot,
+            AccountSetup,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        cast_slice,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+fn entry(price: i64, publishing_slot: u64) -> UpdPriceBatchEntry {
+    UpdPriceBatchEntry {
+        status: PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price,
+        confidence: 1,
+        publishing_slot,
+    }
+}
+
+fn batch_data(entries: &[UpdPriceBatchEntry]) -> Vec<u8> {
+    let header: CommandHeader = OracleCommand::UpdPriceBatch.into();
+    let mut data = bytes_of(&header).to_vec();
+    data.extend_from_slice(cast_slice(entries));
+    data
+}
+
+fn take_batch_return_data() -> Vec<UpdPriceReturnData> {
+    take_return_data()
+        .chunks(UpdPriceReturnData::LEN)
+        .map(|bytes| UpdPriceReturnData::from_bytes(bytes).unwrap())
+        .collect()
+}
+
+fn new_price_account<'a>(setup: &'a mut AccountSetup, publishers: &[Pubkey]) -> AccountInfo<'a> {
+    let mut price_account = setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = publishers.len() as u32;
+        for (component, publisher) in price_data.comp_.iter_mut().zip(publishers) {
+            component.pub_ = *publisher;
+        }
+    }
+    price_account
+}
+
+fn latest_price(price_account: &AccountInfo, index: usize) -> (i64, u64) {
+    let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    (
+        price_data.comp_[index].latest_.price_,
+        price_data.comp_[index].latest_.pub_slot_,
+    )
+}
+
+#[test]
+fn test_upd_price_batch() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let publisher = *funding_account.key;
+
+    let mut price_setup_1 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_1 = new_price_account(&mut price_setup_1, &[publisher]);
+    // The publisher isn't a publisher of this price account
+    let mut price_setup_2 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_2 = new_price_account(&mut price_setup_2, &[Pubkey::new_unique()]);
+    let mut price_setup_3 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_3 = new_price_account(&mut price_setup_3, &[Pubkey::new_unique(), publisher]);
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    let accounts = [
+        funding_account.clone(),
+        clock_account.clone(),
+        price_account_1.clone(),
+        price_account_2.clone(),
+        price_account_3.clone(),
+    ];
+
+    // A failing price doesn't prevent the other prices from being published
+    assert!(process_instruction(
+        &program_id,
+        &accounts,
+        &batch_data(&[entry(10, 1), entry(20, 1), entry(30, 1)])
+    )
+    .is_ok());
+    let return_data = take_batch_return_data();
+    assert_eq!(return_data.len(), 3);
+    assert_eq!(return_data[0].component, ComponentUpdate::Accepted);
+    assert!(return_data[0].aggregate_triggered);
+    assert_eq!(return_data[0].error, None);
+    assert_eq!(
+        return_data[1],
+        UpdPriceReturnData::from_error(OracleError::PermissionViolation.into())
+    );
+    assert_eq!(return_data[2].component, ComponentUpdate::Accepted);
+    assert_eq!(latest_price(&price_account_1, 0), (10, 1));
+    assert_eq!(latest_price(&price_account_2, 0), (0, 0));
+    assert_eq!(latest_price(&price_account_3, 1), (30, 1));
+
+    // Same checks as `UpdPrice`: the publishing slot must increase
+    update_clock_slot(&mut clock_account, 2);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            clock_account.clone(),
+            price_account_1.clone(),
+            price_account_3.clone(),
+        ],
+        &batch_data(&[entry(11, 1), entry(31, 2)])
+    )
+    .is_ok());
+    let return_data = take_batch_return_data();
+    assert_eq!(
+        return_data[0],
+        UpdPriceReturnData::from_error(OracleError::StalePublishingSlot.into())
+    );
+    assert_eq!(return_data[1].component, ComponentUpdate::Accepted);
+    assert!(return_data[1].aggregate_triggered);
+    assert_eq!(return_data[1].agg_price, 30);
+    assert_eq!(latest_price(&price_account_1, 0), (10, 1));
+    assert_eq!(latest_price(&price_account_3, 1), (31, 2));
+
+    // The batch must have one entry per price account
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &batch_data(&[entry(12, 2)])),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+    assert_eq!(
+        process_instruction(&program_id, &accounts[..2], &batch_data(&[])),
+        Err(OracleError::InvalidBatchSize.into())
+    );
+    let mut data = batch_data(&[entry(12, 2), entry(22, 2), entry(32, 3)]);
+    data.push(0);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidBatchSize.into())
+    );
+    let entries = vec![entry(12, 2); MAX_UPD_PRICE_BATCH_SIZE + 1];
+    let too_many_accounts = vec![price_account_1.clone(); MAX_UPD_PRICE_BATCH_SIZE + 1];
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[&accounts[..2], &too_many_accounts[..]].concat(),
+            &batch_data(&entries)
+        ),
+        Err(OracleError::InvalidBatchSize.into())
+    );
+
+    // The funding account must sign
+    let mut unsigned_funding_account = funding_account.clone();
+    unsigned_funding_account.is_signer = false;
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                unsigned_funding_account,
+                clock_account.clone(),
+                price_account_1.clone(),
+            ],
+            &batch_data(&[entry(12, 2)])
+        ),
+        Err(OracleError::InvalidFundingAccount.into())
+    );
+}
+
+#[test]
+fn test_upd_price_batch_scheduled() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let publisher = *funding_account.key;
+
+    let mut price_setup_1 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_1 = new_price_account(&mut price_setup_1, &[publisher]);
+    let mut price_setup_2 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_2 = new_price_account(&mut price_setup_2, &[publisher]);
+    load_checked::<PriceAccount>(&price_account_2, PC_VERSION)
+        .unwrap()
+        .flags = PriceAccountFlags::SCHEDULED;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    // The batch has no schedule accounts, so scheduled prices fail
+    assert!(process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            clock_account.clone(),
+            price_account_1.clone(),
+            price_account_2.clone(),
+        ],
+        &batch_data(&[entry(10, 1), entry(20, 1)])
+    )
+    .is_ok());
+    let return_data = take_batch_return_data();
+    assert_eq!(return_data[0].component, ComponentUpdate::Accepted);
+    assert_eq!(
+        return_data[1],
+        UpdPriceReturnData::from_error(OracleError::ScheduleAccountRequired.into())
+    );
+    assert_eq!(latest_price(&price_account_1, 0), (10, 1));
+    assert_eq!(latest_price(&price_account_2, 0), (0, 0));
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account_2, PC_VERSION)
+            .unwrap()
+            .agg_
+            .pub_slot_,
+        0
+    );
+}
+
+#[test]
+fn test_upd_price_batch_as_delegate() {
+    let program_id = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+
+    let mut delegate_setup = AccountSetup::new_funding();
+    let delegate_account = delegate_setup.as_account_info();
+
+    let mut price_setup_1 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_1 = new_price_account(&mut price_setup_1, &[publisher]);
+    let mut price_setup_2 = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account_2 = new_price_account(&mut price_setup_2, &[publisher]);
+
+    let mut delegation_setup = AccountSetup::new_delegation(&program_id, &publisher);
+    let mut delegation_account = delegation_setup.as_account_info();
+    delegation_account.is_signer = false;
+    delegation_account.is_writable = false;
+    {
+        let mut delegation_data =
+            DelegationAccount::initialize(&delegation_account, PC_VERSION).unwrap();
+        delegation_data.publisher = publisher;
+        delegation_data.num_delegates = 1;
+        delegation_data.delegates[0].key = *delegate_account.key;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    assert!(process_instruction(
+        &program_id,
+        &[
+            delegate_account.clone(),
+            clock_account.clone(),
+            delegation_account.clone(),
+            price_account_1.clone(),
+            price_account_2.clone(),
+        ],
+        &batch_data(&[entry(10, 1), entry(20, 1)])
+    )
+    .is_ok());
+    let return_data = take_batch_return_data();
+    assert!(return_data
+        .iter()
+        .all(|price_return_data| price_return_data.component == ComponentUpdate::Accepted));
+    assert_eq!(latest_price(&price_account_1, 0), (10, 1));
+    assert_eq!(latest_price(&price_account_2, 0), (20, 1));
+
+    // Without its delegation account, the delegate isn't a publisher
+    update_clock_slot(&mut clock_account, 2);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            delegate_account.clone(),
+            clock_account.clone(),
+            price_account_1.clone(),
+        ],
+        &batch_data(&[entry(11, 2)])
+    )
+    .is_ok());
+    assert_eq!(
+        take_batch_return_data(),
+        vec![UpdPriceReturnData::from_error(
+            OracleError::PermissionViolation.into()
+        )]
+    );
+    assert_eq!(latest_price(&price_account_1, 0), (10, 1));
+}
@@ -0,0 +1,309 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceInfo,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_CORP_ACT_ALL,
+            PC_CORP_ACT_NONE,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            compact::{
+                read_varint,
+                write_varint,
+                zigzag_decode,
+                zigzag_encode,
+                CompactUpdPrice,
+            },
+            CommandHeader,
+            OracleCommand,
+            UpdPriceArgs,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            take_logged_data,
+            take_return_data,
+            update_clock_slot,
+            AccountSetup,
+        },
+    },
+    bytemuck::bytes_of,
+    quickcheck_macros::quickcheck,
+    solana_program::pubkey::Pubkey,
+};
+
+fn compact_data(update: &CompactUpdPrice) -> Vec<u8> {
+    let header: CommandHeader = OracleCommand::UpdPriceCompact.into();
+    let mut data = bytes_of(&header).to_vec();
+    data.extend_from_slice(&update.to_bytes());
+    data
+}
+
+#[quickcheck]
+fn test_varint_roundtrip(values: Vec<u64>) -> bool {
+    let mut bytes = vec![];
+    for value in &values {
+        write_varint(&mut bytes, *value);
+    }
+
+    let mut remaining = &bytes[..];
+    for value in &values {
+        assert_eq!(read_varint(&mut remaining), Ok(*value));
+    }
+    assert!(remaining.is_empty());
+
+    // A truncated varint is rejected
+    if let Some(value) = values.last() {
+        let mut last = vec![];
+        write_varint(&mut last, *value);
+        assert_eq!(
+            read_varint(&mut &last[..last.len() - 1]),
+            Err(OracleError::InvalidCompactUpdate)
+        );
+    }
+    true
+}
+
+#[quickcheck]
+fn test_zigzag_roundtrip(value: i64) -> bool {
+    // Small deltas get small encodings, whatever their sign
+    zigzag_decode(zigzag_encode(value)) == value
+        && zigzag_encode(value) <= value.unsigned_abs().saturating_mul(2)
+}
+
+#[quickcheck]
+fn test_compact_upd_price_roundtrip(
+    latest: PriceInfo,
+    clock_slot: u64,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    slot_lag: u64,
+) -> bool {
+    let publishing_slot = clock_slot.saturating_sub(slot_lag);
+    let update = CompactUpdPrice::new(
+        &latest,
+        clock_slot,
+        status,
+        price,
+        confidence,
+        publishing_slot,
+    )
+    .unwrap();
+    assert_eq!(CompactUpdPrice::from_bytes(&update.to_bytes()), Ok(update));
+
+    let cmd_args = update
+        .to_upd_price_args(PC_VERSION, &latest, clock_slot)
+        .unwrap();
+    cmd_args.header.command == OracleCommand::UpdPrice as i32
+        && cmd_args.status == status
+        && cmd_args.corp_act_status == PC_CORP_ACT_NONE
+        && cmd_args.price == price
+        && cmd_args.confidence == confidence
+        && cmd_args.publishing_slot == publishing_slot
+}
+
+/// `UpdPriceCompact` performs exactly the same state transition as the equivalent `UpdPrice`,
+/// including when the update is rejected.
+#[quickcheck]
+fn test_upd_price_compact_matches_upd_price(
+    latest: PriceInfo,
+    last_aggregate_slot: u8,
+    clock_slot: u8,
+    price: i64,
+    confidence: u64,
+    slot_lag: u8,
+) -> bool {
+    // `UpdPrice` can't take the absolute value of this price, for either encoding
+    if price == i64::MIN {
+        return true;
+    }
+    let program_id = Pubkey::new_unique();
+    // Keep the latest publishing slot close to the clock, so that most updates are accepted
+    let mut latest = latest;
+    latest.pub_slot_ %= 256;
+    latest.corp_act_status_ &= PC_CORP_ACT_ALL;
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    let clock_slot = u64::from(clock_slot);
+    update_clock_slot(&mut clock_account, clock_slot);
+
+    let mut price_setups = [
+        AccountSetup::new::<PriceAccount>(&program_id),
+        AccountSetup::new::<PriceAccount>(&program_id),
+    ];
+    let price_accounts: Vec<_> = price_setups
+        .iter_mut()
+        .map(|setup| {
+            let mut price_account = setup.as_account_info();
+            price_account.is_signer = false;
+            let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+            price_data.num_ = 1;
+            price_data.comp_[0].pub_ = *funding_account.key;
+            price_data.comp_[0].latest_ = latest;
+            price_data.agg_.pub_slot_ = u64::from(last_aggregate_slot);
+            drop(price_data);
+            price_account
+        })
+        .collect();
+
+    let publishing_slot = clock_slot.saturating_sub(u64::from(slot_lag));
+    let upd_price = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status: PC_STATUS_TRADING,
+        // Compact updates report no corporate action, whatever the latest price reported
+        corp_act_status: PC_CORP_ACT_NONE,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    let update = CompactUpdPrice::new(
+        &latest,
+        clock_slot,
+        PC_STATUS_TRADING,
+        price,
+        confidence,
+        publishing_slot,
+    )
+    .unwrap();
+
+    let result = process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            price_accounts[0].clone(),
+            clock_account.clone(),
+        ],
+        bytes_of(&upd_price),
+    );
+    let return_data = take_return_data();
+    let compact_result = process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            price_accounts[1].clone(),
+            clock_account.clone(),
+        ],
+        &compact_data(&update),
+    );
+    let compact_return_data = take_return_data();
+    take_logged_data();
+
+    assert_eq!(result, compact_result);
+    assert_eq!(return_data, compact_return_data);
+    let price_data = load_checked::<PriceAccount>(&price_accounts[0], PC_VERSION).unwrap();
+    let compact_price_data = load_checked::<PriceAccount>(&price_accounts[1], PC_VERSION).unwrap();
+    bytes_of(&*price_data) == bytes_of(&*compact_price_data)
+}
+
+#[test]
+fn test_upd_price_compact() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+        price_data.comp_[0].latest_.price_ = 1_000_000;
+        price_data.comp_[0].latest_.conf_ = 500;
+        price_data.comp_[0].latest_.pub_slot_ = 99;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 101);
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+
+    // A small move fits in 4 bytes instead of 32
+    let latest = load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .comp_[0]
+        .latest_;
+    let update =
+        CompactUpdPrice::new(&latest, 101, PC_STATUS_TRADING, 1_000_003, 499, 100).unwrap();
+    assert_eq!(update.to_bytes(), vec![1, 6, 1, 1]);
+    assert!(process_instruction(&program_id, &accounts, &compact_data(&update)).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 1_000_003);
+        assert_eq!(price_data.comp_[0].latest_.conf_, 499);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 100);
+        assert_eq!(price_data.comp_[0].latest_.status_, PC_STATUS_TRADING);
+    }
+
+    // The publishing slot can't be before slot 0
+    let mut data = compact_data(&CompactUpdPrice {
+        status:      PC_STATUS_TRADING,
+        price_delta: 0,
+        conf_delta:  0,
+        slot_lag:    102,
+    });
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidCompactUpdate.into())
+    );
+
+    // Malformed encodings are rejected: trailing bytes, a missing varint, a non-canonical varint
+    // and a status that doesn't fit in a u32
+    data.truncate(data.len() - 1);
+    data.extend_from_slice(&[1, 0]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidCompactUpdate.into())
+    );
+    data.truncate(data.len() - 2);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidCompactUpdate.into())
+    );
+    data.extend_from_slice(&[0x81, 0x00]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidCompactUpdate.into())
+    );
+    let mut data = bytes_of(&CommandHeader::from(OracleCommand::UpdPriceCompact)).to_vec();
+    write_varint(&mut data, u64::from(u32::MAX) + 1);
+    data.extend_from_slice(&[0, 0, 1]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &data),
+        Err(OracleError::InvalidCompactUpdate.into())
+    );
+
+    // Only the publishers of the price account can publish
+    let mut other_setup = AccountSetup::new_funding();
+    let other_account = other_setup.as_account_info();
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[other_account, price_account.clone(), clock_account.clone()],
+            &compact_data(&update)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+}
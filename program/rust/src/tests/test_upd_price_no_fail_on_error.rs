@@ -24,10 +24,7 @@ use {
             AccountSetup,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -141,7 +138,7 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     populate_instruction(&mut instruction_data, 55, 22, 1, false);
@@ -188,5 +185,5 @@ fn populate_instruction(
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = pub_slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 }
@@ -0,0 +1,296 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::{
+            load_checked,
+            load_mut,
+        },
+        error::OracleError,
+        instruction::{
+            builders,
+            ComponentUpdate,
+            OracleCommand,
+            UpdPriceArgs,
+            UpdPriceReturnData,
+        },
+        processor::process_instruction,
+        tests::{
+            pyth_simulator::PythSimulator,
+            test_utils::{
+                take_return_data,
+                update_clock_slot,
+                AccountSetup,
+            },
+        },
+    },
+    solana_program::{
+        instruction::InstructionError,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::{
+            Keypair,
+            Signer,
+        },
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_upd_price_return_data() {
+    let mut instruction_data = [0u8; size_of::<UpdPriceArgs>()];
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        clock_account.clone(),
+    ];
+
+    // The first update of the slot triggers an aggregation, which fails without valid quotes
+    populate_instruction(&mut instruction_data, OracleCommand::UpdPrice, 42, 2, 1);
+    update_clock_slot(&mut clock_account, 1);
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&take_return_data()),
+        Ok(UpdPriceReturnData {
+            component:           ComponentUpdate::Accepted,
+            aggregate_triggered: true,
+            message_sent:        false,
+            agg_status:          PC_STATUS_UNKNOWN,
+            agg_price:           0,
+            num_qt:              0,
+            error:               None,
+        })
+    );
+
+    populate_instruction(&mut instruction_data, OracleCommand::UpdPrice, 81, 2, 2);
+    update_clock_slot(&mut clock_account, 3);
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&take_return_data()),
+        Ok(UpdPriceReturnData {
+            component:           ComponentUpdate::Accepted,
+            aggregate_triggered: true,
+            message_sent:        false,
+            agg_status:          PC_STATUS_TRADING,
+            agg_price:           42,
+            num_qt:              1,
+            error:               None,
+        })
+    );
+
+    // A wide confidence interval is ignored, and the slot has already been aggregated
+    populate_instruction(&mut instruction_data, OracleCommand::UpdPrice, 50, 20, 3);
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&take_return_data()),
+        Ok(UpdPriceReturnData {
+            component:           ComponentUpdate::Ignored,
+            aggregate_triggered: false,
+            message_sent:        false,
+            agg_status:          PC_STATUS_TRADING,
+            agg_price:           42,
+            num_qt:              1,
+            error:               None,
+        })
+    );
+
+    // Failed updates don't set return data
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data),
+        Err(OracleError::StalePublishingSlot.into())
+    );
+    assert!(take_return_data().is_empty());
+
+    // unless the error is swallowed
+    populate_instruction(
+        &mut instruction_data,
+        OracleCommand::UpdPriceNoFailOnError,
+        50,
+        20,
+        3,
+    );
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&take_return_data()),
+        Ok(UpdPriceReturnData::from_error(
+            OracleError::StalePublishingSlot.into()
+        ))
+    );
+}
+
+#[test]
+fn test_upd_price_return_data_encoding() {
+    let return_data = UpdPriceReturnData {
+        component:           ComponentUpdate::Ignored,
+        aggregate_triggered: true,
+        message_sent:        true,
+        agg_status:          PC_STATUS_TRADING,
+        agg_price:           -5,
+        num_qt:              7,
+        error:               None,
+    };
+    let bytes = return_data.to_bytes();
+    assert_eq!(
+        bytes,
+        [
+            1, 2, 1, 1, 1, 0, 0, 0, 251, 255, 255, 255, 255, 255, 255, 255, 7, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0
+        ]
+    );
+    assert_eq!(UpdPriceReturnData::from_bytes(&bytes), Ok(return_data));
+
+    for error in [
+        ProgramError::Custom(0),
+        ProgramError::InvalidArgument,
+        OracleError::PermissionViolation.into(),
+    ] {
+        let return_data = UpdPriceReturnData::from_error(error);
+        assert_eq!(
+            UpdPriceReturnData::from_bytes(&return_data.to_bytes()),
+            Ok(return_data)
+        );
+    }
+
+    // Unknown versions and values are rejected
+    for (index, value) in [(0, 2), (1, 3), (2, 2), (3, 2)] {
+        let mut bytes = bytes;
+        bytes[index] = value;
+        assert_eq!(
+            UpdPriceReturnData::from_bytes(&bytes),
+            Err(OracleError::DeserializationError)
+        );
+    }
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&bytes[..UpdPriceReturnData::LEN - 1]),
+        Err(OracleError::DeserializationError)
+    );
+}
+
+#[tokio::test]
+async fn test_upd_price_return_data_in_transaction() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price_account = *price_accounts.values().next().unwrap();
+    let program_id = sim.program_id();
+
+    sim.warp_to_slot(10).await.unwrap();
+    let upd_price = builders::upd_price(
+        &program_id,
+        &publisher.pubkey(),
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        10,
+        None,
+    );
+    let transaction = sim
+        .process_ixs_with_metadata(&[upd_price.clone()], &vec![&publisher], &publisher)
+        .await
+        .unwrap();
+    assert_eq!(transaction.result, Ok(()));
+    let (return_program_id, return_data) = transaction.return_data.unwrap();
+    assert_eq!(return_program_id, program_id);
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&return_data),
+        Ok(UpdPriceReturnData {
+            component:           ComponentUpdate::Accepted,
+            aggregate_triggered: true,
+            message_sent:        false,
+            agg_status:          PC_STATUS_UNKNOWN,
+            agg_price:           0,
+            num_qt:              0,
+            error:               None,
+        })
+    );
+
+    // The error of a failed update reaches the client through the transaction result
+    let transaction = sim
+        .process_ixs_with_metadata(&[upd_price], &vec![&publisher], &publisher)
+        .await
+        .unwrap();
+    assert_eq!(
+        transaction.result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::StalePublishingSlot as u32)
+        ))
+    );
+    assert_eq!(transaction.return_data, None);
+
+    // or through the return data when the error is swallowed
+    let upd_price_no_fail_on_error = builders::upd_price_no_fail_on_error(
+        &program_id,
+        &publisher.pubkey(),
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        10,
+        None,
+    );
+    let transaction = sim
+        .process_ixs_with_metadata(&[upd_price_no_fail_on_error], &vec![&publisher], &publisher)
+        .await
+        .unwrap();
+    assert_eq!(transaction.result, Ok(()));
+    let (return_program_id, return_data) = transaction.return_data.unwrap();
+    assert_eq!(return_program_id, program_id);
+    assert_eq!(
+        UpdPriceReturnData::from_bytes(&return_data),
+        Ok(UpdPriceReturnData::from_error(
+            OracleError::StalePublishingSlot.into()
+        ))
+    );
+}
+
+fn populate_instruction(
+    instruction_data: &mut [u8],
+    command: OracleCommand,
+    price: i64,
+    conf: u64,
+    pub_slot: u64,
+) {
+    let mut cmd = load_mut::<UpdPriceArgs>(instruction_data).unwrap();
+    cmd.header = command.into();
+    cmd.status = PC_STATUS_TRADING;
+    cmd.price = price;
+    cmd.confidence = conf;
+    cmd.publishing_slot = pub_slot;
+    cmd.corp_act_status = 0;
+}
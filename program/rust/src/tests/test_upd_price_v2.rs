@@ -14,6 +14,7 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
@@ -24,10 +25,7 @@ use {
             AccountSetup,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -99,7 +97,7 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     {
@@ -223,7 +221,7 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     {
@@ -533,5 +531,5 @@ fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = pub_slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 }
@@ -16,6 +16,7 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
@@ -34,10 +35,7 @@ use {
         PriceFeedMessage,
         TwapMessage,
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -112,7 +110,7 @@ fn test_upd_price_with_validator() {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     {
@@ -304,7 +302,7 @@ fn test_upd_price_with_validator() {
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StalePublishingSlot.into())
     );
 
     {
@@ -475,5 +473,5 @@ fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = pub_slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 }
@@ -15,6 +15,7 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             CommandHeader,
             OracleCommand,
@@ -22,10 +23,7 @@ use {
         processor::process_instruction,
         tests::test_utils::AccountSetup,
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -84,7 +82,7 @@ fn test_upd_product() {
             ],
             &instruction_data[..size]
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidProductMetadata.into())
     );
     assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
 
@@ -119,7 +117,7 @@ fn test_upd_product() {
             ],
             &instruction_data[..size]
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidProductMetadata.into())
     );
     assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
 }
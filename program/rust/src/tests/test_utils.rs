@@ -3,16 +3,13 @@ use {
         accounts::{
             PermissionAccount,
             PythAccount,
+            ScheduleAccount,
             PERMISSIONS_SEED,
+            SCHEDULE_SEED,
         },
-        c_oracle_header::PC_VERSION,
         error::OracleError,
-        instruction::{
-            CommandHeader,
-            OracleCommand,
-        },
+        events::Event,
     },
-    num_traits::ToPrimitive,
     solana_program::{
         account_info::AccountInfo,
         clock::{
@@ -22,6 +19,10 @@ use {
         instruction::InstructionError,
         native_token::LAMPORTS_PER_SOL,
         program_error::ProgramError,
+        program_stubs::{
+            set_syscall_stubs,
+            SyscallStubs,
+        },
         pubkey::Pubkey,
         rent::Rent,
         system_program,
@@ -31,7 +32,12 @@ use {
             SysvarId,
         },
     },
+    solana_program_test::ProgramTest,
     solana_sdk::transaction::TransactionError,
+    std::{
+        cell::RefCell,
+        sync::Once,
+    },
 };
 
 const UPPER_BOUND_OF_ALL_ACCOUNT_SIZES: usize = 160056;
@@ -99,6 +105,46 @@ impl AccountSetup {
         }
     }
 
+    #[cfg(any(feature = "batch-updates", feature = "delegation"))]
+    pub fn new_delegation(owner: &Pubkey, publisher: &Pubkey) -> Self {
+        use crate::accounts::{
+            DelegationAccount,
+            DELEGATION_SEED,
+        };
+
+        let (key, _bump) =
+            Pubkey::find_program_address(&[DELEGATION_SEED.as_bytes(), publisher.as_ref()], owner);
+        let owner = *owner;
+        let balance = Rent::minimum_balance(&Rent::default(), DelegationAccount::MINIMUM_SIZE);
+        let size = DelegationAccount::MINIMUM_SIZE;
+        let data = vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES];
+        AccountSetup {
+            key,
+            owner,
+            balance,
+            size,
+            data,
+        }
+    }
+
+    pub fn new_schedule(owner: &Pubkey, price_account: &Pubkey) -> Self {
+        let (key, _bump) = Pubkey::find_program_address(
+            &[SCHEDULE_SEED.as_bytes(), price_account.as_ref()],
+            owner,
+        );
+        let owner = *owner;
+        let balance = Rent::minimum_balance(&Rent::default(), ScheduleAccount::MINIMUM_SIZE);
+        let size = ScheduleAccount::MINIMUM_SIZE;
+        let data = vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES];
+        AccountSetup {
+            key,
+            owner,
+            balance,
+            size,
+            data,
+        }
+    }
+
     pub fn new_clock() -> Self {
         let key = clock::Clock::id();
         let owner = sysvar::id();
@@ -115,6 +161,7 @@ impl AccountSetup {
     }
 
     pub fn as_account_info(&mut self) -> AccountInfo {
+        install_syscall_stubs();
         AccountInfo::new(
             &self.key,
             true,
@@ -134,15 +181,72 @@ pub fn update_clock_slot(clock_account: &mut AccountInfo, slot: u64) {
     clock_data.to_account_info(clock_account);
 }
 
-impl From<OracleCommand> for CommandHeader {
-    fn from(val: OracleCommand) -> Self {
-        CommandHeader {
-            version: PC_VERSION,
-            command: val.to_i32().unwrap(), // This can never fail and is only used in tests
-        }
+pub fn update_clock_timestamp(clock_account: &mut AccountInfo, unix_timestamp: i64) {
+    let mut clock_data = clock::Clock::from_account_info(clock_account).unwrap();
+    clock_data.unix_timestamp = unix_timestamp;
+    clock_data.to_account_info(clock_account);
+}
+
+thread_local! {
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(vec![]) };
+    static LOGGED_DATA: RefCell<Vec<Vec<Vec<u8>>>> = const { RefCell::new(vec![]) };
+}
+
+/// Syscall stubs recording the return data and the data logged by the processors that the tests
+/// call directly, on the calling thread. The simulator tests run the BPF program, whose syscalls
+/// don't go through the stubs.
+struct RecordingSyscallStubs;
+
+impl SyscallStubs for RecordingSyscallStubs {
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|x| *x.borrow_mut() = data.to_vec())
+    }
+
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        LOGGED_DATA.with(|x| {
+            x.borrow_mut()
+                .push(data.iter().map(|field| field.to_vec()).collect())
+        })
     }
 }
 
+/// Install `RecordingSyscallStubs`. The simulator installs its own stubs, which need a running
+/// bank, the first time a `ProgramTest` starts, so one is started beforehand to keep it from
+/// replacing ours. It runs on its own thread, in case the caller is already in a runtime.
+fn install_syscall_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        std::thread::spawn(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(ProgramTest::default().start());
+        })
+        .join()
+        .unwrap();
+        set_syscall_stubs(Box::new(RecordingSyscallStubs));
+    });
+}
+
+/// The data set as return data by the last instruction processed on this thread
+pub fn take_return_data() -> Vec<u8> {
+    RETURN_DATA.with(|x| std::mem::take(&mut *x.borrow_mut()))
+}
+
+/// The data logged with `sol_log_data` on this thread since the last call
+pub fn take_logged_data() -> Vec<Vec<Vec<u8>>> {
+    LOGGED_DATA.with(|x| std::mem::take(&mut *x.borrow_mut()))
+}
+
+/// The events logged since the last call to `take_logged_data`
+pub fn take_events() -> Vec<Event> {
+    take_logged_data()
+        .into_iter()
+        .map(|fields| Event::from_bytes(&fields[0]).unwrap())
+        .collect()
+}
+
 impl From<OracleError> for TransactionError {
     fn from(error: OracleError) -> Self {
         TransactionError::InstructionError(
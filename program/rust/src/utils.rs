@@ -101,7 +101,7 @@ pub fn check_valid_fresh_account(account: &AccountInfo) -> Result<(), ProgramErr
 pub fn check_exponent_range(expo: i32) -> Result<(), ProgramError> {
     pyth_assert(
         (-MAX_NUM_DECIMALS..=MAX_NUM_DECIMALS).contains(&expo),
-        ProgramError::InvalidArgument,
+        OracleError::InvalidExponent.into(),
     )
 }
 
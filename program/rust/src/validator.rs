@@ -6,12 +6,17 @@ use {
             PriceAccountFlags,
             PythAccount,
             PythOracleSerialize,
+            ScheduleAccount,
+        },
+        aggregation,
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_UNKNOWN,
         },
-        c_oracle_header::PC_MAGIC,
         error::OracleError,
         processor::{
-            c_upd_aggregate,
             c_upd_twap,
+            upd_aggregate,
         },
         utils::pyth_assert,
     },
@@ -32,40 +37,55 @@ use {
 
 // Checks that the account is a PriceAccount from the length and header.
 fn check_price_account_header(price_account_info: &[u8]) -> Result<(), ProgramError> {
+    check_account_header::<PriceAccount>(price_account_info)
+}
+
+// Checks that the account is a `T` from the length and header.
+fn check_account_header<T: PythAccount>(account_info: &[u8]) -> Result<(), ProgramError> {
     pyth_assert(
-        price_account_info.len() >= PriceAccount::MINIMUM_SIZE,
+        account_info.len() >= T::MINIMUM_SIZE,
         OracleError::AccountTooSmall.into(),
     )?;
 
     let account_header =
-        bytemuck::from_bytes::<AccountHeader>(&price_account_info[0..size_of::<AccountHeader>()]);
+        bytemuck::from_bytes::<AccountHeader>(&account_info[0..size_of::<AccountHeader>()]);
 
     pyth_assert(
-        account_header.magic_number == PC_MAGIC
-            && account_header.account_type == PriceAccount::ACCOUNT_TYPE,
+        account_header.magic_number == PC_MAGIC && account_header.account_type == T::ACCOUNT_TYPE,
         OracleError::InvalidAccountHeader.into(),
     )?;
 
     Ok(())
 }
 
-fn update_aggregate(slot: u64, timestamp: i64, price_account: &mut PriceAccount) {
-    // NOTE: c_upd_aggregate must use a raw pointer to price data. We already
-    // have the exclusive mut reference so we can simply cast before calling
-    // the function.
-    let updated = unsafe {
-        c_upd_aggregate(
-            price_account as *mut PriceAccount as *mut u8,
-            slot,
-            timestamp,
-        )
+fn update_aggregate(
+    slot: u64,
+    timestamp: i64,
+    price_account: &mut PriceAccount,
+    weights: Option<&[u64]>,
+) {
+    let updated = match weights {
+        // The C code doesn't take weights
+        Some(weights) => {
+            aggregation::upd_aggregate_with_weights(price_account, slot, timestamp, weights)
+        }
+        // NOTE: upd_aggregate must use a raw pointer to price data. We already
+        // have the exclusive mut reference so we can simply cast before calling
+        // the function.
+        None => unsafe {
+            upd_aggregate(
+                price_account as *mut PriceAccount as *mut u8,
+                slot,
+                timestamp,
+            )
+        },
     };
 
     // If the aggregate was successfully updated, calculate the difference and update TWAP.
     if updated {
         let agg_diff = (slot as i64) - price_account.prev_slot_ as i64;
 
-        // See comment on unsafe `c_upd_aggregate` call above for details.
+        // See comment on unsafe `upd_aggregate` call above for details.
         unsafe {
             c_upd_twap(price_account as *mut PriceAccount as *mut u8, agg_diff);
         }
@@ -87,6 +107,8 @@ pub enum AggregationError {
     V1AggregationMode,
     #[error("AlreadyAggregated")]
     AlreadyAggregated,
+    #[error("ScheduleMismatch")]
+    ScheduleMismatch,
 }
 
 /// Attempts to read a price account and create a new price aggregate if v2
@@ -94,11 +116,52 @@ pub enum AggregationError {
 /// Returns messages that should be included in the merkle tree, unless v1 aggregation
 /// is still in use.
 /// Note that the `messages` may be returned even if aggregation fails for some reason.
+/// If the feed is paused, the aggregate is held at `PC_STATUS_HALTED` instead of being computed,
+/// and the messages show the last trading price.
+/// The trading hours of scheduled price accounts aren't enforced, use
+/// `aggregate_price_with_schedule` to enforce them.
 pub fn aggregate_price(
     slot: u64,
     timestamp: i64,
     price_account_pubkey: &Pubkey,
     price_account: &mut PriceAccount,
+) -> Result<[Vec<u8>; 2], AggregationError> {
+    aggregate_price_with_schedule(slot, timestamp, price_account_pubkey, price_account, None)
+}
+
+/// Same as `aggregate_price`, with the schedule account of the price account if it has one.
+/// If the price account has the `SCHEDULED` flag, outside of the trading hours of `schedule` the
+/// aggregate is held at `PC_STATUS_UNKNOWN` like for a paused feed. Without `schedule`, the price
+/// account is aggregated like an unscheduled one. If `schedule` isn't the schedule account of the
+/// price account, the price account is left unchanged and `ScheduleMismatch` is returned.
+pub fn aggregate_price_with_schedule(
+    slot: u64,
+    timestamp: i64,
+    price_account_pubkey: &Pubkey,
+    price_account: &mut PriceAccount,
+    schedule: Option<&ScheduleAccount>,
+) -> Result<[Vec<u8>; 2], AggregationError> {
+    aggregate_price_with_weights(
+        slot,
+        timestamp,
+        price_account_pubkey,
+        price_account,
+        schedule,
+        None,
+    )
+}
+
+/// Same as `aggregate_price_with_schedule`, weighting the quotes of the publishers by `weights`,
+/// in the order of the components of the price account, for example with
+/// `capped_stake_weights`. See `aggregation::upd_aggregate_with_weights`. Without weights, the
+/// aggregate is the same as with `aggregate_price_with_schedule`.
+pub fn aggregate_price_with_weights(
+    slot: u64,
+    timestamp: i64,
+    price_account_pubkey: &Pubkey,
+    price_account: &mut PriceAccount,
+    schedule: Option<&ScheduleAccount>,
+    weights: Option<&[u64]>,
 ) -> Result<[Vec<u8>; 2], AggregationError> {
     if !price_account
         .flags
@@ -120,7 +183,22 @@ pub fn aggregate_price(
         // (this should normally happen only in the slot that contains the v1->v2 transition).
         return Err(AggregationError::AlreadyAggregated);
     }
-    update_aggregate(slot, timestamp, price_account);
+    let is_trading = match schedule {
+        Some(schedule) if schedule.price_account != *price_account_pubkey => {
+            return Err(AggregationError::ScheduleMismatch);
+        }
+        Some(schedule) if price_account.flags.contains(PriceAccountFlags::SCHEDULED) => {
+            schedule.is_trading(timestamp)
+        }
+        _ => true,
+    };
+    if price_account.flags.contains(PriceAccountFlags::PAUSED) {
+        price_account.skip_paused_aggregate(slot, timestamp);
+    } else if !is_trading {
+        price_account.skip_aggregate(slot, timestamp, PC_STATUS_UNKNOWN);
+    } else {
+        update_aggregate(slot, timestamp, price_account, weights);
+    }
     Ok([
         price_account
             .as_price_feed_message(price_account_pubkey)
@@ -139,6 +217,14 @@ pub fn checked_load_price_account(price_account_info: &[u8]) -> Option<&PriceAcc
     ))
 }
 
+/// Load a schedule account as read-only, returning `None` if it isn't a valid schedule account.
+pub fn checked_load_schedule_account(schedule_account_info: &[u8]) -> Option<&ScheduleAccount> {
+    check_account_header::<ScheduleAccount>(schedule_account_info).ok()?;
+    Some(bytemuck::from_bytes::<ScheduleAccount>(
+        &schedule_account_info[0..size_of::<ScheduleAccount>()],
+    ))
+}
+
 pub fn checked_load_price_account_mut(
     price_account_info: &mut [u8],
 ) -> Result<&mut PriceAccount, ProgramError> {
@@ -164,9 +250,7 @@ pub fn compute_publisher_stake_caps<'a>(
     let mut publisher_caps: BTreeMap<Pubkey, u64> = BTreeMap::new(); // BTreeMap to ensure it will be sorted by publisher
     for account in account_datas {
         if let Some(price_account) = checked_load_price_account(account) {
-            let cap: u64 = m
-                .checked_div(max(u64::from(price_account.num_), z))
-                .unwrap_or(0);
+            let cap = publisher_stake_cap(price_account, m, z);
             for i in 0..(price_account.num_ as usize) {
                 if let Some(pub_) = price_account.comp_.get(i).map(|comp| &comp.pub_) {
                     publisher_caps
@@ -191,3 +275,31 @@ pub fn compute_publisher_stake_caps<'a>(
     }
     .to_bytes()
 }
+
+/// The cap on the stake of each publisher of `price_account` in `compute_publisher_stake_caps`:
+/// `m / max(num_, z)`.
+fn publisher_stake_cap(price_account: &PriceAccount, m: u64, z: u64) -> u64 {
+    m.checked_div(max(u64::from(price_account.num_), z))
+        .unwrap_or(0)
+}
+
+/// Weights for `aggregate_price_with_weights`: the stake of each publisher of `price_account`,
+/// in the order of its components, capped so that no single publisher dominates the aggregate.
+/// The cap is the share of this price account in the caps of `compute_publisher_stake_caps`,
+/// `m / max(num_, z)`, not the cap of the publisher summed over all the price accounts it
+/// publishes to, which would grow with the number of feeds of the publisher. Publishers missing
+/// from `stakes` have a weight of 0.
+pub fn capped_stake_weights(
+    price_account: &PriceAccount,
+    stakes: &BTreeMap<Pubkey, u64>,
+    m: u64,
+    z: u64,
+) -> Vec<u64> {
+    let cap = publisher_stake_cap(price_account, m, z);
+    price_account
+        .comp_
+        .iter()
+        .take(price_account.num_ as usize)
+        .map(|component| stakes.get(&component.pub_).copied().unwrap_or(0).min(cap))
+        .collect()
+}
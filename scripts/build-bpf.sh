@@ -21,6 +21,13 @@ cd "${PYTH_DIR}"
 
 # Re-run tests affected by features
 cargo-test-bpf
+# The batch and delegation instructions are left out of the deployed program
+cargo-test-bpf --features batch-updates,delegation
+# The Rust aggregation engine skips the C build and bindgen, the simulator tests use the program
+# built above
+cargo test --locked --features rust-aggregation
+# The JSON forms of the accounts are only built with the serde feature
+cargo test --locked --features serde
 
 cargo-build-bpf -- --locked -Z build-std=std,panic_abort -Z build-std-features=panic_immediate_abort
 sha256sum ./target/**/*.so